@@ -12,4 +12,8 @@ fn main() {
 		format!("pub const VERSION: &str = \"{}\";", version),
 	)
 	.unwrap();
+
+	// `db::MIGRATIONS` embeds this directory, so new migrations must rebuild the crate.
+	println!("cargo:rerun-if-changed=migrations");
+	println!("cargo:rerun-if-changed=Cargo.toml");
 }
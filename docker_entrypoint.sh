@@ -7,7 +7,7 @@ COUNTER=0
 echo "Waiting for PostgreSQL to be ready..."
 
 # Check for database readiness
-until diesel migration run --locked-schema; do
+until ./target/release/izumo migrate; do
 	COUNTER=$((COUNTER + 1))
 	if [ "$COUNTER" -ge "$RETRIES" ]; then
 		echo "Exceeded maximum retries, exiting."
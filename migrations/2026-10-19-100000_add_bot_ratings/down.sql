@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS set_updated_at ON bots;
+SELECT diesel_manage_updated_at('bots');
+
+DROP TRIGGER IF EXISTS update_bot_rating ON bot_ratings;
+DROP FUNCTION IF EXISTS update_bot_rating;
+
+ALTER TABLE bots
+    DROP COLUMN rating_count,
+    DROP COLUMN rating_average;
+
+DROP TABLE IF EXISTS bot_ratings;
@@ -0,0 +1,57 @@
+CREATE TABLE bot_ratings
+(
+    id         SERIAL PRIMARY KEY,
+    bot_id     VARCHAR                 NOT NULL REFERENCES bots (id) ON DELETE CASCADE,
+    user_id    VARCHAR                 NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    rating     SMALLINT                NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    body       TEXT                    NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW() NOT NULL,
+    updated_at TIMESTAMP DEFAULT NOW() NOT NULL,
+    UNIQUE (bot_id, user_id)
+);
+
+SELECT diesel_manage_updated_at('bot_ratings');
+
+ALTER TABLE bots
+    ADD COLUMN rating_count   INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN rating_average REAL    NOT NULL DEFAULT 0;
+
+-- Keep the rating aggregates of a bot in sync with its ratings
+CREATE OR REPLACE FUNCTION update_bot_rating()
+    RETURNS TRIGGER AS
+$$
+DECLARE
+    target VARCHAR;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target := OLD.bot_id;
+    ELSE
+        target := NEW.bot_id;
+    END IF;
+
+    UPDATE bots
+    SET (rating_count, rating_average) = (SELECT count(*), coalesce(avg(rating), 0)
+                                          FROM bot_ratings
+                                          WHERE bot_id = target)
+    WHERE id = target;
+
+    RETURN NULL; -- Triggers on AFTER don't return a value
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER update_bot_rating
+    AFTER INSERT OR DELETE OR UPDATE OF rating
+    ON bot_ratings
+    FOR EACH ROW
+EXECUTE FUNCTION update_bot_rating();
+
+-- Refreshing the rating aggregates must not mark the bot itself as updated
+DROP TRIGGER IF EXISTS set_updated_at ON bots;
+
+CREATE TRIGGER set_updated_at
+    BEFORE UPDATE
+    ON bots
+    FOR EACH ROW
+    WHEN (OLD.rating_count IS NOT DISTINCT FROM NEW.rating_count
+        AND OLD.rating_average IS NOT DISTINCT FROM NEW.rating_average)
+EXECUTE PROCEDURE diesel_set_updated_at();
@@ -0,0 +1,40 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS update_bot_rating ON bot_ratings;
+
+CREATE OR REPLACE FUNCTION update_bot_rating()
+    RETURNS TRIGGER AS
+$$
+DECLARE
+    target VARCHAR;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target := OLD.bot_id;
+    ELSE
+        target := NEW.bot_id;
+    END IF;
+
+    UPDATE bots
+    SET (rating_count, rating_average) = (SELECT count(*), coalesce(avg(rating), 0)
+                                          FROM bot_ratings
+                                          WHERE bot_id = target)
+    WHERE id = target;
+
+    RETURN NULL; -- Triggers on AFTER don't return a value
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER update_bot_rating
+    AFTER INSERT OR DELETE OR UPDATE OF rating
+    ON bot_ratings
+    FOR EACH ROW
+EXECUTE FUNCTION update_bot_rating();
+
+DROP TABLE IF EXISTS bot_rating_actions;
+DROP TABLE IF EXISTS bot_rating_flags;
+DROP TABLE IF EXISTS bot_rating_replies;
+
+ALTER TABLE bot_ratings
+    DROP COLUMN hidden;
+
+ALTER TABLE users
+    DROP COLUMN role;
@@ -0,0 +1,69 @@
+-- Staff members are users with a role other than `USER` (0)
+ALTER TABLE users
+    ADD COLUMN role INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE bot_ratings
+    ADD COLUMN hidden BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE bot_rating_replies
+(
+    rating_id  INTEGER PRIMARY KEY REFERENCES bot_ratings (id) ON DELETE CASCADE,
+    user_id    VARCHAR                 NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    body       TEXT                    NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW() NOT NULL,
+    updated_at TIMESTAMP DEFAULT NOW() NOT NULL
+);
+
+SELECT diesel_manage_updated_at('bot_rating_replies');
+
+CREATE TABLE bot_rating_flags
+(
+    rating_id   INTEGER                 NOT NULL REFERENCES bot_ratings (id) ON DELETE CASCADE,
+    user_id     VARCHAR                 NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    reason      TEXT                    NOT NULL,
+    created_at  TIMESTAMP DEFAULT NOW() NOT NULL,
+    resolved_at TIMESTAMP,
+    PRIMARY KEY (rating_id, user_id)
+);
+
+CREATE TABLE bot_rating_actions
+(
+    id         SERIAL PRIMARY KEY,
+    rating_id  INTEGER                 NOT NULL REFERENCES bot_ratings (id) ON DELETE CASCADE,
+    actor_id   VARCHAR                 NOT NULL REFERENCES users (id),
+    action     INTEGER                 NOT NULL,
+    reason     TEXT                    NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW() NOT NULL
+);
+
+-- Hidden ratings don't count towards the rating aggregates of a bot
+CREATE OR REPLACE FUNCTION update_bot_rating()
+    RETURNS TRIGGER AS
+$$
+DECLARE
+    target VARCHAR;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target := OLD.bot_id;
+    ELSE
+        target := NEW.bot_id;
+    END IF;
+
+    UPDATE bots
+    SET (rating_count, rating_average) = (SELECT count(*), coalesce(avg(rating), 0)
+                                          FROM bot_ratings
+                                          WHERE bot_id = target
+                                            AND NOT hidden)
+    WHERE id = target;
+
+    RETURN NULL; -- Triggers on AFTER don't return a value
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS update_bot_rating ON bot_ratings;
+
+CREATE TRIGGER update_bot_rating
+    AFTER INSERT OR DELETE OR UPDATE OF rating, hidden
+    ON bot_ratings
+    FOR EACH ROW
+EXECUTE FUNCTION update_bot_rating();
@@ -0,0 +1,22 @@
+CREATE TABLE bot_reports
+(
+    id         SERIAL PRIMARY KEY,
+    bot_id     VARCHAR                 NOT NULL REFERENCES bots (id) ON DELETE CASCADE,
+    user_id    VARCHAR                 NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    reason     INTEGER                 NOT NULL,
+    body       TEXT                    NOT NULL,
+    status     INTEGER   DEFAULT 0     NOT NULL,
+    handled_by VARCHAR REFERENCES users (id) ON DELETE SET NULL,
+    resolution TEXT,
+    bot_status INTEGER,
+    created_at TIMESTAMP DEFAULT NOW() NOT NULL,
+    updated_at TIMESTAMP DEFAULT NOW() NOT NULL
+);
+
+COMMENT ON COLUMN bot_reports.bot_status IS 'The status the bot was changed to while handling the report, if any.';
+
+CREATE INDEX bot_reports_bot_id_idx ON bot_reports (bot_id);
+CREATE INDEX bot_reports_user_id_created_at_idx ON bot_reports (user_id, created_at);
+CREATE INDEX bot_reports_status_idx ON bot_reports (status);
+
+SELECT diesel_manage_updated_at('bot_reports');
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS bot_resubmissions;
+DROP TABLE IF EXISTS bot_denials;
@@ -0,0 +1,21 @@
+CREATE TABLE bot_denials
+(
+    id          SERIAL PRIMARY KEY,
+    bot_id      VARCHAR                 NOT NULL REFERENCES bots (id) ON DELETE CASCADE,
+    reviewer_id VARCHAR REFERENCES users (id) ON DELETE SET NULL,
+    reason      TEXT                    NOT NULL,
+    created_at  TIMESTAMP DEFAULT NOW() NOT NULL
+);
+
+CREATE INDEX bot_denials_bot_id_idx ON bot_denials (bot_id);
+
+CREATE TABLE bot_resubmissions
+(
+    id         SERIAL PRIMARY KEY,
+    bot_id     VARCHAR                 NOT NULL REFERENCES bots (id) ON DELETE CASCADE,
+    user_id    VARCHAR                 NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    appeal     TEXT,
+    created_at TIMESTAMP DEFAULT NOW() NOT NULL
+);
+
+CREATE INDEX bot_resubmissions_bot_id_idx ON bot_resubmissions (bot_id);
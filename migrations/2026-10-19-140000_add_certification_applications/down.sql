@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS revoke_denied_certification ON bots;
+DROP FUNCTION IF EXISTS revoke_denied_certification();
+
+DROP TABLE IF EXISTS certification_applications;
@@ -0,0 +1,36 @@
+CREATE TABLE certification_applications
+(
+    id          SERIAL PRIMARY KEY,
+    bot_id      VARCHAR                 NOT NULL REFERENCES bots (id) ON DELETE CASCADE,
+    user_id     VARCHAR                 NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    message     TEXT,
+    status      INTEGER   DEFAULT 0     NOT NULL,
+    reviewer_id VARCHAR REFERENCES users (id) ON DELETE SET NULL,
+    reason      TEXT,
+    created_at  TIMESTAMP DEFAULT NOW() NOT NULL,
+    updated_at  TIMESTAMP DEFAULT NOW() NOT NULL
+);
+
+-- Only one application per bot can wait for review at a time
+CREATE UNIQUE INDEX certification_applications_pending_idx
+    ON certification_applications (bot_id)
+    WHERE status = 0;
+
+SELECT diesel_manage_updated_at('certification_applications');
+
+-- Denied bots lose their certification
+CREATE OR REPLACE FUNCTION revoke_denied_certification()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    NEW.certified := FALSE;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER revoke_denied_certification
+    BEFORE UPDATE OF status
+    ON bots
+    FOR EACH ROW
+    WHEN (NEW.status = 1 AND NEW.certified)
+EXECUTE FUNCTION revoke_denied_certification();
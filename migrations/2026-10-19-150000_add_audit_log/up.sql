@@ -0,0 +1,43 @@
+-- Append-only record of staff and owner actions. The table has no foreign
+-- keys on purpose: entries have to outlive the users, tokens and bots they
+-- refer to.
+CREATE TABLE audit_log
+(
+    id         BIGSERIAL PRIMARY KEY,
+    actor_id   VARCHAR                 NOT NULL,
+    token_id   INTEGER,
+    action     INTEGER                 NOT NULL,
+    bot_id     VARCHAR,
+    target     VARCHAR                 NOT NULL,
+    before     JSONB,
+    after      JSONB,
+    ip         INET,
+    created_at TIMESTAMP DEFAULT NOW() NOT NULL
+);
+
+COMMENT ON COLUMN audit_log.token_id IS 'ID of the API token used for the action, NULL if a cookie session was used.';
+COMMENT ON COLUMN audit_log.target IS 'What the action was performed on, e.g. `bot:1234` or `token:42`.';
+
+CREATE INDEX audit_log_actor_id_idx ON audit_log (actor_id);
+CREATE INDEX audit_log_bot_id_idx ON audit_log (bot_id);
+CREATE INDEX audit_log_action_idx ON audit_log (action);
+
+CREATE OR REPLACE FUNCTION reject_audit_log_changes()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reject_audit_log_changes
+    BEFORE UPDATE OR DELETE
+    ON audit_log
+    FOR EACH ROW
+EXECUTE FUNCTION reject_audit_log_changes();
+
+CREATE TRIGGER reject_audit_log_truncate
+    BEFORE TRUNCATE
+    ON audit_log
+    FOR EACH STATEMENT
+EXECUTE FUNCTION reject_audit_log_changes();
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS categories_slug_idx;
+
+ALTER TABLE categories
+    ALTER COLUMN category TYPE VARCHAR(25),
+    ALTER COLUMN slug TYPE VARCHAR(25);
@@ -0,0 +1,8 @@
+-- Subcategories repeat the names and slugs of their parents, e.g.
+-- `moderation::auto-moderation`, so 25 characters are not enough.
+ALTER TABLE categories
+    ALTER COLUMN category TYPE VARCHAR(100),
+    ALTER COLUMN slug TYPE VARCHAR(100);
+
+-- Required by `NewCategory::upsert`
+CREATE UNIQUE INDEX categories_slug_idx ON categories (slug);
@@ -0,0 +1,34 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS update_bots_count_on_status ON bots;
+DROP FUNCTION IF EXISTS update_bots_count_on_status;
+
+CREATE OR REPLACE FUNCTION update_bots_count()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    -- Increment bots_cnt on insert into bots_categories
+    IF TG_OP = 'INSERT' THEN
+        UPDATE categories
+        SET bots_cnt = bots_cnt + 1
+        WHERE id = NEW.category_id;
+        -- Decrement bots_cnt on delete from bots_categories
+    ELSIF TG_OP = 'DELETE' THEN
+        UPDATE categories
+        SET bots_cnt = bots_cnt - 1
+        WHERE id = OLD.category_id;
+    END IF;
+
+    RETURN NULL; -- Triggers on AFTER don't return a value
+END;
+$$ LANGUAGE plpgsql;
+
+DROP FUNCTION IF EXISTS recalculate_bots_cnt;
+
+UPDATE categories
+SET bots_cnt = (
+    SELECT COUNT(*)
+    FROM bots_categories
+    WHERE bots_categories.category_id = categories.id
+);
+
+DROP INDEX IF EXISTS bots_categories_category_id_idx;
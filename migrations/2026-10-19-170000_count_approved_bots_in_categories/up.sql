@@ -0,0 +1,55 @@
+CREATE INDEX bots_categories_category_id_idx ON bots_categories (category_id);
+
+-- Count only the approved bots of a category. Recounting instead of
+-- incrementing keeps the counter correct for every kind of change, including
+-- rows removed together with their bot.
+CREATE OR REPLACE FUNCTION recalculate_bots_cnt(INTEGER)
+    RETURNS VOID AS
+$$
+    UPDATE categories
+    SET bots_cnt = (
+        SELECT COUNT(*)
+        FROM bots_categories
+        INNER JOIN bots ON bots.id = bots_categories.bot_id
+        WHERE bots_categories.category_id = categories.id
+          AND bots.status = 2
+    )
+    WHERE id = $1;
+$$ LANGUAGE sql;
+
+CREATE OR REPLACE FUNCTION update_bots_count()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM recalculate_bots_cnt(NEW.category_id);
+    ELSIF TG_OP = 'DELETE' THEN
+        PERFORM recalculate_bots_cnt(OLD.category_id);
+    END IF;
+
+    RETURN NULL; -- Triggers on AFTER don't return a value
+END;
+$$ LANGUAGE plpgsql;
+
+-- Recount the categories of a bot when it gets approved or loses its approval
+CREATE OR REPLACE FUNCTION update_bots_count_on_status()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    PERFORM recalculate_bots_cnt(category_id)
+    FROM bots_categories
+    WHERE bot_id = NEW.id;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER update_bots_count_on_status
+    AFTER UPDATE OF status
+    ON bots
+    FOR EACH ROW
+    WHEN (OLD.status IS DISTINCT FROM NEW.status)
+EXECUTE FUNCTION update_bots_count_on_status();
+
+-- Fix the counters that included pending and denied bots
+SELECT recalculate_bots_cnt(id) FROM categories;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE users
+    DROP COLUMN banned_at,
+    DROP COLUMN ban_reason;
@@ -0,0 +1,5 @@
+ALTER TABLE users
+    ADD COLUMN banned_at  TIMESTAMP,
+    ADD COLUMN ban_reason TEXT;
+
+COMMENT ON COLUMN users.banned_at IS 'When the user was banned, NULL if the user is not banned. Banned users cannot authenticate.';
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE rate_limit_overrides;
+DROP TABLE rate_limit_buckets;
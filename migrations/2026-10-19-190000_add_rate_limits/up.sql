@@ -0,0 +1,21 @@
+-- Token buckets of the rate limiter, one per action and key.
+CREATE TABLE rate_limit_buckets
+(
+    action      INTEGER                 NOT NULL,
+    key         VARCHAR                 NOT NULL,
+    tokens      INTEGER                 NOT NULL,
+    last_refill TIMESTAMP DEFAULT NOW() NOT NULL,
+    PRIMARY KEY (action, key)
+);
+
+COMMENT ON COLUMN rate_limit_buckets.key IS 'What the bucket is keyed by, e.g. `user:1234` or `ip:127.0.0.1`.';
+
+-- Larger bursts granted to trusted users, e.g. partners publishing many bots.
+CREATE TABLE rate_limit_overrides
+(
+    action     INTEGER NOT NULL,
+    user_id    VARCHAR NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    burst      INTEGER NOT NULL,
+    expires_at TIMESTAMP,
+    PRIMARY KEY (action, user_id)
+);
@@ -0,0 +1,18 @@
+-- Traffic blocked by the `block_traffic` middleware, in addition to the
+-- `BLOCKED_*` environment variables. Each row blocks either an IP range or
+-- a user agent.
+CREATE TABLE blocked_traffic
+(
+    id         SERIAL PRIMARY KEY,
+    ip_range   INET,
+    user_agent VARCHAR,
+    reason     TEXT                    NOT NULL,
+    created_by VARCHAR                 REFERENCES users (id) ON DELETE SET NULL,
+    created_at TIMESTAMP DEFAULT NOW() NOT NULL,
+    expires_at TIMESTAMP,
+    CONSTRAINT blocked_traffic_target_check CHECK ((ip_range IS NULL) <> (user_agent IS NULL)),
+    CONSTRAINT blocked_traffic_user_agent_check CHECK (user_agent <> '')
+);
+
+COMMENT ON COLUMN blocked_traffic.ip_range IS 'A single address or a CIDR block, e.g. `192.0.2.0/24`.';
+COMMENT ON COLUMN blocked_traffic.user_agent IS 'Requests whose user agent contains this string are blocked.';
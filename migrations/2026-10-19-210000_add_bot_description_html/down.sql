@@ -0,0 +1,14 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS set_updated_at ON bots;
+
+CREATE TRIGGER set_updated_at
+    BEFORE UPDATE
+    ON bots
+    FOR EACH ROW
+    WHEN (OLD.rating_count IS NOT DISTINCT FROM NEW.rating_count
+        AND OLD.rating_average IS NOT DISTINCT FROM NEW.rating_average)
+EXECUTE PROCEDURE diesel_set_updated_at();
+
+ALTER TABLE bots
+    DROP COLUMN description_html,
+    DROP COLUMN description_html_version;
@@ -0,0 +1,20 @@
+-- The description of the bots rendered to sanitized HTML, see `src/markdown.rs`.
+ALTER TABLE bots
+    ADD COLUMN description_html         TEXT    DEFAULT '' NOT NULL,
+    ADD COLUMN description_html_version INTEGER DEFAULT 0  NOT NULL;
+
+COMMENT ON COLUMN bots.description_html_version IS 'The sanitizer policy `description_html` was rendered with, `0` if it was never rendered.';
+
+-- Rendering the descriptions again after a policy change must not mark the bots
+-- as updated either
+DROP TRIGGER IF EXISTS set_updated_at ON bots;
+
+CREATE TRIGGER set_updated_at
+    BEFORE UPDATE
+    ON bots
+    FOR EACH ROW
+    WHEN (OLD.rating_count IS NOT DISTINCT FROM NEW.rating_count
+        AND OLD.rating_average IS NOT DISTINCT FROM NEW.rating_average
+        AND (OLD.description_html_version IS NOT DISTINCT FROM NEW.description_html_version
+            OR OLD.description IS DISTINCT FROM NEW.description))
+EXECUTE PROCEDURE diesel_set_updated_at();
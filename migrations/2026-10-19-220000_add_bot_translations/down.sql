@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX bots_search_idx;
+DROP TABLE bot_translations;
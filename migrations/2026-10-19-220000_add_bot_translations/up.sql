@@ -0,0 +1,24 @@
+-- Translations of the descriptions of a bot, one per language.
+CREATE TABLE bot_translations
+(
+    bot_id                   VARCHAR                 NOT NULL REFERENCES bots (id) ON DELETE CASCADE,
+    language                 VARCHAR                 NOT NULL,
+    short_description        TEXT                    NOT NULL,
+    description              TEXT                    NOT NULL,
+    description_html         TEXT                    NOT NULL,
+    description_html_version INTEGER                 NOT NULL,
+    created_at               TIMESTAMP DEFAULT NOW() NOT NULL,
+    updated_at               TIMESTAMP DEFAULT NOW() NOT NULL,
+    PRIMARY KEY (bot_id, language)
+);
+
+COMMENT ON COLUMN bot_translations.language IS 'The lowercase language code, e.g. `es`.';
+
+SELECT diesel_manage_updated_at('bot_translations');
+
+-- Full-text search of the bots, in every language they are described in
+CREATE INDEX bots_search_idx ON bots
+    USING GIN (to_tsvector('simple', name || ' ' || short_description || ' ' || description));
+
+CREATE INDEX bot_translations_search_idx ON bot_translations
+    USING GIN (to_tsvector('simple', short_description || ' ' || description));
@@ -0,0 +1,23 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE bots
+    ADD COLUMN supported_language_ids INTEGER[] DEFAULT '{}' NOT NULL;
+
+UPDATE bots
+SET supported_language_ids = ARRAY(
+    SELECT CASE language
+               WHEN 'en' THEN 0
+               WHEN 'es' THEN 1
+               WHEN 'de' THEN 2
+               WHEN 'ja' THEN 3
+               WHEN 'zh' THEN 4
+               END
+    FROM unnest(bots.supported_languages) WITH ORDINALITY AS languages (language, position)
+    WHERE language IN ('en', 'es', 'de', 'ja', 'zh')
+    ORDER BY position
+);
+
+ALTER TABLE bots
+    DROP COLUMN supported_languages;
+
+ALTER TABLE bots
+    RENAME COLUMN supported_language_ids TO supported_languages;
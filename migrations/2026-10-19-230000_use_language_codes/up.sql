@@ -0,0 +1,32 @@
+-- The supported languages of the bots become ISO 639-1 codes, see
+-- `src/models/language.rs`, instead of the integers of the old `BotLanguages`
+-- enum.
+ALTER TABLE bots
+    ADD COLUMN supported_language_codes TEXT[] DEFAULT '{}' NOT NULL;
+
+UPDATE bots
+SET supported_language_codes = ARRAY(
+    SELECT CASE language
+               WHEN 0 THEN 'en'
+               WHEN 1 THEN 'es'
+               WHEN 2 THEN 'de'
+               WHEN 3 THEN 'ja'
+               WHEN 4 THEN 'zh'
+               END
+    FROM unnest(bots.supported_languages) WITH ORDINALITY AS languages (language, position)
+    WHERE language BETWEEN 0 AND 4
+    ORDER BY position
+);
+
+ALTER TABLE bots
+    DROP COLUMN supported_languages;
+
+ALTER TABLE bots
+    RENAME COLUMN supported_language_codes TO supported_languages;
+
+ALTER TABLE bots
+    ADD CONSTRAINT bots_supported_languages_not_null
+        CHECK (array_position(supported_languages, NULL) IS NULL);
+
+-- Filtering the bots by language
+CREATE INDEX bots_supported_languages_idx ON bots USING GIN (supported_languages);
@@ -0,0 +1,27 @@
+-- Images uploaded to the storage: the banners of the bots and the users, and
+-- the screenshots of the bots.
+CREATE TABLE images
+(
+    id         SERIAL PRIMARY KEY,
+    kind       INTEGER                 NOT NULL,
+    bot_id     VARCHAR REFERENCES bots (id) ON DELETE CASCADE,
+    user_id    VARCHAR REFERENCES users (id) ON DELETE CASCADE,
+    key        TEXT                    NOT NULL UNIQUE,
+    extension  VARCHAR                 NOT NULL,
+    width      INTEGER                 NOT NULL,
+    height     INTEGER                 NOT NULL,
+    variants   INTEGER[]               NOT NULL CHECK (array_position(variants, NULL) IS NULL),
+    position   INTEGER DEFAULT 0       NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW() NOT NULL,
+    CHECK ((bot_id IS NULL) <> (user_id IS NULL))
+);
+
+COMMENT ON COLUMN images.key IS 'The prefix of the objects in the storage, e.g. `bots/1234/banners/abcd`.';
+COMMENT ON COLUMN images.variants IS 'The widths of the stored variants from the largest, each one stored as `{key}/{width}.{extension}`.';
+
+CREATE INDEX images_bot_id_idx ON images (bot_id, kind, position);
+CREATE INDEX images_user_id_idx ON images (user_id, kind);
+
+-- A bot or a user has at most one banner
+CREATE UNIQUE INDEX images_bot_banner_idx ON images (bot_id) WHERE kind = 0;
+CREATE UNIQUE INDEX images_user_banner_idx ON images (user_id) WHERE kind = 2;
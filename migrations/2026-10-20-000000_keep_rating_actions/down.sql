@@ -0,0 +1,13 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM bot_rating_actions
+WHERE rating_id IS NULL;
+
+DROP INDEX IF EXISTS bot_rating_actions_bot_id_user_id_idx;
+
+ALTER TABLE bot_rating_actions
+    DROP CONSTRAINT bot_rating_actions_rating_id_fkey,
+    ADD CONSTRAINT bot_rating_actions_rating_id_fkey
+        FOREIGN KEY (rating_id) REFERENCES bot_ratings (id) ON DELETE CASCADE,
+    ALTER COLUMN rating_id SET NOT NULL,
+    DROP COLUMN bot_id,
+    DROP COLUMN user_id;
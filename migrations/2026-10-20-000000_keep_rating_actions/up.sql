@@ -0,0 +1,22 @@
+-- Keep the moderation history of the ratings deleted by their author, along
+-- with who the rating was from, so that it can be carried over if they rate
+-- the bot again.
+ALTER TABLE bot_rating_actions
+    ADD COLUMN bot_id  VARCHAR,
+    ADD COLUMN user_id VARCHAR;
+
+UPDATE bot_rating_actions
+SET bot_id  = bot_ratings.bot_id,
+    user_id = bot_ratings.user_id
+FROM bot_ratings
+WHERE bot_ratings.id = bot_rating_actions.rating_id;
+
+ALTER TABLE bot_rating_actions
+    ALTER COLUMN bot_id SET NOT NULL,
+    ALTER COLUMN user_id SET NOT NULL,
+    ALTER COLUMN rating_id DROP NOT NULL,
+    DROP CONSTRAINT bot_rating_actions_rating_id_fkey,
+    ADD CONSTRAINT bot_rating_actions_rating_id_fkey
+        FOREIGN KEY (rating_id) REFERENCES bot_ratings (id) ON DELETE SET NULL;
+
+CREATE INDEX bot_rating_actions_bot_id_user_id_idx ON bot_rating_actions (bot_id, user_id);
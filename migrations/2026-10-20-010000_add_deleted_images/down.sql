@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS queue_deleted_images ON users;
+DROP TRIGGER IF EXISTS queue_deleted_images ON bots;
+DROP FUNCTION IF EXISTS queue_deleted_images;
+
+DROP TABLE IF EXISTS deleted_images;
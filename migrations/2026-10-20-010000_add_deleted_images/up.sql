@@ -0,0 +1,39 @@
+-- The images of the deleted bots and users, whose stored files are yet to be
+-- deleted by the server.
+CREATE TABLE deleted_images
+(
+    id         SERIAL PRIMARY KEY,
+    key        TEXT                    NOT NULL,
+    extension  VARCHAR                 NOT NULL,
+    variants   INTEGER[]               NOT NULL CHECK (array_position(variants, NULL) IS NULL),
+    deleted_at TIMESTAMP DEFAULT NOW() NOT NULL
+);
+
+-- Queue the images of a bot or a user before they are deleted along with it
+CREATE OR REPLACE FUNCTION queue_deleted_images()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    INSERT INTO deleted_images (key, extension, variants)
+    SELECT key, extension, variants
+    FROM images
+    WHERE CASE TG_TABLE_NAME
+              WHEN 'bots' THEN bot_id = OLD.id
+              ELSE user_id = OLD.id
+              END;
+
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER queue_deleted_images
+    BEFORE DELETE
+    ON bots
+    FOR EACH ROW
+EXECUTE FUNCTION queue_deleted_images();
+
+CREATE TRIGGER queue_deleted_images
+    BEFORE DELETE
+    ON users
+    FOR EACH ROW
+EXECUTE FUNCTION queue_deleted_images();
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER IF EXISTS revoke_denied_certification ON bots;
+
+CREATE TRIGGER revoke_denied_certification
+    BEFORE UPDATE OF status
+    ON bots
+    FOR EACH ROW
+    WHEN (NEW.status = 1 AND NEW.certified)
+EXECUTE FUNCTION revoke_denied_certification();
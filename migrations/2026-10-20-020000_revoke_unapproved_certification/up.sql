@@ -0,0 +1,10 @@
+-- Only approved bots can be certified, whichever of the status or the
+-- certification changes
+DROP TRIGGER IF EXISTS revoke_denied_certification ON bots;
+
+CREATE TRIGGER revoke_denied_certification
+    BEFORE UPDATE OF status, certified
+    ON bots
+    FOR EACH ROW
+    WHEN (NEW.status <> 2 AND NEW.certified)
+EXECUTE FUNCTION revoke_denied_certification();
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM bot_rating_actions
+WHERE actor_id IS NULL;
+
+ALTER TABLE bot_rating_actions
+    ALTER COLUMN actor_id SET NOT NULL,
+    DROP CONSTRAINT bot_rating_actions_actor_id_fkey,
+    ADD CONSTRAINT bot_rating_actions_actor_id_fkey
+        FOREIGN KEY (actor_id) REFERENCES users (id);
@@ -0,0 +1,6 @@
+-- The actions taken by a deleted staff member are kept, without their actor
+ALTER TABLE bot_rating_actions
+    ALTER COLUMN actor_id DROP NOT NULL,
+    DROP CONSTRAINT bot_rating_actions_actor_id_fkey,
+    ADD CONSTRAINT bot_rating_actions_actor_id_fkey
+        FOREIGN KEY (actor_id) REFERENCES users (id) ON DELETE SET NULL;
@@ -0,0 +1,13 @@
+-- The approved bots of a category and of its subcategories at any depth, each
+-- bot counted once, like the bot listing of the category. `bots_cnt` only
+-- counts the direct members of a category.
+CREATE OR REPLACE FUNCTION subtree_bots_cnt(VARCHAR)
+    RETURNS INTEGER AS
+$$
+    SELECT COUNT(DISTINCT bots_categories.bot_id)::int
+    FROM bots_categories
+    INNER JOIN categories ON categories.id = bots_categories.category_id
+    INNER JOIN bots ON bots.id = bots_categories.bot_id
+    WHERE bots.status = 2
+      AND (categories.slug = $1 OR starts_with(categories.slug, $1 || '::'))
+$$ LANGUAGE sql STABLE;
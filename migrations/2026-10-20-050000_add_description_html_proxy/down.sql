@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE bot_translations
+    DROP COLUMN description_html_proxy;
+
+ALTER TABLE bots
+    DROP COLUMN description_html_proxy;
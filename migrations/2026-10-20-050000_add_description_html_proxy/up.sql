@@ -0,0 +1,8 @@
+-- The settings of the image proxy the descriptions were rendered with, so
+-- that they are rendered again once the settings change. Empty without a
+-- proxy.
+ALTER TABLE bots
+    ADD COLUMN description_html_proxy TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE bot_translations
+    ADD COLUMN description_html_proxy TEXT NOT NULL DEFAULT '';
@@ -1,4 +1,9 @@
+use crate::blocklist::Blocklist;
 use crate::db::{connection_url, make_manager_config, ConnectionConfig};
+use crate::image_proxy::ImageProxy;
+use crate::metrics::InstanceMetrics;
+use crate::rate_limiter::RateLimiter;
+use crate::storage::Storage;
 use axum::extract::{FromRef, FromRequestParts, State};
 use deadpool_diesel::Runtime;
 use diesel_async::pooled_connection::deadpool::Pool as DeadpoolPool;
@@ -26,6 +31,21 @@ pub struct App {
 
 	pub http: ReqwestClient,
 	pub config: Arc<server::Server>,
+
+	/// Metrics related to this specific instance of the service
+	pub instance_metrics: InstanceMetrics,
+
+	/// Rate limit select actions
+	pub rate_limiter: RateLimiter,
+
+	/// Traffic rejected by the `block_traffic` middleware
+	pub blocklist: Blocklist,
+
+	/// Storage of the uploaded images
+	pub storage: Storage,
+
+	/// Caching proxy of external images, if enabled
+	pub image_proxy: Option<ImageProxy>,
 }
 
 impl App {
@@ -78,11 +98,25 @@ impl App {
 
 		let http = ReqwestClient::new();
 
+		let instance_metrics =
+			InstanceMetrics::new().expect("could not initialize instance metrics");
+
+		let storage = Storage::from_config(&config.storage).expect("could not initialize storage");
+
+		let image_proxy = config.image_proxy.as_ref().map(|config| {
+			ImageProxy::from_config(config).expect("could not initialize image proxy")
+		});
+
 		App {
 			primary_database,
 			replica_database,
 			http,
+			rate_limiter: RateLimiter::new(config.rate_limiter.clone()),
+			blocklist: Blocklist::new(&config),
+			storage,
+			image_proxy,
 			config: Arc::new(config),
+			instance_metrics,
 		}
 	}
 
@@ -94,7 +128,7 @@ impl App {
 	/// Obtain a read/write database connection from the async primary pool
 	#[instrument(skip_all)]
 	pub async fn db_write(&self) -> DeadpoolResult {
-		self.primary_database.get().await
+		self.get_connection("primary", &self.primary_database).await
 	}
 
 	/// Obtain a readonly database connection from the replica pool
@@ -104,23 +138,22 @@ impl App {
 	pub async fn db_read(&self) -> DeadpoolResult {
 		let Some(read_only_pool) = self.replica_database.as_ref() else {
 			// Replica is disabled, but primary might be available
-			return self.primary_database.get().await;
+			return self.get_connection("primary", &self.primary_database).await;
 		};
 
-		match read_only_pool.get().await {
+		match self.get_connection("replica", read_only_pool).await {
 			// Replica is available
 			Ok(connection) => Ok(connection),
 
 			// Replica is not available, but primary might be available
 			Err(error) => {
-				// let _ = self
-				// 	.instance_metrics
-				// 	.database_fallback_used
-				// 	.get_metric_with_label_values(&["follower"])
-				// 	.map(|metric| metric.inc());
+				self.instance_metrics
+					.database_fallback_used
+					.with_label_values(&["replica"])
+					.inc();
 
 				warn!("Replica is unavailable, falling back to primary ({error})");
-				self.primary_database.get().await
+				self.get_connection("primary", &self.primary_database).await
 			}
 		}
 	}
@@ -131,26 +164,43 @@ impl App {
 	#[instrument(skip_all)]
 	pub async fn db_read_prefer_primary(&self) -> DeadpoolResult {
 		let Some(read_only_pool) = self.replica_database.as_ref() else {
-			return self.primary_database.get().await;
+			return self.get_connection("primary", &self.primary_database).await;
 		};
 
-		match self.primary_database.get().await {
+		match self.get_connection("primary", &self.primary_database).await {
 			// Primary is available
 			Ok(connection) => Ok(connection),
 
 			// Primary is not available, but replica might be available
 			Err(error) => {
-				// let _ = self
-				//     .instance_metrics
-				//     .database_fallback_used
-				//     .get_metric_with_label_values(&["primary"])
-				//     .map(|metric| metric.inc());
+				self.instance_metrics
+					.database_fallback_used
+					.with_label_values(&["primary"])
+					.inc();
 
 				warn!("Primary is unavailable, falling back to replica ({error})");
-				read_only_pool.get().await
+				self.get_connection("replica", read_only_pool).await
 			}
 		}
 	}
+
+	/// Obtain a connection from the pool, recording how long it took
+	async fn get_connection(
+		&self,
+		name: &str,
+		pool: &DeadpoolPool<AsyncPgConnection>,
+	) -> DeadpoolResult {
+		let timer = self
+			.instance_metrics
+			.database_time_to_obtain_connection
+			.with_label_values(&[name])
+			.start_timer();
+
+		let result = pool.get().await;
+		timer.observe_duration();
+
+		result
+	}
 }
 
 #[derive(Clone, FromRequestParts)]
@@ -4,12 +4,11 @@ use crate::middleware::log_request::RequestLogExt;
 use crate::middleware::session::RequestSession;
 use crate::models::token::ApiToken;
 use crate::models::User;
-use crate::util::errors::{internal, AppResult, InsecurelyGeneratedTokenRevoked};
-use crate::util::token::HashedToken;
-use crate::{
-	models::{token::EndpointScope, util::diesel::Conn},
-	util::errors::forbidden,
+use crate::models::{token::EndpointScope, util::diesel::Conn};
+use crate::util::errors::{
+	api_error, internal, AppResult, ErrorCode, InsecurelyGeneratedTokenRevoked,
 };
+use crate::util::token::HashedToken;
 use reqwest::header;
 use tracing::instrument;
 
@@ -18,6 +17,7 @@ pub struct AuthCheck {
 	allow_token: bool,
 	endpoint_scope: Option<EndpointScope>,
 	bot_id: Option<String>,
+	require_staff: bool,
 }
 
 impl AuthCheck {
@@ -29,6 +29,7 @@ impl AuthCheck {
 			allow_token: true,
 			endpoint_scope: None,
 			bot_id: None,
+			require_staff: false,
 		}
 	}
 
@@ -38,6 +39,7 @@ impl AuthCheck {
 			allow_token: false,
 			endpoint_scope: None,
 			bot_id: None,
+			require_staff: false,
 		}
 	}
 
@@ -46,6 +48,7 @@ impl AuthCheck {
 			allow_token: self.allow_token,
 			endpoint_scope: Some(endpoint_scope),
 			bot_id: self.bot_id.clone(),
+			require_staff: self.require_staff,
 		}
 	}
 
@@ -54,6 +57,17 @@ impl AuthCheck {
 			allow_token: self.allow_token,
 			endpoint_scope: self.endpoint_scope,
 			bot_id: Some(bot_id.to_string()),
+			require_staff: self.require_staff,
+		}
+	}
+
+	/// Only allow users that are part of the staff team.
+	pub fn require_staff(&self) -> Self {
+		Self {
+			allow_token: self.allow_token,
+			endpoint_scope: self.endpoint_scope,
+			bot_id: self.bot_id.clone(),
+			require_staff: true,
 		}
 	}
 
@@ -65,13 +79,24 @@ impl AuthCheck {
 	) -> AppResult<Authentication> {
 		let auth = authenticate(request, conn)?;
 
+		if auth.user().is_banned() {
+			let error_message = "User is banned";
+			request.request_log().add("cause", error_message);
+
+			return Err(api_error(
+				ErrorCode::AccountBanned,
+				"this account has been banned",
+			));
+		}
+
 		if let Some(token) = auth.api_token() {
 			if !self.allow_token {
 				let error_message =
 					"API Token authentication was explicitly disallowed for this API";
 				request.request_log().add("cause", error_message);
 
-				return Err(forbidden(
+				return Err(api_error(
+					ErrorCode::WebsiteOnly,
 					"this action can only be performed on the dbots.fun website",
 				));
 			}
@@ -80,12 +105,23 @@ impl AuthCheck {
 				let error_message = "Endpoint scope mismatch";
 				request.request_log().add("cause", error_message);
 
-				return Err(forbidden(
+				return Err(api_error(
+					ErrorCode::ScopeMismatch,
 					"this token does not have the required permissions to perform this action",
 				));
 			}
 		}
 
+		if self.require_staff && !auth.user().is_staff() {
+			let error_message = "User is not a staff member";
+			request.request_log().add("cause", error_message);
+
+			return Err(api_error(
+				ErrorCode::StaffOnly,
+				"this action requires staff permissions",
+			));
+		}
+
 		Ok(auth)
 	}
 
@@ -189,7 +225,7 @@ fn authenticate_via_token<T: RequestPartsExt>(
 		let cause = format!("invalid token caused by {e}");
 		req.request_log().add("cause", cause);
 
-		forbidden("authentication failed")
+		api_error(ErrorCode::AuthenticationFailed, "authentication failed")
 	})?;
 
 	let user_id = token.user_id.clone();
@@ -225,5 +261,8 @@ fn authenticate<T: RequestPartsExt>(req: &T, conn: &mut impl Conn) -> AppResult<
 	let cause = "no cookie session or auth header found";
 	req.request_log().add("cause", cause);
 
-	return Err(forbidden("this action requires authentication"));
+	return Err(api_error(
+		ErrorCode::AuthenticationRequired,
+		"this action requires authentication",
+	));
 }
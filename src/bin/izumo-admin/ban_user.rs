@@ -0,0 +1,87 @@
+use crate::Context;
+use anyhow::{bail, Context as _};
+use diesel::prelude::*;
+use izumo::models::audit::{AuditAction, AuditEntry, NewAuditEntry};
+use izumo::models::User;
+use izumo::schema::users;
+use serde_json::json;
+
+#[derive(clap::Parser, Debug)]
+pub struct BanOpts {
+	/// ID of the user
+	user_id: String,
+
+	/// Why the user was banned
+	#[arg(long)]
+	reason: String,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct UnbanOpts {
+	/// ID of the user
+	user_id: String,
+}
+
+fn find_user(ctx: &mut Context, user_id: &str) -> anyhow::Result<User> {
+	User::find(&mut ctx.conn, user_id)
+		.optional()?
+		.with_context(|| format!("user `{user_id}` not found"))
+}
+
+pub fn ban(ctx: &mut Context, opts: BanOpts) -> anyhow::Result<Vec<AuditEntry>> {
+	let reason = opts.reason.trim();
+	if reason.is_empty() {
+		bail!("a reason is required to ban a user");
+	}
+
+	let actor = ctx.actor()?;
+	let user = find_user(ctx, &opts.user_id)?;
+
+	if user.is_banned() {
+		bail!("user `{}` is already banned", user.id);
+	}
+
+	ctx.conn.transaction(|conn| {
+		diesel::update(&user)
+			.set((
+				users::banned_at.eq(diesel::dsl::now),
+				users::ban_reason.eq(reason),
+			))
+			.execute(conn)?;
+
+		let entry =
+			NewAuditEntry::for_actor(&actor.id, AuditAction::UserBan, format!("user:{}", user.id))
+				.after(json!({ "reason": reason }))
+				.insert(conn)?;
+
+		Ok(vec![entry])
+	})
+}
+
+pub fn unban(ctx: &mut Context, opts: UnbanOpts) -> anyhow::Result<Vec<AuditEntry>> {
+	let actor = ctx.actor()?;
+	let user = find_user(ctx, &opts.user_id)?;
+
+	if !user.is_banned() {
+		bail!("user `{}` is not banned", user.id);
+	}
+
+	ctx.conn.transaction(|conn| {
+		diesel::update(&user)
+			.set((
+				users::banned_at.eq(None::<chrono::NaiveDateTime>),
+				users::ban_reason.eq(None::<String>),
+			))
+			.execute(conn)?;
+
+		let entry = NewAuditEntry::for_actor(
+			&actor.id,
+			AuditAction::UserUnban,
+			format!("user:{}", user.id),
+		)
+		.before(json!({ "reason": user.ban_reason }))
+		.insert(conn)?;
+
+		Ok(vec![entry])
+	})
+}
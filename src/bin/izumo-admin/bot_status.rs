@@ -0,0 +1,89 @@
+use crate::{lock_bot, Context};
+use anyhow::bail;
+use diesel::prelude::*;
+use izumo::models::audit::{AuditAction, AuditEntry, NewAuditEntry};
+use izumo::models::bot::BotStatus;
+use izumo::models::BotDenial;
+use izumo::schema::bots;
+use serde_json::json;
+
+#[derive(clap::Parser, Debug)]
+pub struct ApproveOpts {
+	/// ID of the bot
+	bot_id: String,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct DenyOpts {
+	/// ID of the bot
+	bot_id: String,
+
+	/// Why the bot was denied, shown to its owners
+	#[arg(long)]
+	reason: String,
+}
+
+pub fn approve(ctx: &mut Context, opts: ApproveOpts) -> anyhow::Result<Vec<AuditEntry>> {
+	let actor = ctx.actor()?;
+
+	ctx.conn.transaction(|conn| {
+		let bot = lock_bot(conn, &opts.bot_id)?;
+
+		if bot.status == BotStatus::APPROVED {
+			bail!("bot `{}` is already approved", bot.id);
+		}
+
+		diesel::update(&bot)
+			.set(bots::status.eq(BotStatus::APPROVED))
+			.execute(conn)?;
+
+		let entry = NewAuditEntry::for_actor(
+			&actor.id,
+			AuditAction::BotStatusChange,
+			format!("bot:{}", bot.id),
+		)
+		.bot(&bot.id)
+		.before(json!({ "status": String::from(bot.status) }))
+		.after(json!({ "status": String::from(BotStatus::APPROVED), "reason": null }))
+		.insert(conn)?;
+
+		Ok(vec![entry])
+	})
+}
+
+pub fn deny(ctx: &mut Context, opts: DenyOpts) -> anyhow::Result<Vec<AuditEntry>> {
+	let reason = opts.reason.trim();
+	if reason.is_empty() {
+		bail!("a reason is required to deny a bot");
+	}
+	if reason.chars().count() > BotDenial::MAX_REASON_LENGTH {
+		bail!(
+			"reason must be at most {} characters long",
+			BotDenial::MAX_REASON_LENGTH
+		);
+	}
+
+	let actor = ctx.actor()?;
+
+	ctx.conn.transaction(|conn| {
+		let bot = lock_bot(conn, &opts.bot_id)?;
+
+		if bot.status == BotStatus::DENIED {
+			bail!("bot `{}` is already denied", bot.id);
+		}
+
+		BotDenial::deny(conn, &bot.id, &actor.id, reason)?;
+
+		let entry = NewAuditEntry::for_actor(
+			&actor.id,
+			AuditAction::BotStatusChange,
+			format!("bot:{}", bot.id),
+		)
+		.bot(&bot.id)
+		.before(json!({ "status": String::from(bot.status) }))
+		.after(json!({ "status": String::from(BotStatus::DENIED), "reason": reason }))
+		.insert(conn)?;
+
+		Ok(vec![entry])
+	})
+}
@@ -0,0 +1,107 @@
+use crate::{find_bot, Context};
+use diesel::prelude::*;
+use izumo::models::audit::AuditEntry;
+use izumo::models::{Bot, BotCategory, BotOwner};
+use izumo::schema::{bot_owners, categories};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(clap::Parser, Debug)]
+pub struct Opts {
+	/// ID of the bot
+	bot_id: String,
+
+	/// Where to write the JSON, defaults to the standard output
+	#[arg(long, short)]
+	output: Option<PathBuf>,
+}
+
+/// A bot together with its categories and owners. The API key is left out.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BotExport {
+	pub id: String,
+	pub name: String,
+	pub avatar: Option<String>,
+	pub certified: bool,
+	/// The storage key of the banner, left out on import
+	pub banner: Option<String>,
+	/// `PENDING`, `DENIED` or `APPROVED`
+	pub status: String,
+	pub description: String,
+	pub short_description: String,
+	pub prefix: String,
+	pub is_slash: bool,
+	pub github: Option<String>,
+	pub website: Option<String>,
+	pub invite_link: Option<String>,
+	pub support_server: Option<String>,
+	pub imported_from: Option<String>,
+	pub supported_languages: Vec<String>,
+	pub guild_count: i32,
+	pub categories: Vec<String>,
+	pub owners: Vec<ExportedOwner>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExportedOwner {
+	pub user_id: String,
+	pub is_owner: bool,
+	pub permissions: i32,
+}
+
+impl BotExport {
+	pub fn load(conn: &mut PgConnection, bot: Bot) -> QueryResult<Self> {
+		let categories = BotCategory::belonging_to(&bot)
+			.inner_join(categories::table)
+			.select(categories::slug)
+			.order(categories::slug)
+			.load(conn)?;
+
+		let owners = BotOwner::belonging_to(&bot)
+			.select(BotOwner::as_select())
+			.order(bot_owners::created_at)
+			.load(conn)?
+			.into_iter()
+			.map(|owner| ExportedOwner {
+				user_id: owner.user_id,
+				is_owner: owner.is_owner,
+				permissions: owner.permissions,
+			})
+			.collect();
+
+		Ok(BotExport {
+			id: bot.id,
+			name: bot.name,
+			avatar: bot.avatar,
+			certified: bot.certified,
+			banner: bot.banner,
+			status: bot.status.into(),
+			description: bot.description,
+			short_description: bot.short_description,
+			prefix: bot.prefix,
+			is_slash: bot.is_slash,
+			github: bot.github,
+			website: bot.website,
+			invite_link: bot.invite_link,
+			support_server: bot.support_server,
+			imported_from: bot.imported_from,
+			supported_languages: bot.supported_languages,
+			guild_count: bot.guild_count,
+			categories,
+			owners,
+		})
+	}
+}
+
+pub fn run(ctx: &mut Context, opts: Opts) -> anyhow::Result<Vec<AuditEntry>> {
+	let bot = find_bot(&mut ctx.conn, &opts.bot_id)?;
+	let export = BotExport::load(&mut ctx.conn, bot)?;
+	let json = serde_json::to_string_pretty(&export)?;
+
+	match opts.output {
+		Some(path) => std::fs::write(path, json)?,
+		None => println!("{json}"),
+	}
+
+	Ok(vec![])
+}
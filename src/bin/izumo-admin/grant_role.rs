@@ -0,0 +1,51 @@
+use crate::Context;
+use anyhow::{anyhow, bail, Context as _};
+use diesel::prelude::*;
+use izumo::models::audit::{AuditAction, AuditEntry, NewAuditEntry};
+use izumo::models::user::UserRole;
+use izumo::models::User;
+use izumo::schema::users;
+use serde_json::json;
+
+#[derive(clap::Parser, Debug)]
+pub struct Opts {
+	/// ID of the user
+	user_id: String,
+
+	/// `user`, `moderator` or `admin`
+	role: String,
+}
+
+pub fn run(ctx: &mut Context, opts: Opts) -> anyhow::Result<Vec<AuditEntry>> {
+	let role: UserRole = opts.role.parse().map_err(|err| anyhow!("{err}"))?;
+
+	let actor = ctx.actor()?;
+	let user = User::find(&mut ctx.conn, &opts.user_id)
+		.optional()?
+		.with_context(|| format!("user `{}` not found", opts.user_id))?;
+
+	if user.role == role {
+		bail!(
+			"user `{}` already has the {} role",
+			user.id,
+			String::from(role)
+		);
+	}
+
+	ctx.conn.transaction(|conn| {
+		diesel::update(&user)
+			.set(users::role.eq(role))
+			.execute(conn)?;
+
+		let entry = NewAuditEntry::for_actor(
+			&actor.id,
+			AuditAction::UserRoleChange,
+			format!("user:{}", user.id),
+		)
+		.before(json!({ "role": String::from(user.role) }))
+		.after(json!({ "role": String::from(role) }))
+		.insert(conn)?;
+
+		Ok(vec![entry])
+	})
+}
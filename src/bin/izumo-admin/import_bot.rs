@@ -0,0 +1,118 @@
+use crate::export_bot::BotExport;
+use crate::Context;
+use anyhow::{anyhow, bail, Context as _};
+use diesel::prelude::*;
+use izumo::markdown;
+use izumo::models::audit::{AuditAction, AuditEntry, NewAuditEntry};
+use izumo::models::bot::{BotStatus, NewBot, NewBotBuilder};
+use izumo::models::{Bot, BotOwner, Category};
+use izumo::schema::{bot_owners, bots, users};
+use std::path::PathBuf;
+
+#[derive(clap::Parser, Debug)]
+pub struct Opts {
+	/// JSON file written by `export-bot`
+	input: PathBuf,
+}
+
+pub fn run(ctx: &mut Context, opts: Opts) -> anyhow::Result<Vec<AuditEntry>> {
+	let json = std::fs::read_to_string(&opts.input)
+		.with_context(|| format!("could not read {}", opts.input.display()))?;
+	let export: BotExport = serde_json::from_str(&json).context("invalid bot export")?;
+	let status: BotStatus = export.status.parse().map_err(|err| anyhow!("{err}"))?;
+
+	let actor = ctx.actor()?;
+	let description_html =
+		markdown::render(&export.description, ctx.config.image_proxy_url.as_ref());
+	let description_html_proxy = markdown::proxy_key(ctx.config.image_proxy_url.as_ref());
+
+	ctx.conn.transaction(|conn| {
+		let exists: Option<Bot> = Bot::by_id(&export.id).first(conn).optional()?;
+		if exists.is_some() {
+			bail!("bot `{}` already exists", export.id);
+		}
+
+		let owner_ids = export.owners.iter().map(|o| &o.user_id).collect::<Vec<_>>();
+		let known_owners: Vec<String> = users::table
+			.filter(users::id.eq_any(&owner_ids))
+			.select(users::id)
+			.load(conn)?;
+
+		let unknown_owners = owner_ids
+			.iter()
+			.filter(|id| !known_owners.contains(id))
+			.collect::<Vec<_>>();
+		if !unknown_owners.is_empty() {
+			bail!("unknown owners: {unknown_owners:?}");
+		}
+
+		let mut new_bot = NewBot::new(NewBotBuilder {
+			id: &export.id,
+			name: &export.name,
+			avatar: export.avatar.as_deref(),
+			// The banner is the storage key of an image of the source
+			// instance, which isn't carried over.
+			banner: None,
+			description: &export.description,
+			description_html: &description_html,
+			description_html_proxy: &description_html_proxy,
+			short_description: &export.short_description,
+			prefix: &export.prefix,
+			is_slash: export.is_slash,
+			imported_from: export.imported_from.as_deref(),
+			github: export.github.as_deref(),
+			website: export.website.as_deref(),
+			invite_link: export.invite_link.as_deref(),
+			support_server: export.support_server.as_deref(),
+			supported_languages: export.supported_languages.clone(),
+			guild_count: export.guild_count,
+		});
+		new_bot.certified = export.certified;
+
+		diesel::insert_into(bots::table)
+			.values(&new_bot)
+			.execute(conn)?;
+
+		diesel::update(bots::table.find(&export.id))
+			.set(bots::status.eq(status))
+			.execute(conn)?;
+
+		let now = chrono::Utc::now().naive_utc();
+		let owners = export
+			.owners
+			.iter()
+			.map(|owner| BotOwner {
+				bot_id: export.id.clone(),
+				user_id: owner.user_id.clone(),
+				is_owner: owner.is_owner,
+				permissions: owner.permissions,
+				created_at: now,
+			})
+			.collect::<Vec<_>>();
+
+		diesel::insert_into(bot_owners::table)
+			.values(&owners)
+			.execute(conn)?;
+
+		let categories = export
+			.categories
+			.iter()
+			.map(String::as_str)
+			.collect::<Vec<_>>();
+		let unknown_categories = Category::update_bot(conn, &export.id, &categories)?;
+		if !unknown_categories.is_empty() {
+			bail!("unknown categories: {unknown_categories:?}");
+		}
+
+		let entry = NewAuditEntry::for_actor(
+			&actor.id,
+			AuditAction::BotImport,
+			format!("bot:{}", export.id),
+		)
+		.bot(&export.id)
+		.after(serde_json::to_value(&export)?)
+		.insert(conn)?;
+
+		Ok(vec![entry])
+	})
+}
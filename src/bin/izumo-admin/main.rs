@@ -0,0 +1,168 @@
+//! Command-line tool for the operational tasks of dbots.fun.
+//!
+//! Every change is recorded in the audit log under the user given with
+//! `--actor` (or `IZUMO_ADMIN_ACTOR`), and the recorded entries are printed
+//! once the command is done. With `--dry-run` the command runs inside a
+//! transaction that is rolled back at the end.
+
+mod ban_user;
+mod bot_status;
+mod export_bot;
+mod grant_role;
+mod import_bot;
+mod override_rate_limit;
+mod resync_bot;
+mod revoke_token;
+
+use anyhow::Context as _;
+use diesel::prelude::*;
+use izumo::config::server::Server;
+use izumo::models::audit::AuditEntry;
+use izumo::models::{Bot, Category, User};
+use izumo::views::EncodableAuditEntry;
+use izumo::{db, util};
+
+#[derive(clap::Parser, Debug)]
+#[command(name = "izumo-admin", about = "Administrative tasks for dbots.fun")]
+struct Opts {
+	/// ID of the user the changes are attributed to in the audit log
+	#[arg(long, env = "IZUMO_ADMIN_ACTOR", global = true)]
+	actor: Option<String>,
+
+	/// Print what would change and roll everything back
+	#[arg(long, global = true)]
+	dry_run: bool,
+
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+	/// Approve a bot
+	ApproveBot(bot_status::ApproveOpts),
+	/// Deny a bot, its owners will see the reason
+	DenyBot(bot_status::DenyOpts),
+	/// Change the role of a user
+	GrantRole(grant_role::Opts),
+	/// Revoke API tokens
+	RevokeToken(revoke_token::Opts),
+	/// Ban a user, banned users cannot authenticate
+	BanUser(ban_user::BanOpts),
+	/// Lift the ban of a user
+	UnbanUser(ban_user::UnbanOpts),
+	/// Recount the approved bots of every category
+	RecalculateCounts,
+	/// Refresh the name, avatar and guild count of a bot from Discord
+	ResyncBot(resync_bot::Opts),
+	/// Export a bot, its categories and its owners as JSON
+	ExportBot(export_bot::Opts),
+	/// Import a bot previously exported with `export-bot`
+	ImportBot(import_bot::Opts),
+	/// Raise the rate limit burst of a user, e.g. for a trusted partner
+	OverrideRateLimit(override_rate_limit::Opts),
+}
+
+pub struct Context {
+	pub config: Server,
+	pub conn: PgConnection,
+	pub http: reqwest::blocking::Client,
+	actor: Option<String>,
+}
+
+impl Context {
+	/// Loads the user the changes are attributed to.
+	pub fn actor(&mut self) -> anyhow::Result<User> {
+		let id = self
+			.actor
+			.as_deref()
+			.context("this command requires `--actor` or `IZUMO_ADMIN_ACTOR` to be set")?;
+
+		User::find(&mut self.conn, id)
+			.optional()?
+			.with_context(|| format!("actor `{id}` not found"))
+	}
+}
+
+fn main() -> anyhow::Result<()> {
+	use clap::Parser;
+
+	dotenvy::dotenv().ok();
+
+	let opts = Opts::parse();
+
+	util::tracing::init();
+
+	let result = run(opts);
+
+	util::tracing::shutdown();
+
+	result
+}
+
+fn run(opts: Opts) -> anyhow::Result<()> {
+	let config = Server::from_environment()?;
+	let conn = db::oneoff_connection_with_config(&config.db)?;
+
+	let mut ctx = Context {
+		config,
+		conn,
+		http: reqwest::blocking::Client::new(),
+		actor: opts.actor,
+	};
+
+	if opts.dry_run {
+		// Nothing done inside a test transaction is ever committed.
+		ctx.conn.begin_test_transaction()?;
+	}
+
+	let entries = match opts.command {
+		Command::ApproveBot(opts) => bot_status::approve(&mut ctx, opts)?,
+		Command::DenyBot(opts) => bot_status::deny(&mut ctx, opts)?,
+		Command::GrantRole(opts) => grant_role::run(&mut ctx, opts)?,
+		Command::RevokeToken(opts) => revoke_token::run(&mut ctx, opts)?,
+		Command::BanUser(opts) => ban_user::ban(&mut ctx, opts)?,
+		Command::UnbanUser(opts) => ban_user::unban(&mut ctx, opts)?,
+		Command::RecalculateCounts => recalculate_counts(&mut ctx)?,
+		Command::ResyncBot(opts) => resync_bot::run(&mut ctx, opts)?,
+		Command::ExportBot(opts) => export_bot::run(&mut ctx, opts)?,
+		Command::ImportBot(opts) => import_bot::run(&mut ctx, opts)?,
+		Command::OverrideRateLimit(opts) => override_rate_limit::run(&mut ctx, opts)?,
+	};
+
+	for entry in entries {
+		let entry = EncodableAuditEntry::from(entry, false);
+		eprintln!("{}", serde_json::to_string_pretty(&entry)?);
+	}
+
+	if opts.dry_run {
+		eprintln!("dry run, all changes were rolled back");
+	}
+
+	Ok(())
+}
+
+fn recalculate_counts(ctx: &mut Context) -> anyhow::Result<Vec<AuditEntry>> {
+	let updated = Category::recalculate_bots_cnt(&mut ctx.conn)?;
+	eprintln!("recalculated the bot counts of {updated} categories");
+
+	Ok(vec![])
+}
+
+/// Loads a bot regardless of its status.
+pub fn find_bot(conn: &mut PgConnection, bot_id: &str) -> anyhow::Result<Bot> {
+	Bot::by_id(bot_id)
+		.first(conn)
+		.optional()?
+		.with_context(|| format!("bot `{bot_id}` not found"))
+}
+
+/// Loads a bot regardless of its status, locking it until the end of the
+/// transaction.
+pub fn lock_bot(conn: &mut PgConnection, bot_id: &str) -> anyhow::Result<Bot> {
+	Bot::by_id(bot_id)
+		.for_update()
+		.first(conn)
+		.optional()?
+		.with_context(|| format!("bot `{bot_id}` not found"))
+}
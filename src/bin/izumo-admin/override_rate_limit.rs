@@ -0,0 +1,103 @@
+use crate::Context;
+use anyhow::{anyhow, bail, Context as _};
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use izumo::models::audit::{AuditAction, AuditEntry, NewAuditEntry};
+use izumo::models::User;
+use izumo::rate_limiter::LimitedAction;
+use izumo::schema::rate_limit_overrides;
+use serde_json::json;
+
+#[derive(clap::Parser, Debug)]
+pub struct Opts {
+	/// ID of the user
+	user_id: String,
+
+	/// `publish_new`, `vote`, `token_create` or `login`
+	action: String,
+
+	/// Number of requests allowed in a row, replacing the configured burst
+	#[arg(required_unless_present = "remove")]
+	burst: Option<i32>,
+
+	/// Days until the override expires, it never expires when omitted
+	#[arg(long, conflicts_with = "remove")]
+	expires_in_days: Option<i64>,
+
+	/// Remove the override instead
+	#[arg(long)]
+	remove: bool,
+}
+
+pub fn run(ctx: &mut Context, opts: Opts) -> anyhow::Result<Vec<AuditEntry>> {
+	let action: LimitedAction = opts.action.parse().map_err(|err| anyhow!("{err}"))?;
+
+	let actor = ctx.actor()?;
+	let user = User::find(&mut ctx.conn, &opts.user_id)
+		.optional()?
+		.with_context(|| format!("user `{}` not found", opts.user_id))?;
+
+	let expires_at = opts
+		.expires_in_days
+		.map(|days| Utc::now().naive_utc() + chrono::Duration::days(days));
+
+	ctx.conn.transaction(|conn| {
+		let target = rate_limit_overrides::table.find((action, &user.id));
+
+		let before: Option<(i32, Option<NaiveDateTime>)> = target
+			.select((
+				rate_limit_overrides::burst,
+				rate_limit_overrides::expires_at,
+			))
+			.first(conn)
+			.optional()?;
+
+		let after = match opts.burst {
+			Some(burst) if !opts.remove => {
+				diesel::insert_into(rate_limit_overrides::table)
+					.values((
+						rate_limit_overrides::action.eq(action),
+						rate_limit_overrides::user_id.eq(&user.id),
+						rate_limit_overrides::burst.eq(burst),
+						rate_limit_overrides::expires_at.eq(expires_at),
+					))
+					.on_conflict((rate_limit_overrides::action, rate_limit_overrides::user_id))
+					.do_update()
+					.set((
+						rate_limit_overrides::burst.eq(burst),
+						rate_limit_overrides::expires_at.eq(expires_at),
+					))
+					.execute(conn)?;
+
+				Some((burst, expires_at))
+			}
+			_ => {
+				if before.is_none() {
+					bail!(
+						"user `{}` has no override for `{}`",
+						user.id,
+						action.env_var_key()
+					);
+				}
+
+				diesel::delete(target).execute(conn)?;
+				None
+			}
+		};
+
+		let encode = |state: Option<(i32, Option<NaiveDateTime>)>| {
+			state.map(|(burst, expires_at)| json!({ "burst": burst, "expires_at": expires_at }))
+		};
+
+		let entry = NewAuditEntry::for_actor(
+			&actor.id,
+			AuditAction::RateLimitOverride,
+			format!("user:{}", user.id),
+		)
+		.before(json!({ "action": action.env_var_key(), "override": encode(before) }))
+		.after(json!({ "action": action.env_var_key(), "override": encode(after) }))
+		.insert(conn)?;
+
+		Ok(vec![entry])
+	})
+}
@@ -0,0 +1,55 @@
+use crate::{find_bot, Context};
+use diesel::prelude::*;
+use izumo::discord;
+use izumo::models::audit::{AuditAction, AuditEntry, NewAuditEntry};
+use izumo::schema::bots;
+use serde_json::json;
+
+#[derive(clap::Parser, Debug)]
+pub struct Opts {
+	/// ID of the bot
+	bot_id: String,
+}
+
+pub fn run(ctx: &mut Context, opts: Opts) -> anyhow::Result<Vec<AuditEntry>> {
+	let actor = ctx.actor()?;
+	let bot = find_bot(&mut ctx.conn, &opts.bot_id)?;
+
+	let info = discord::get_bot_information(&ctx.http, &ctx.config.discord, &bot.id)?.bot;
+
+	let unchanged = bot.name == info.username
+		&& bot.avatar.as_deref() == Some(info.avatar.as_str())
+		&& bot.guild_count == info.approximate_guild_count;
+
+	if unchanged {
+		eprintln!("bot `{}` is already up to date", bot.id);
+		return Ok(vec![]);
+	}
+
+	ctx.conn.transaction(|conn| {
+		diesel::update(&bot)
+			.set((
+				bots::name.eq(&info.username),
+				bots::avatar.eq(&info.avatar),
+				bots::guild_count.eq(info.approximate_guild_count),
+			))
+			.execute(conn)?;
+
+		let entry =
+			NewAuditEntry::for_actor(&actor.id, AuditAction::BotResync, format!("bot:{}", bot.id))
+				.bot(&bot.id)
+				.before(json!({
+					"name": bot.name,
+					"avatar": bot.avatar,
+					"guild_count": bot.guild_count,
+				}))
+				.after(json!({
+					"name": info.username,
+					"avatar": info.avatar,
+					"guild_count": info.approximate_guild_count,
+				}))
+				.insert(conn)?;
+
+		Ok(vec![entry])
+	})
+}
@@ -0,0 +1,52 @@
+use crate::Context;
+use anyhow::Context as _;
+use diesel::prelude::*;
+use izumo::models::audit::{AuditAction, AuditEntry, NewAuditEntry};
+use izumo::models::ApiToken;
+use izumo::schema::api_tokens;
+use serde_json::json;
+
+#[derive(clap::Parser, Debug)]
+pub struct Opts {
+	/// IDs of the tokens
+	#[arg(required = true)]
+	token_ids: Vec<i32>,
+}
+
+pub fn run(ctx: &mut Context, opts: Opts) -> anyhow::Result<Vec<AuditEntry>> {
+	let actor = ctx.actor()?;
+
+	ctx.conn.transaction(|conn| {
+		let mut entries = Vec::with_capacity(opts.token_ids.len());
+
+		for id in opts.token_ids {
+			let token: ApiToken = api_tokens::table
+				.find(id)
+				.select(ApiToken::as_select())
+				.first(conn)
+				.optional()?
+				.with_context(|| format!("token `{id}` not found"))?;
+
+			if token.revoked {
+				eprintln!("token `{id}` is already revoked, skipping");
+				continue;
+			}
+
+			diesel::update(&token)
+				.set(api_tokens::revoked.eq(true))
+				.execute(conn)?;
+
+			let entry = NewAuditEntry::for_actor(
+				&actor.id,
+				AuditAction::TokenRevoke,
+				format!("token:{id}"),
+			)
+			.after(json!({ "user_id": token.user_id, "name": token.token_name }))
+			.insert(conn)?;
+
+			entries.push(entry);
+		}
+
+		Ok(entries)
+	})
+}
@@ -0,0 +1,139 @@
+//! In-memory cache of the traffic blocked by the `block_traffic` middleware.
+//!
+//! The rules combine the `BLOCKED_IPS`, `BLOCKED_CIDRS` and
+//! `BLOCKED_USER_AGENTS` environment variables with the active entries of
+//! the `blocked_traffic` table, which are reloaded every
+//! `BLOCKLIST_REFRESH_SECONDS` so that staff can edit them without a redeploy.
+
+use crate::config::server::Server;
+use crate::models::blocked_traffic::BlockedTraffic;
+use crate::models::util::diesel::Conn;
+use diesel::QueryResult;
+use ipnetwork::IpNetwork;
+use parking_lot::RwLock;
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+pub struct BlocklistRules {
+	ips: HashSet<IpAddr>,
+	cidrs: Vec<IpNetwork>,
+	user_agents: Vec<String>,
+}
+
+impl BlocklistRules {
+	/// The rules of the environment variables.
+	fn from_config(config: &Server) -> Self {
+		Self {
+			ips: config.blocked_ips.clone(),
+			cidrs: config.blocked_cidrs.clone(),
+			user_agents: config.blocked_user_agents.clone(),
+		}
+	}
+
+	fn extend(&mut self, entries: Vec<BlockedTraffic>) {
+		for entry in entries {
+			if let Some(ip_range) = entry.ip_range {
+				if ip_range.prefix() == max_prefix(&ip_range) {
+					self.ips.insert(ip_range.ip());
+				} else {
+					self.cidrs.push(ip_range);
+				}
+			}
+
+			if let Some(user_agent) = entry.user_agent {
+				self.user_agents.push(user_agent);
+			}
+		}
+	}
+
+	pub fn is_ip_blocked(&self, ip: IpAddr) -> bool {
+		self.ips.contains(&ip) || self.cidrs.iter().any(|cidr| cidr.contains(ip))
+	}
+
+	pub fn is_user_agent_blocked(&self, user_agent: &str) -> bool {
+		self.user_agents
+			.iter()
+			.any(|blocked| user_agent.contains(blocked.as_str()))
+	}
+}
+
+fn max_prefix(ip_range: &IpNetwork) -> u8 {
+	match ip_range {
+		IpNetwork::V4(_) => 32,
+		IpNetwork::V6(_) => 128,
+	}
+}
+
+#[derive(Debug)]
+pub struct Blocklist {
+	rules: RwLock<Arc<BlocklistRules>>,
+}
+
+impl Blocklist {
+	/// Creates a blocklist with the rules of the environment variables only,
+	/// until the first [`Blocklist::refresh`].
+	pub fn new(config: &Server) -> Self {
+		Self {
+			rules: RwLock::new(Arc::new(BlocklistRules::from_config(config))),
+		}
+	}
+
+	/// The current rules, which stay the same while a request is checked.
+	pub fn rules(&self) -> Arc<BlocklistRules> {
+		self.rules.read().clone()
+	}
+
+	/// Reloads the active entries of the `blocked_traffic` table.
+	pub fn refresh(&self, config: &Server, conn: &mut impl Conn) -> QueryResult<()> {
+		let mut rules = BlocklistRules::from_config(config);
+		rules.extend(BlockedTraffic::active(conn)?);
+
+		*self.rules.write() = Arc::new(rules);
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn entry(ip_range: Option<&str>, user_agent: Option<&str>) -> BlockedTraffic {
+		BlockedTraffic {
+			id: 1,
+			ip_range: ip_range.map(|ip_range| ip_range.parse().unwrap()),
+			user_agent: user_agent.map(Into::into),
+			reason: "abuse".into(),
+			created_by: None,
+			created_at: chrono::Utc::now().naive_utc(),
+			expires_at: None,
+		}
+	}
+
+	#[test]
+	fn blocks_ips_and_cidrs() {
+		let mut rules = BlocklistRules::default();
+		rules.extend(vec![
+			entry(Some("192.0.2.1"), None),
+			entry(Some("198.51.100.0/24"), None),
+			entry(Some("2001:db8::/32"), None),
+		]);
+
+		assert!(rules.is_ip_blocked("192.0.2.1".parse().unwrap()));
+		assert!(!rules.is_ip_blocked("192.0.2.2".parse().unwrap()));
+		assert!(rules.is_ip_blocked("198.51.100.42".parse().unwrap()));
+		assert!(rules.is_ip_blocked("2001:db8::1".parse().unwrap()));
+		assert!(!rules.is_ip_blocked("2001:db9::1".parse().unwrap()));
+	}
+
+	#[test]
+	fn blocks_user_agents() {
+		let mut rules = BlocklistRules::default();
+		rules.extend(vec![entry(None, Some("BadBot/"))]);
+
+		assert!(rules.is_user_agent_blocked("Mozilla/5.0 (compatible; BadBot/1.0)"));
+		assert!(!rules.is_user_agent_blocked("Mozilla/5.0 (compatible; GoodBot/1.0)"));
+	}
+}
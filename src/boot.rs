@@ -0,0 +1,3 @@
+pub mod categories;
+pub mod descriptions;
+pub mod migrations;
@@ -0,0 +1,380 @@
+//! Sync the categories defined in `categories.toml` with the database.
+//!
+//! - `SYNC_CATEGORIES_ON_STARTUP`: When set, the server syncs the categories
+//!   before accepting connections. Off by default, as the sync deletes the
+//!   categories created through the admin API.
+//! - `CATEGORIES_FALLBACK`: The slug of the category that receives the bots of
+//!   removed categories. Defaults to `other`.
+
+use crate::boot::migrations;
+use crate::models::audit::{AuditAction, NewAuditEntry, SYSTEM_ACTOR};
+use crate::models::category::NewCategory;
+use crate::models::util::diesel::Conn;
+use crate::models::Category as CategoryModel;
+use crate::schema::{bots_categories, categories};
+use anyhow::{bail, Context};
+use crates_io_env_vars::var;
+use diesel::prelude::*;
+use std::fmt;
+
+/// The categories checked into the repository.
+pub const CATEGORIES_TOML: &str = include_str!("categories.toml");
+
+const DEFAULT_FALLBACK: &str = "other";
+
+/// Reads the slug of the fallback category from the environment.
+pub fn fallback_from_environment() -> anyhow::Result<String> {
+	Ok(var("CATEGORIES_FALLBACK")?.unwrap_or_else(|| DEFAULT_FALLBACK.into()))
+}
+
+#[derive(Debug, PartialEq)]
+struct Category {
+	slug: String,
+	name: String,
+	description: String,
+}
+
+impl Category {
+	fn from_parent(
+		slug: &str,
+		name: &str,
+		description: &str,
+		parent: Option<&Category>,
+	) -> Category {
+		match parent {
+			Some(parent) => Category {
+				slug: format!("{}::{}", parent.slug, slug),
+				name: format!("{}::{}", parent.name, name),
+				description: description.into(),
+			},
+			None => Category {
+				slug: slug.into(),
+				name: name.into(),
+				description: description.into(),
+			},
+		}
+	}
+}
+
+fn required_string_from_toml<'a>(
+	toml: &'a toml::value::Table,
+	key: &str,
+) -> anyhow::Result<&'a str> {
+	toml.get(key)
+		.and_then(toml::Value::as_str)
+		.with_context(|| format!("Expected category TOML attribute '{key}' to be a String"))
+}
+
+fn optional_string_from_toml<'a>(toml: &'a toml::value::Table, key: &str) -> &'a str {
+	toml.get(key).and_then(toml::Value::as_str).unwrap_or("")
+}
+
+fn categories_from_toml(
+	categories: &toml::value::Table,
+	parent: Option<&Category>,
+) -> anyhow::Result<Vec<Category>> {
+	let mut result = vec![];
+
+	for (slug, details) in categories {
+		let details = details
+			.as_table()
+			.with_context(|| format!("category {slug} was not a TOML table"))?;
+
+		let category = Category::from_parent(
+			slug,
+			required_string_from_toml(details, "name")?,
+			optional_string_from_toml(details, "description"),
+			parent,
+		);
+
+		if !CategoryModel::is_valid_slug(&category.slug) {
+			bail!("category slug `{}` is invalid", category.slug);
+		}
+
+		if category.name.len() > CategoryModel::MAX_LENGTH {
+			bail!("category name `{}` is too long", category.name);
+		}
+
+		if let Some(categories) = details.get("categories") {
+			let categories = categories
+				.as_table()
+				.with_context(|| format!("child categories of {slug} were not a TOML table"))?;
+
+			result.extend(categories_from_toml(categories, Some(&category))?);
+		}
+
+		result.push(category);
+	}
+
+	Ok(result)
+}
+
+/// A category that is no longer defined, along with the number of bots that
+/// will be moved to the fallback category.
+#[derive(Debug)]
+pub struct RemovedCategory {
+	pub slug: String,
+	pub bots: i64,
+}
+
+/// The changes made (or, for a dry run, to be made) by a sync.
+#[derive(Debug, Default)]
+pub struct SyncReport {
+	pub fallback: String,
+	pub added: Vec<String>,
+	pub updated: Vec<String>,
+	pub removed: Vec<RemovedCategory>,
+}
+
+impl SyncReport {
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+	}
+}
+
+impl fmt::Display for SyncReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.is_empty() {
+			return writeln!(f, "categories are up to date");
+		}
+
+		for slug in &self.added {
+			writeln!(f, "+ {slug}")?;
+		}
+		for slug in &self.updated {
+			writeln!(f, "~ {slug}")?;
+		}
+		for removed in &self.removed {
+			writeln!(
+				f,
+				"- {} ({} bots moved to {})",
+				removed.slug, removed.bots, self.fallback
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Reconciles the `categories` table with the given TOML definitions.
+///
+/// Categories are upserted by slug. Categories missing from the TOML are
+/// deleted, and their bots are added to the `fallback` category. When
+/// `dry_run` is set the database is left untouched and only the report of
+/// the pending changes is returned.
+///
+/// The sync holds the migrations advisory lock, and records every change in
+/// the audit log.
+pub fn sync_with_connection(
+	toml_str: &str,
+	fallback: &str,
+	conn: &mut impl Conn,
+	dry_run: bool,
+) -> anyhow::Result<SyncReport> {
+	let toml: toml::value::Table =
+		toml::from_str(toml_str).context("Could not parse categories toml")?;
+
+	let definitions = categories_from_toml(&toml, None)?;
+
+	if !definitions.iter().any(|c| c.slug == fallback) {
+		bail!("fallback category `{fallback}` is not defined");
+	}
+
+	let fallback_prefix = format!("{fallback}::");
+	if definitions
+		.iter()
+		.any(|c| c.slug.starts_with(&fallback_prefix))
+	{
+		bail!("fallback category `{fallback}` must not have subcategories");
+	}
+
+	conn.transaction(|conn| {
+		migrations::lock_for_transaction(conn)?;
+
+		let existing: Vec<CategoryModel> = categories::table.load(conn)?;
+
+		let mut report = SyncReport {
+			fallback: fallback.into(),
+			..Default::default()
+		};
+
+		for definition in &definitions {
+			match existing.iter().find(|c| c.slug == definition.slug) {
+				None => report.added.push(definition.slug.clone()),
+				Some(c)
+					if c.category != definition.name
+						|| c.description.as_deref().unwrap_or("") != definition.description =>
+				{
+					report.updated.push(definition.slug.clone())
+				}
+				Some(_) => {}
+			}
+		}
+
+		let mut removed = existing
+			.iter()
+			.filter(|c| !definitions.iter().any(|d| d.slug == c.slug))
+			.collect::<Vec<_>>();
+		removed.sort_by(|a, b| a.slug.cmp(&b.slug));
+
+		for category in &removed {
+			let bots = bots_categories::table
+				.filter(bots_categories::category_id.eq(category.id))
+				.count()
+				.get_result(conn)?;
+
+			report.removed.push(RemovedCategory {
+				slug: category.slug.clone(),
+				bots,
+			});
+		}
+
+		report.added.sort();
+		report.updated.sort();
+
+		if dry_run {
+			return Ok(report);
+		}
+
+		let mut fallback_id = None;
+		for definition in &definitions {
+			let category = NewCategory {
+				category: &definition.name,
+				slug: &definition.slug,
+				description: &definition.description,
+			}
+			.upsert(conn)?;
+
+			if category.slug == fallback {
+				fallback_id = Some(category.id);
+			}
+
+			let previous = existing.iter().find(|c| c.slug == definition.slug);
+			let action = match previous {
+				None => AuditAction::CategoryCreate,
+				Some(_) if report.updated.contains(&definition.slug) => AuditAction::CategoryUpdate,
+				Some(_) => continue,
+			};
+
+			let mut entry = NewAuditEntry::for_actor(
+				SYSTEM_ACTOR,
+				action,
+				format!("category:{}", category.slug),
+			)
+			.after(json!({
+				"category": category.category,
+				"description": category.description,
+			}));
+			if let Some(previous) = previous {
+				entry = entry.before(json!({
+					"category": previous.category,
+					"description": previous.description,
+				}));
+			}
+			entry.insert(conn)?;
+		}
+		let fallback_id = fallback_id.context("fallback category was not inserted")?;
+
+		let removed_ids = removed.iter().map(|c| c.id).collect::<Vec<_>>();
+		if !removed_ids.is_empty() {
+			let remapped = bots_categories::table
+				.filter(bots_categories::category_id.eq_any(&removed_ids))
+				.select((
+					bots_categories::bot_id,
+					fallback_id.into_sql::<diesel::sql_types::Integer>(),
+				));
+
+			diesel::insert_into(bots_categories::table)
+				.values(remapped)
+				.into_columns((bots_categories::bot_id, bots_categories::category_id))
+				.on_conflict_do_nothing()
+				.execute(conn)?;
+
+			diesel::delete(bots_categories::table)
+				.filter(bots_categories::category_id.eq_any(&removed_ids))
+				.execute(conn)?;
+
+			diesel::delete(categories::table)
+				.filter(categories::id.eq_any(&removed_ids))
+				.execute(conn)?;
+		}
+
+		for (category, moved) in removed.iter().zip(&report.removed) {
+			NewAuditEntry::for_actor(
+				SYSTEM_ACTOR,
+				AuditAction::CategoryDelete,
+				format!("category:{}", category.slug),
+			)
+			.before(json!({
+				"category": category.category,
+				"description": category.description,
+				"bots_cnt": moved.bots,
+			}))
+			.after(json!({ "bots_moved_to": fallback }))
+			.insert(conn)?;
+		}
+
+		Ok(report)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn nested_categories() {
+		let toml = r#"
+			[moderation]
+			name = "Moderation"
+			description = "Keep a server safe."
+
+			[moderation.categories.logging]
+			name = "Logging"
+
+			[music]
+			name = "Music"
+		"#;
+
+		let toml: toml::value::Table = toml::from_str(toml).unwrap();
+		let mut categories = categories_from_toml(&toml, None).unwrap();
+		categories.sort_by(|a, b| a.slug.cmp(&b.slug));
+
+		assert_eq!(
+			categories,
+			vec![
+				Category {
+					slug: "moderation".into(),
+					name: "Moderation".into(),
+					description: "Keep a server safe.".into(),
+				},
+				Category {
+					slug: "moderation::logging".into(),
+					name: "Moderation::Logging".into(),
+					description: "".into(),
+				},
+				Category {
+					slug: "music".into(),
+					name: "Music".into(),
+					description: "".into(),
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn invalid_categories() {
+		let toml: toml::value::Table = toml::from_str("[Music]\nname = \"Music\"").unwrap();
+		assert!(categories_from_toml(&toml, None).is_err());
+
+		let toml: toml::value::Table = toml::from_str("[music]\ndescription = \"\"").unwrap();
+		assert!(categories_from_toml(&toml, None).is_err());
+	}
+
+	#[test]
+	fn checked_in_categories_parse() {
+		let toml: toml::value::Table = toml::from_str(CATEGORIES_TOML).unwrap();
+		let categories = categories_from_toml(&toml, None).unwrap();
+		assert!(categories.iter().any(|c| c.slug == DEFAULT_FALLBACK));
+	}
+}
@@ -0,0 +1,121 @@
+//! Render the bot descriptions and their translations again after a change
+//! of the sanitizer policy, see [`crate::markdown::POLICY_VERSION`], or of
+//! the settings of the image proxy, see [`crate::markdown::proxy_key`].
+//!
+//! Only the descriptions rendered with an older policy, or with the current
+//! policy and other proxy settings, are rendered again, so that the replicas
+//! of older versions don't undo each other's work during a rolling deploy.
+
+use crate::markdown::{self, ImageProxyUrl};
+use crate::models::util::diesel::Conn;
+use crate::schema::{bot_translations, bots};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool};
+
+/// How many descriptions are rendered per query.
+const BATCH_SIZE: i64 = 100;
+
+/// Key of the session-level advisory lock held while the descriptions are
+/// rendered, so that only one replica renders them.
+const ADVISORY_LOCK_KEY: i64 = 0x0064_6573_6372; // "descr" in ASCII
+
+#[derive(QueryableByName)]
+struct Locked {
+	#[diesel(sql_type = Bool)]
+	locked: bool,
+}
+
+/// Renders the outdated descriptions, returning how many were rendered, or `None` if another process is already rendering them.
+pub fn render_outdated(
+	conn: &mut impl Conn,
+	image_proxy: Option<&ImageProxyUrl>,
+) -> anyhow::Result<Option<usize>> {
+	let Locked { locked } = diesel::sql_query("SELECT pg_try_advisory_lock($1) AS locked")
+		.bind::<BigInt, _>(ADVISORY_LOCK_KEY)
+		.get_result(conn)?;
+
+	if !locked {
+		return Ok(None);
+	}
+
+	let result = render_outdated_locked(conn, image_proxy);
+
+	diesel::sql_query("SELECT pg_advisory_unlock($1)")
+		.bind::<BigInt, _>(ADVISORY_LOCK_KEY)
+		.execute(conn)?;
+
+	result.map(Some)
+}
+
+fn render_outdated_locked(
+	conn: &mut impl Conn,
+	image_proxy: Option<&ImageProxyUrl>,
+) -> anyhow::Result<usize> {
+	let proxy_key = markdown::proxy_key(image_proxy);
+	let mut rendered = 0;
+
+	let bot_outdated = bots::description_html_version
+		.lt(markdown::POLICY_VERSION)
+		.or(bots::description_html_version
+			.eq(markdown::POLICY_VERSION)
+			.and(bots::description_html_proxy.ne(&proxy_key)));
+
+	loop {
+		let outdated: Vec<(String, String)> = bots::table
+			.filter(bot_outdated)
+			.select((bots::id, bots::description))
+			.order(bots::id)
+			.limit(BATCH_SIZE)
+			.load(conn)?;
+
+		if outdated.is_empty() {
+			break;
+		}
+
+		for (id, description) in outdated {
+			rendered += diesel::update(bots::table.find(&id))
+				.filter(bot_outdated)
+				.set((
+					bots::description_html.eq(markdown::render(&description, image_proxy)),
+					bots::description_html_version.eq(markdown::POLICY_VERSION),
+					bots::description_html_proxy.eq(&proxy_key),
+				))
+				.execute(conn)?;
+		}
+	}
+
+	let translation_outdated = bot_translations::description_html_version
+		.lt(markdown::POLICY_VERSION)
+		.or(bot_translations::description_html_version
+			.eq(markdown::POLICY_VERSION)
+			.and(bot_translations::description_html_proxy.ne(&proxy_key)));
+
+	loop {
+		let outdated: Vec<(String, String, String)> = bot_translations::table
+			.filter(translation_outdated)
+			.select((
+				bot_translations::bot_id,
+				bot_translations::language,
+				bot_translations::description,
+			))
+			.order((bot_translations::bot_id, bot_translations::language))
+			.limit(BATCH_SIZE)
+			.load(conn)?;
+
+		if outdated.is_empty() {
+			return Ok(rendered);
+		}
+
+		for (bot_id, language, description) in outdated {
+			rendered += diesel::update(bot_translations::table.find((&bot_id, &language)))
+				.filter(translation_outdated)
+				.set((
+					bot_translations::description_html
+						.eq(markdown::render(&description, image_proxy)),
+					bot_translations::description_html_version.eq(markdown::POLICY_VERSION),
+					bot_translations::description_html_proxy.eq(&proxy_key),
+				))
+				.execute(conn)?;
+		}
+	}
+}
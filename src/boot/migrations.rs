@@ -0,0 +1,176 @@
+//! Apply the migrations embedded in the binary.
+//!
+//! - `RUN_MIGRATIONS`: When set, the server applies the pending migrations
+//!   before accepting connections.
+
+use crate::db::MIGRATIONS;
+use crate::models::util::diesel::Conn;
+use anyhow::{anyhow, bail};
+use diesel::migration::MigrationSource;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use diesel::PgConnection;
+use diesel::QueryResult;
+use diesel_migrations::MigrationHarness;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Key of the session-level advisory lock held while migrations are applied,
+/// so that replicas booting at the same time apply them one after the other.
+const ADVISORY_LOCK_KEY: i64 = 0x0069_7a75_6d6f; // "izumo" in ASCII
+
+/// Compares the migrations embedded in the binary with the applied ones.
+#[derive(Debug, Default)]
+pub struct MigrationStatus {
+	/// Embedded migrations that were applied
+	pub applied: Vec<String>,
+	/// Embedded migrations that were not applied yet
+	pub pending: Vec<String>,
+	/// Versions applied to the database that this binary doesn't know about
+	pub unknown: Vec<String>,
+}
+
+impl MigrationStatus {
+	/// The database was migrated by a newer build than this one.
+	pub fn is_schema_ahead(&self) -> bool {
+		!self.unknown.is_empty()
+	}
+}
+
+impl fmt::Display for MigrationStatus {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for name in &self.applied {
+			writeln!(f, "[x] {name}")?;
+		}
+		for name in &self.pending {
+			writeln!(f, "[ ] {name}")?;
+		}
+		for version in &self.unknown {
+			writeln!(f, "[?] {version} (not embedded in this build)")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Lists the applied, pending and unknown migrations.
+pub fn status<C: MigrationHarness<Pg>>(conn: &mut C) -> anyhow::Result<MigrationStatus> {
+	let applied = conn
+		.applied_migrations()
+		.map_err(|err| anyhow!(err))?
+		.into_iter()
+		.map(|version| version.to_string())
+		.collect::<HashSet<_>>();
+
+	let embedded = MigrationSource::<Pg>::migrations(&MIGRATIONS).map_err(|err| anyhow!(err))?;
+	let embedded_versions = embedded
+		.iter()
+		.map(|migration| migration.name().version().to_string())
+		.collect::<HashSet<_>>();
+
+	let mut status = MigrationStatus::default();
+	for migration in &embedded {
+		let name = migration.name().to_string();
+		if applied.contains(&migration.name().version().to_string()) {
+			status.applied.push(name);
+		} else {
+			status.pending.push(name);
+		}
+	}
+
+	status.unknown = applied
+		.into_iter()
+		.filter(|version| !embedded_versions.contains(version))
+		.collect();
+	status.unknown.sort();
+
+	Ok(status)
+}
+
+/// Applies the pending migrations while holding an advisory lock, and
+/// returns the versions of the applied ones.
+///
+/// Fails without applying anything if the database has migrations that this
+/// binary doesn't know about.
+pub fn run_pending(conn: &mut PgConnection) -> anyhow::Result<Vec<String>> {
+	diesel::sql_query("SELECT pg_advisory_lock($1)")
+		.bind::<BigInt, _>(ADVISORY_LOCK_KEY)
+		.execute(conn)?;
+
+	let result = run_pending_locked(conn);
+
+	diesel::sql_query("SELECT pg_advisory_unlock($1)")
+		.bind::<BigInt, _>(ADVISORY_LOCK_KEY)
+		.execute(conn)?;
+
+	result
+}
+
+/// Takes the same advisory lock until the end of the current transaction,
+/// for the other startup tasks that must not run concurrently on replicas.
+pub fn lock_for_transaction(conn: &mut impl Conn) -> QueryResult<()> {
+	diesel::sql_query("SELECT pg_advisory_xact_lock($1)")
+		.bind::<BigInt, _>(ADVISORY_LOCK_KEY)
+		.execute(conn)?;
+
+	Ok(())
+}
+
+fn run_pending_locked(conn: &mut PgConnection) -> anyhow::Result<Vec<String>> {
+	ensure_schema_not_ahead(&status(conn)?)?;
+
+	let applied = conn
+		.run_pending_migrations(MIGRATIONS)
+		.map_err(|err| anyhow!(err))?
+		.into_iter()
+		.map(|version| version.to_string())
+		.collect();
+
+	Ok(applied)
+}
+
+/// Fails if the database was migrated by a newer build than this one.
+pub fn ensure_schema_not_ahead(status: &MigrationStatus) -> anyhow::Result<()> {
+	if status.is_schema_ahead() {
+		bail!(
+			"The database schema is ahead of this build, unknown migrations: {}",
+			status.unknown.join(", ")
+		);
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn schema_ahead() {
+		let status = MigrationStatus {
+			applied: vec!["2024-07-30-164348_add_users".into()],
+			pending: vec![],
+			unknown: vec!["20991231000000".into()],
+		};
+
+		assert!(status.is_schema_ahead());
+		assert!(ensure_schema_not_ahead(&status).is_err());
+		assert_eq!(
+			status.to_string(),
+			"[x] 2024-07-30-164348_add_users\n[?] 20991231000000 (not embedded in this build)\n"
+		);
+	}
+
+	#[test]
+	fn schema_behind() {
+		let status = MigrationStatus {
+			applied: vec![],
+			pending: vec!["2024-07-30-164348_add_users".into()],
+			unknown: vec![],
+		};
+
+		assert!(!status.is_schema_ahead());
+		assert!(ensure_schema_not_ahead(&status).is_ok());
+	}
+}
@@ -3,10 +3,16 @@ use anyhow::Context;
 use axum::http::HeaderValue;
 use crates_io_env_vars::{list, list_parsed, required_var, var, var_parsed};
 use ipnetwork::IpNetwork;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
 use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
 
+use crate::image_proxy::ImageProxyConfig;
+use crate::markdown::ImageProxyUrl;
+use crate::rate_limiter::{LimitedAction, RateLimiterConfig};
+use crate::storage::StorageConfig;
 use crate::util::env::Env;
 
 use super::base::Base;
@@ -22,11 +28,36 @@ pub struct Server {
 	pub discord: DiscordConfig,
 	pub session_key: cookie::Key,
 	pub blocked_ips: HashSet<IpAddr>,
+	pub blocked_cidrs: Vec<IpNetwork>,
+	pub blocked_user_agents: Vec<String>,
+	/// How often the `blocked_traffic` table is reloaded.
+	pub blocklist_refresh_interval: Duration,
 	pub db: DatabasePools,
 	pub max_allowed_page_offset: u32,
 	pub page_offset_ua_blocklist: Vec<String>,
 	pub page_offset_cidr_blocklist: Vec<IpNetwork>,
 	pub domain_name: String,
+	/// Whether the pending migrations are applied on startup.
+	pub run_migrations: bool,
+	/// Whether `categories.toml` is synced with the database on startup,
+	/// which deletes the categories created through the admin API.
+	pub sync_categories: bool,
+	pub categories_fallback: String,
+	pub rate_limiter: HashMap<LimitedAction, RateLimiterConfig>,
+	/// Bearer token required to access `GET /metrics`, the endpoint is
+	/// disabled when unset.
+	pub metrics_authorization_token: Option<String>,
+	/// Base URL of the proxy the images of the bot descriptions are loaded
+	/// through, as `{url}?url={image}`. The images are hotlinked when unset.
+	///
+	/// When the proxy of [`crate::image_proxy`] is enabled, `IMAGE_PROXY_URL`
+	/// is expected to point at it, and only the images of its allowed hosts
+	/// are loaded through it.
+	pub image_proxy_url: Option<ImageProxyUrl>,
+	/// Where the uploaded images are stored, see [`crate::storage`].
+	pub storage: StorageConfig,
+	/// The caching proxy of external images, see [`crate::image_proxy`].
+	pub image_proxy: Option<ImageProxyConfig>,
 }
 
 impl Server {
@@ -43,6 +74,13 @@ impl Server {
 		let port = var_parsed("PORT")?.unwrap_or(8888);
 
 		let blocked_ips = HashSet::from_iter(list_parsed("BLOCKED_IPS", IpAddr::from_str)?);
+		let blocked_cidrs = list_parsed("BLOCKED_CIDRS", IpNetwork::from_str)?;
+		let blocked_user_agents = list("BLOCKED_USER_AGENTS")?;
+		let blocklist_refresh_seconds = var_parsed("BLOCKLIST_REFRESH_SECONDS")?.unwrap_or(60);
+		if blocklist_refresh_seconds == 0 {
+			return Err(anyhow!("BLOCKLIST_REFRESH_SECONDS must be greater than 0."));
+		}
+		let blocklist_refresh_interval = Duration::from_secs(blocklist_refresh_seconds);
 		let max_blocking_threads = var_parsed("SERVER_THREADS")?;
 		let allowed_origins = AllowedOrigins::from_default_env()?;
 
@@ -55,6 +93,18 @@ impl Server {
 
 		let domain_name = var("DOMAIN_NAME")?.unwrap_or(String::from("dbots.fun"));
 
+		let run_migrations = var("RUN_MIGRATIONS")?.is_some();
+		let sync_categories = var("SYNC_CATEGORIES_ON_STARTUP")?.is_some();
+		let categories_fallback = crate::boot::categories::fallback_from_environment()?;
+
+		let image_proxy = ImageProxyConfig::from_environment()?;
+		let image_proxy_url = var_parsed::<Url>("IMAGE_PROXY_URL")?.map(|url| ImageProxyUrl {
+			url,
+			allowed_hosts: image_proxy
+				.as_ref()
+				.map(|image_proxy| image_proxy.allowed_hosts.clone()),
+		});
+
 		Ok(Self {
 			db: DatabasePools::full_from_environment(&base)?,
 			base,
@@ -68,7 +118,18 @@ impl Server {
 			allowed_origins,
 			discord,
 			blocked_ips,
+			blocked_cidrs,
+			blocked_user_agents,
+			blocklist_refresh_interval,
 			domain_name,
+			run_migrations,
+			sync_categories,
+			categories_fallback,
+			rate_limiter: RateLimiterConfig::from_environment()?,
+			metrics_authorization_token: var("METRICS_AUTHORIZATION_TOKEN")?,
+			image_proxy_url,
+			storage: StorageConfig::from_environment(port)?,
+			image_proxy,
 		})
 	}
 }
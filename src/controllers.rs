@@ -1,6 +1,11 @@
+pub mod admin;
 pub mod bot;
 pub mod category;
+pub mod health;
 pub mod helpers;
+pub mod image_proxy;
+pub mod language;
+pub mod metrics;
 pub mod summary;
 pub mod token;
 pub mod user;
@@ -0,0 +1,7 @@
+pub mod audit;
+pub mod blocklist;
+pub mod bots;
+pub mod categories;
+pub mod certifications;
+pub mod ratings;
+pub mod reports;
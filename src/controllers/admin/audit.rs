@@ -0,0 +1,81 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::Paginate;
+use crate::models::audit::{AuditAction, AuditEntry};
+use crate::schema::audit_log;
+use crate::task::spawn_blocking;
+use crate::util::errors::AppResult;
+use crate::util::RequestUtils;
+use crate::views::EncodableAuditEntry;
+use axum::http::request::Parts;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// Handles the `GET /admin/audit` route.
+///
+/// Lists the audit log, newest first. Can be filtered with `?actor=`, `?bot=`,
+/// `?action=` and `?target=`.
+pub async fn list(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder().gather(&req)?;
+
+	let query = req.query();
+	let action = query
+		.get("action")
+		.map(|action| action.parse::<AuditAction>())
+		.transpose()?;
+	let actor = query.get("actor").cloned();
+	let bot = query.get("bot").cloned();
+	let target = query.get("target").cloned();
+
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		let mut entries = audit_log::table
+			.select(audit_log::all_columns)
+			.order(audit_log::id.desc())
+			.into_boxed();
+
+		if let Some(action) = action {
+			entries = entries.filter(audit_log::action.eq(action));
+		}
+
+		if let Some(actor) = actor {
+			entries = entries.filter(audit_log::actor_id.eq(actor));
+		}
+
+		if let Some(bot) = bot {
+			entries = entries.filter(audit_log::bot_id.eq(bot));
+		}
+
+		if let Some(target) = target {
+			entries = entries.filter(audit_log::target.eq(target));
+		}
+
+		let data: Paginated<AuditEntry> = entries.pages_pagination(options).load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let entries = data
+			.into_iter()
+			.map(|entry| EncodableAuditEntry::from(entry, true))
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"entries": entries,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
@@ -0,0 +1,238 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::helpers::ok_true;
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::Paginate;
+use crate::models::audit::{AuditAction, NewAuditEntry};
+use crate::models::blocked_traffic::{BlockedTraffic, NewBlockedTraffic};
+use crate::schema::blocked_traffic;
+use crate::task::spawn_blocking;
+use crate::util::errors::{
+	bad_request, invalid_field, not_found, AppResult, BoxedAppError, ErrorCode,
+};
+use crate::util::{rfc3339, RequestUtils};
+use crate::views::EncodableBlockedTraffic;
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::response::Response;
+use axum::Json;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use ipnetwork::IpNetwork;
+use serde_json::Value;
+
+/// Handles the `GET /admin/blocklist` route.
+///
+/// Lists the entries of the blocklist, newest first, including the expired ones.
+pub async fn list(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder().gather(&req)?;
+
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		let data: Paginated<BlockedTraffic> = blocked_traffic::table
+			.select(BlockedTraffic::as_select())
+			.order(blocked_traffic::id.desc())
+			.pages_pagination(options)
+			.load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let entries = data
+			.into_iter()
+			.map(EncodableBlockedTraffic::from)
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"entries": entries,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestNewBlockedTraffic {
+	/// A single address or a CIDR block, e.g. `192.0.2.0/24`.
+	pub ip_range: Option<String>,
+	/// Requests whose user agent contains this string are blocked.
+	pub user_agent: Option<String>,
+	pub reason: String,
+	#[serde(default, with = "rfc3339::option")]
+	pub expires_at: Option<NaiveDateTime>,
+}
+
+/// Handles the `POST /admin/blocklist` route.
+///
+/// The entry is enforced right away by this instance, and by the other
+/// instances on their next refresh.
+pub async fn create(
+	app: AppState,
+	parts: Parts,
+	Json(new): Json<RequestNewBlockedTraffic>,
+) -> AppResult<Json<Value>> {
+	let ip_range = new
+		.ip_range
+		.as_deref()
+		.map(|ip_range| ip_range.trim().parse::<IpNetwork>())
+		.transpose()
+		.map_err(|_| {
+			invalid_field(
+				"/ip_range",
+				ErrorCode::InvalidFormat,
+				"ip_range must be an IPv4 or IPv6 address or CIDR block",
+			)
+		})?;
+
+	if let Some(ip_range) = ip_range {
+		let (min_prefix, family) = match ip_range {
+			IpNetwork::V4(_) => (BlockedTraffic::MIN_IPV4_PREFIX, "IPv4"),
+			IpNetwork::V6(_) => (BlockedTraffic::MIN_IPV6_PREFIX, "IPv6"),
+		};
+
+		if ip_range.prefix() < min_prefix {
+			return Err(invalid_field(
+				"/ip_range",
+				ErrorCode::OutOfRange,
+				format!("{family} blocks must have a prefix of at least /{min_prefix}"),
+			));
+		}
+	}
+
+	let user_agent = new
+		.user_agent
+		.as_deref()
+		.map(str::trim)
+		.filter(|user_agent| !user_agent.is_empty());
+
+	if ip_range.is_some() == user_agent.is_some() {
+		return Err(bad_request(
+			"exactly one of ip_range and user_agent must be given",
+		));
+	}
+
+	if user_agent.is_some_and(|user_agent| {
+		user_agent.chars().count() < BlockedTraffic::MIN_USER_AGENT_LENGTH
+	}) {
+		return Err(invalid_field(
+			"/user_agent",
+			ErrorCode::OutOfRange,
+			format!(
+				"user_agent must be at least {} characters long",
+				BlockedTraffic::MIN_USER_AGENT_LENGTH
+			),
+		));
+	}
+
+	let reason = new.reason.trim();
+	if reason.is_empty() || reason.chars().count() > BlockedTraffic::MAX_REASON_LENGTH {
+		let code = match reason.is_empty() {
+			true => ErrorCode::MissingValue,
+			false => ErrorCode::TooLong,
+		};
+
+		return Err(invalid_field(
+			"/reason",
+			code,
+			format!(
+				"reason must be between 1 and {} characters long",
+				BlockedTraffic::MAX_REASON_LENGTH
+			),
+		));
+	}
+
+	let reason = reason.to_string();
+	let user_agent = user_agent.map(ToString::to_string);
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie()
+			.require_staff()
+			.check(&parts, conn)?;
+
+		let entry = conn.transaction(|conn| {
+			let entry = NewBlockedTraffic {
+				ip_range,
+				user_agent: user_agent.as_deref(),
+				reason: &reason,
+				created_by: &auth.user().id,
+				expires_at: new.expires_at,
+			}
+			.insert(conn)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BlocklistAdd,
+				format!("blocklist:{}", entry.id),
+			)
+			.after(json!({
+				"ip_range": entry.ip_range.map(|ip_range| ip_range.to_string()),
+				"user_agent": entry.user_agent,
+				"reason": entry.reason,
+				"expires_at": entry.expires_at,
+			}))
+			.insert(conn)?;
+
+			Ok::<_, BoxedAppError>(entry)
+		})?;
+
+		app.blocklist.refresh(&app.config, conn)?;
+
+		Ok(Json(
+			json!({ "entry": EncodableBlockedTraffic::from(entry) }),
+		))
+	})
+	.await
+}
+
+/// Handles the `DELETE /admin/blocklist/:id` route.
+pub async fn delete(app: AppState, Path(id): Path<i32>, req: Parts) -> AppResult<Response> {
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		conn.transaction(|conn| {
+			let entry: BlockedTraffic = diesel::delete(blocked_traffic::table.find(id))
+				.returning(BlockedTraffic::as_returning())
+				.get_result(conn)
+				.optional()?
+				.ok_or_else(not_found)?;
+
+			NewAuditEntry::new(
+				&req,
+				&auth,
+				AuditAction::BlocklistRemove,
+				format!("blocklist:{id}"),
+			)
+			.before(json!({
+				"ip_range": entry.ip_range.map(|ip_range| ip_range.to_string()),
+				"user_agent": entry.user_agent,
+				"reason": entry.reason,
+				"expires_at": entry.expires_at,
+			}))
+			.insert(conn)?;
+
+			Ok::<_, BoxedAppError>(())
+		})?;
+
+		app.blocklist.refresh(&app.config, conn)?;
+
+		ok_true()
+	})
+	.await
+}
@@ -0,0 +1,248 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::Paginate;
+use crate::models::audit::{AuditAction, NewAuditEntry};
+use crate::models::bot::BotStatus;
+use crate::models::{Bot, BotDenial, BotReport, BotResubmission, User};
+use crate::schema::{bot_denials, bot_reports, bot_resubmissions, bots};
+use crate::task::spawn_blocking;
+use crate::util::errors::{bot_not_found, invalid_field, AppResult, BoxedAppError, ErrorCode};
+use crate::util::RequestUtils;
+use crate::views::{
+	EncodableBot, EncodableBotDenial, EncodableBotOwner, EncodableBotReport,
+	EncodableBotResubmission,
+};
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// Handles the `GET /admin/bots/:bot_id` route.
+///
+/// Shows everything the staff team needs to moderate a bot, regardless of its status.
+pub async fn show(app: AppState, Path(bot_id): Path<String>, req: Parts) -> AppResult<Json<Value>> {
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		let owners = User::owning(&bot, conn)?
+			.into_iter()
+			.map(EncodableBotOwner::from)
+			.collect::<Vec<_>>();
+
+		let reports = BotReport::belonging_to(&bot)
+			.select(BotReport::as_select())
+			.order(bot_reports::id.desc())
+			.load(conn)?
+			.into_iter()
+			.map(EncodableBotReport::from)
+			.collect::<Vec<_>>();
+
+		let denials = BotDenial::belonging_to(&bot)
+			.select(BotDenial::as_select())
+			.order(bot_denials::id.desc())
+			.load(conn)?
+			.into_iter()
+			.map(EncodableBotDenial::from)
+			.collect::<Vec<_>>();
+
+		let resubmissions = BotResubmission::belonging_to(&bot)
+			.select(BotResubmission::as_select())
+			.order(bot_resubmissions::id.desc())
+			.load(conn)?
+			.into_iter()
+			.map(EncodableBotResubmission::from)
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"bot": EncodableBot::from_minimal(bot),
+			"owners": owners,
+			"reports": reports,
+			"denials": denials,
+			"resubmissions": resubmissions,
+		})))
+	})
+	.await
+}
+
+/// Handles the `GET /admin/queue` route.
+///
+/// Lists the bots waiting for review, oldest first, together with their
+/// previous denials and the appeal of their latest resubmission.
+pub async fn queue(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder().gather(&req)?;
+
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		let data: Paginated<Bot> = bots::table
+			.filter(bots::status.eq(BotStatus::PENDING))
+			.select(Bot::as_select())
+			.order(bots::created_at.asc())
+			.pages_pagination(options)
+			.load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let bots = data.into_iter().collect::<Vec<_>>();
+
+		let denials = BotDenial::belonging_to(&bots)
+			.select(BotDenial::as_select())
+			.order(bot_denials::id.desc())
+			.load(conn)?
+			.grouped_by(&bots);
+
+		let resubmissions = BotResubmission::belonging_to(&bots)
+			.select(BotResubmission::as_select())
+			.order(bot_resubmissions::id.desc())
+			.load(conn)?
+			.grouped_by(&bots);
+
+		let queue = bots
+			.into_iter()
+			.zip(denials)
+			.zip(resubmissions)
+			.map(|((bot, denials), resubmissions)| {
+				let denials = denials
+					.into_iter()
+					.map(EncodableBotDenial::from)
+					.collect::<Vec<_>>();
+				let resubmission = resubmissions
+					.into_iter()
+					.next()
+					.map(EncodableBotResubmission::from);
+
+				json!({
+					"bot": EncodableBot::from_minimal(bot),
+					"denials": denials,
+					"resubmission": resubmission,
+				})
+			})
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"queue": queue,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestBotStatus {
+	pub status: String,
+	/// Required when denying a bot, shown to its owners.
+	pub reason: Option<String>,
+}
+
+/// Handles the `PATCH /admin/bots/:bot_id/status` route.
+pub async fn update_status(
+	app: AppState,
+	Path(bot_id): Path<String>,
+	parts: Parts,
+	Json(update): Json<RequestBotStatus>,
+) -> AppResult<Json<Value>> {
+	let status: BotStatus = update.status.parse()?;
+
+	let reason = update
+		.reason
+		.as_deref()
+		.map(str::trim)
+		.filter(|reason| !reason.is_empty())
+		.map(ToString::to_string);
+
+	if reason
+		.as_ref()
+		.is_some_and(|reason| reason.chars().count() > BotDenial::MAX_REASON_LENGTH)
+	{
+		return Err(invalid_field(
+			"/reason",
+			ErrorCode::TooLong,
+			format!(
+				"reason must be at most {} characters long",
+				BotDenial::MAX_REASON_LENGTH
+			),
+		));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie()
+			.require_staff()
+			.check(&parts, conn)?;
+		let reviewer = auth.user();
+
+		let denial = conn.transaction(|conn| {
+			// Locked until the end of the transaction, so that the previous
+			// status in the audit log can't be changed concurrently.
+			let bot: Bot = Bot::by_id(&bot_id)
+				.for_update()
+				.first(conn)
+				.optional()?
+				.ok_or_else(|| bot_not_found(&bot_id))?;
+
+			let denial = match status {
+				BotStatus::DENIED => {
+					let reason = reason.as_deref().ok_or_else(|| {
+						invalid_field(
+							"/reason",
+							ErrorCode::MissingValue,
+							"a reason is required to deny a bot",
+						)
+					})?;
+
+					Some(BotDenial::deny(conn, &bot.id, &reviewer.id, reason)?)
+				}
+				status => {
+					diesel::update(&bot)
+						.set(bots::status.eq(status))
+						.execute(conn)?;
+
+					None
+				}
+			};
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BotStatusChange,
+				format!("bot:{}", bot.id),
+			)
+			.bot(&bot.id)
+			.before(json!({ "status": String::from(bot.status) }))
+			.after(json!({ "status": String::from(status), "reason": reason }))
+			.insert(conn)?;
+
+			Ok::<_, BoxedAppError>(denial)
+		})?;
+
+		let bot: Bot = Bot::by_id(&bot_id).first(conn)?;
+
+		Ok(Json(json!({
+			"bot": EncodableBot::from_minimal(bot),
+			"denial": denial.map(EncodableBotDenial::from),
+		})))
+	})
+	.await
+}
@@ -0,0 +1,267 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::helpers::ok_true;
+use crate::models::audit::{AuditAction, NewAuditEntry};
+use crate::models::category::NewCategory;
+use crate::models::Category;
+use crate::schema::{bots_categories, categories};
+use crate::task::spawn_blocking;
+use crate::util::errors::{
+	api_error, invalid_field, not_found, AppResult, BoxedAppError, ErrorCode,
+};
+use crate::views::EncodableCategory;
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::response::Response;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// The maximum length of the description of a category.
+const MAX_DESCRIPTION_LENGTH: usize = 500;
+
+#[derive(Deserialize)]
+pub struct RequestNewCategory {
+	/// Full name of the category, e.g. `Moderation::Auto Moderation`.
+	pub category: String,
+	/// Full slug of the category, e.g. `moderation::auto-moderation`.
+	pub slug: String,
+	pub description: String,
+}
+
+/// Handles the `POST /admin/categories` route.
+pub async fn create(
+	app: AppState,
+	parts: Parts,
+	Json(new): Json<RequestNewCategory>,
+) -> AppResult<Json<Value>> {
+	let slug = new.slug.trim().to_string();
+	if !Category::is_valid_slug(&slug) {
+		return Err(invalid_field("/slug", ErrorCode::InvalidFormat, format!(
+			"slug must be made of `::`-separated lowercase letters, digits and dashes, and be at most {} characters long",
+			Category::MAX_LENGTH
+		)));
+	}
+
+	let name = new.category.trim().to_string();
+	validate_name(&name, &slug)?;
+	validate_description(&new.description)?;
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie()
+			.require_staff()
+			.check(&parts, conn)?;
+
+		let category = conn.transaction(|conn| {
+			if let Some(parent) = Category::parent_slug(&slug) {
+				let parent: Option<Category> = Category::by_slug(parent).first(conn).optional()?;
+				if parent.is_none() {
+					return Err(api_error(
+						ErrorCode::ParentCategoryNotFound,
+						"the parent category does not exist",
+					));
+				}
+			}
+
+			let existing: Option<Category> = Category::by_slug(&slug).first(conn).optional()?;
+			if existing.is_some() {
+				return Err(api_error(
+					ErrorCode::CategoryAlreadyExists,
+					format!("category `{slug}` already exists"),
+				));
+			}
+
+			let category = NewCategory {
+				category: &name,
+				slug: &slug,
+				description: &new.description,
+			}
+			.upsert(conn)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::CategoryCreate,
+				format!("category:{slug}"),
+			)
+			.after(json!({
+				"category": category.category,
+				"description": category.description,
+			}))
+			.insert(conn)?;
+
+			Ok::<_, BoxedAppError>(category)
+		})?;
+
+		Ok(Json(
+			json!({ "category": EncodableCategory::from(category) }),
+		))
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestCategoryUpdate {
+	pub category: Option<String>,
+	pub description: Option<String>,
+}
+
+/// Handles the `PATCH /admin/categories/:slug` route.
+///
+/// Renames a category or changes its description. Slugs can't be changed,
+/// since bots and links refer to them.
+pub async fn update(
+	app: AppState,
+	Path(slug): Path<String>,
+	parts: Parts,
+	Json(update): Json<RequestCategoryUpdate>,
+) -> AppResult<Json<Value>> {
+	let name = update.category.map(|name| name.trim().to_string());
+	if let Some(name) = &name {
+		validate_name(name, &slug)?;
+	}
+
+	if let Some(description) = &update.description {
+		validate_description(description)?;
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie()
+			.require_staff()
+			.check(&parts, conn)?;
+
+		let category = conn.transaction(|conn| {
+			let existing: Category = Category::by_slug(&slug)
+				.first(conn)
+				.optional()?
+				.ok_or_else(not_found)?;
+
+			let description = existing.description.clone().unwrap_or_default();
+			let category = NewCategory {
+				category: name.as_deref().unwrap_or(&existing.category),
+				slug: &existing.slug,
+				description: update.description.as_deref().unwrap_or(&description),
+			}
+			.upsert(conn)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::CategoryUpdate,
+				format!("category:{}", existing.slug),
+			)
+			.before(json!({
+				"category": existing.category,
+				"description": existing.description,
+			}))
+			.after(json!({
+				"category": category.category,
+				"description": category.description,
+			}))
+			.insert(conn)?;
+
+			Ok::<_, BoxedAppError>(category)
+		})?;
+
+		Ok(Json(
+			json!({ "category": EncodableCategory::from(category) }),
+		))
+	})
+	.await
+}
+
+/// Handles the `DELETE /admin/categories/:slug` route.
+///
+/// Categories with subcategories have to be emptied first. Bots in the
+/// category are removed from it.
+pub async fn delete(app: AppState, Path(slug): Path<String>, req: Parts) -> AppResult<Response> {
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		conn.transaction(|conn| {
+			let category: Category = Category::by_slug(&slug)
+				.first(conn)
+				.optional()?
+				.ok_or_else(not_found)?;
+
+			if !Category::with_subcategories(conn, &[&category.slug])?.is_empty() {
+				return Err(api_error(
+					ErrorCode::CategoryHasSubcategories,
+					"categories with subcategories can't be deleted, delete the subcategories first",
+				));
+			}
+
+			diesel::delete(bots_categories::table)
+				.filter(bots_categories::category_id.eq(category.id))
+				.execute(conn)?;
+
+			diesel::delete(categories::table.find(category.id)).execute(conn)?;
+
+			NewAuditEntry::new(
+				&req,
+				&auth,
+				AuditAction::CategoryDelete,
+				format!("category:{}", category.slug),
+			)
+			.before(json!({
+				"category": category.category,
+				"description": category.description,
+				"bots_cnt": category.bots_cnt,
+			}))
+			.insert(conn)?;
+
+			Ok::<_, BoxedAppError>(())
+		})?;
+
+		ok_true()
+	})
+	.await
+}
+
+/// Names have one `::`-separated segment for each segment of the slug.
+fn validate_name(name: &str, slug: &str) -> AppResult<()> {
+	let segments = name.split("::").map(str::trim).collect::<Vec<_>>();
+
+	if name.chars().count() > Category::MAX_LENGTH || segments.iter().any(|s| s.is_empty()) {
+		return Err(invalid_field(
+			"/category",
+			ErrorCode::InvalidFormat,
+			format!(
+			"category must be made of non-empty `::`-separated names, and be at most {} characters long",
+			Category::MAX_LENGTH
+		),
+		));
+	}
+
+	if segments.len() != slug.split("::").count() {
+		return Err(invalid_field(
+			"/category",
+			ErrorCode::InvalidFormat,
+			"category must have as many `::`-separated names as the slug",
+		));
+	}
+
+	Ok(())
+}
+
+fn validate_description(description: &str) -> AppResult<()> {
+	if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+		return Err(invalid_field(
+			"/description",
+			ErrorCode::TooLong,
+			format!("description must be at most {MAX_DESCRIPTION_LENGTH} characters long"),
+		));
+	}
+
+	Ok(())
+}
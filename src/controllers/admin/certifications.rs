@@ -0,0 +1,182 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::Paginate;
+use crate::models::audit::{AuditAction, NewAuditEntry};
+use crate::models::certification::{
+	ApplicationStatus, CertificationApplication, CertificationEligibility,
+};
+use crate::models::Bot;
+use crate::schema::{bots, certification_applications};
+use crate::task::spawn_blocking;
+use crate::util::errors::{
+	api_error, invalid_field, not_found, AppResult, BoxedAppError, ErrorCode,
+};
+use crate::util::RequestUtils;
+use crate::views::{
+	EncodableBot, EncodableCertificationApplication, EncodableCertificationEligibility,
+};
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// The maximum length of the notes of a reviewer.
+const MAX_REASON_LENGTH: usize = 2000;
+
+/// Handles the `GET /admin/certifications` route.
+///
+/// Lists the applications waiting for review, oldest first, together with
+/// the current eligibility of each bot.
+pub async fn list(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder().gather(&req)?;
+
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		let data: Paginated<(CertificationApplication, Bot)> = certification_applications::table
+			.inner_join(bots::table)
+			.filter(certification_applications::status.eq(ApplicationStatus::PENDING))
+			.select((certification_applications::all_columns, bots::all_columns))
+			.order(certification_applications::id.asc())
+			.pages_pagination(options)
+			.load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let applications = data
+			.into_iter()
+			.map(|(application, bot)| {
+				let eligibility = CertificationEligibility::for_bot(conn, &bot)?;
+
+				Ok(json!({
+					"application": EncodableCertificationApplication::from(application),
+					"bot": EncodableBot::from_minimal(bot),
+					"eligibility": EncodableCertificationEligibility::from(eligibility),
+				}))
+			})
+			.collect::<QueryResult<Vec<_>>>()?;
+
+		Ok(Json(json!({
+			"applications": applications,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestCertificationReview {
+	pub status: String,
+	pub reason: Option<String>,
+}
+
+/// Handles the `PATCH /admin/certifications/:application_id` route.
+pub async fn review(
+	app: AppState,
+	Path(application_id): Path<i32>,
+	parts: Parts,
+	Json(review): Json<RequestCertificationReview>,
+) -> AppResult<Json<Value>> {
+	let status: ApplicationStatus = review.status.parse()?;
+	if status == ApplicationStatus::PENDING {
+		return Err(invalid_field(
+			"/status",
+			ErrorCode::InvalidValue,
+			"applications can only be approved or denied",
+		));
+	}
+
+	let reason = review
+		.reason
+		.as_deref()
+		.map(str::trim)
+		.filter(|reason| !reason.is_empty())
+		.map(ToString::to_string);
+
+	if reason
+		.as_ref()
+		.is_some_and(|reason| reason.chars().count() > MAX_REASON_LENGTH)
+	{
+		return Err(invalid_field(
+			"/reason",
+			ErrorCode::TooLong,
+			format!("reason must be at most {MAX_REASON_LENGTH} characters long"),
+		));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie()
+			.require_staff()
+			.check(&parts, conn)?;
+		let reviewer = auth.user();
+
+		let application = conn.transaction(|conn| {
+			// The application and its bot are locked until the end of the
+			// transaction, so that concurrent reviews and status changes
+			// can't interleave with this one.
+			let application: CertificationApplication = certification_applications::table
+				.find(application_id)
+				.for_update()
+				.select(CertificationApplication::as_select())
+				.first(conn)
+				.optional()?
+				.ok_or_else(not_found)?;
+
+			if application.status != ApplicationStatus::PENDING {
+				return Err(api_error(
+					ErrorCode::ApplicationAlreadyReviewed,
+					"this application was already reviewed",
+				));
+			}
+
+			if status == ApplicationStatus::APPROVED {
+				let bot: Bot = Bot::by_id(&application.bot_id).for_update().first(conn)?;
+
+				if !CertificationEligibility::for_bot(conn, &bot)?.is_eligible() {
+					return Err(api_error(
+						ErrorCode::CertificationRequirementsNotMet,
+						"the bot doesn't meet the certification requirements anymore",
+					));
+				}
+			}
+
+			let application = application.review(conn, &reviewer.id, status, reason.as_deref())?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BotCertificationReview,
+				format!("certification:{}", application.id),
+			)
+			.bot(&application.bot_id)
+			.before(json!({ "status": String::from(ApplicationStatus::PENDING) }))
+			.after(json!({
+				"status": String::from(application.status),
+				"reason": application.reason,
+			}))
+			.insert(conn)?;
+
+			Ok::<_, BoxedAppError>(application)
+		})?;
+
+		Ok(Json(json!({
+			"application": EncodableCertificationApplication::from(application),
+		})))
+	})
+	.await
+}
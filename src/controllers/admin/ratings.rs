@@ -0,0 +1,250 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::Paginate;
+use crate::models::audit::{AuditAction, NewAuditEntry};
+use crate::models::rating::ModerationAction;
+use crate::models::{BotRating, BotRatingAction, BotRatingFlag, BotRatingReply, User};
+use crate::schema::{bot_rating_actions, bot_rating_flags, bot_ratings, users};
+use crate::task::spawn_blocking;
+use crate::util::errors::{invalid_field, not_found, AppResult, ErrorCode};
+use crate::util::RequestUtils;
+use crate::views::{EncodableBotRating, EncodableBotRatingAction, EncodableBotRatingFlag};
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::Json;
+use diesel::dsl::exists;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// The maximum length of the reason of a moderation action.
+const MAX_REASON_LENGTH: usize = 500;
+
+/// Handles the `GET /admin/ratings/flags` route.
+///
+/// Lists the ratings with pending flags, oldest first.
+pub async fn flags(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder().gather(&req)?;
+
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		let pending_flags = bot_rating_flags::table
+			.filter(bot_rating_flags::rating_id.eq(bot_ratings::id))
+			.filter(bot_rating_flags::resolved_at.is_null());
+
+		let data: Paginated<(BotRating, User)> = bot_ratings::table
+			.inner_join(users::table)
+			.filter(exists(pending_flags))
+			.select((bot_ratings::all_columns, users::all_columns))
+			.order(bot_ratings::id.asc())
+			.pages_pagination(options)
+			.load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let (ratings, users): (Vec<BotRating>, Vec<User>) = data.into_iter().unzip();
+
+		let flags = BotRatingFlag::belonging_to(&ratings)
+			.filter(bot_rating_flags::resolved_at.is_null())
+			.select(BotRatingFlag::as_select())
+			.order(bot_rating_flags::created_at.asc())
+			.load(conn)?
+			.grouped_by(&ratings);
+
+		let queue = ratings
+			.into_iter()
+			.zip(users)
+			.zip(flags)
+			.map(|((rating, user), flags)| {
+				json!({
+					"rating": EncodableBotRating::from(rating, user, None, &app.storage),
+					"flags": flags.into_iter().map(EncodableBotRatingFlag::from).collect::<Vec<_>>(),
+				})
+			})
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"queue": queue,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
+
+/// Handles the `GET /admin/ratings/:rating_id` route.
+///
+/// Shows a rating together with all of its flags and its moderation history.
+pub async fn show(app: AppState, Path(rating_id): Path<i32>, req: Parts) -> AppResult<Json<Value>> {
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		let (rating, user): (BotRating, User) = bot_ratings::table
+			.find(rating_id)
+			.inner_join(users::table)
+			.select((bot_ratings::all_columns, users::all_columns))
+			.first(conn)
+			.optional()?
+			.ok_or_else(not_found)?;
+
+		let reply = BotRatingReply::belonging_to(&rating)
+			.select(BotRatingReply::as_select())
+			.first(conn)
+			.optional()?;
+
+		let flags = BotRatingFlag::belonging_to(&rating)
+			.select(BotRatingFlag::as_select())
+			.order(bot_rating_flags::created_at.desc())
+			.load(conn)?
+			.into_iter()
+			.map(EncodableBotRatingFlag::from)
+			.collect::<Vec<_>>();
+
+		// Including the actions on the previous ratings of the user for the
+		// bot, deleted since.
+		let actions = bot_rating_actions::table
+			.filter(bot_rating_actions::bot_id.eq(&rating.bot_id))
+			.filter(bot_rating_actions::user_id.eq(&rating.user_id))
+			.left_join(users::table)
+			.select((
+				bot_rating_actions::all_columns,
+				users::all_columns.nullable(),
+			))
+			.order(bot_rating_actions::id.desc())
+			.load::<(BotRatingAction, Option<User>)>(conn)?
+			.into_iter()
+			.map(|(action, actor)| EncodableBotRatingAction::from(action, actor, &app.storage))
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"rating": EncodableBotRating::from(rating, user, reply, &app.storage),
+			"flags": flags,
+			"actions": actions,
+		})))
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestModeration {
+	pub reason: String,
+}
+
+/// Handles the `POST /admin/ratings/:rating_id/hide` route.
+pub async fn hide(
+	app: AppState,
+	path: Path<i32>,
+	parts: Parts,
+	json: Json<RequestModeration>,
+) -> AppResult<Json<Value>> {
+	moderate(app, path, parts, json, ModerationAction::HIDE).await
+}
+
+/// Handles the `POST /admin/ratings/:rating_id/restore` route.
+pub async fn restore(
+	app: AppState,
+	path: Path<i32>,
+	parts: Parts,
+	json: Json<RequestModeration>,
+) -> AppResult<Json<Value>> {
+	moderate(app, path, parts, json, ModerationAction::RESTORE).await
+}
+
+/// Handles the `POST /admin/ratings/:rating_id/dismiss` route.
+///
+/// Resolves the pending flags of a rating without hiding it.
+pub async fn dismiss(
+	app: AppState,
+	path: Path<i32>,
+	parts: Parts,
+	json: Json<RequestModeration>,
+) -> AppResult<Json<Value>> {
+	moderate(app, path, parts, json, ModerationAction::DISMISS).await
+}
+
+async fn moderate(
+	app: AppState,
+	Path(rating_id): Path<i32>,
+	parts: Parts,
+	Json(new): Json<RequestModeration>,
+	action: ModerationAction,
+) -> AppResult<Json<Value>> {
+	let reason = new.reason.trim().to_string();
+	if reason.is_empty() {
+		return Err(invalid_field(
+			"/reason",
+			ErrorCode::MissingValue,
+			"reason must have a value",
+		));
+	}
+
+	if reason.chars().count() > MAX_REASON_LENGTH {
+		return Err(invalid_field(
+			"/reason",
+			ErrorCode::TooLong,
+			format!("reason must be at most {MAX_REASON_LENGTH} characters long"),
+		));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie()
+			.require_staff()
+			.check(&parts, conn)?;
+		let actor = auth.user();
+
+		let rating: BotRating = bot_ratings::table
+			.find(rating_id)
+			.select(BotRating::as_select())
+			.first(conn)
+			.optional()?
+			.ok_or_else(not_found)?;
+
+		let action = conn.transaction(|conn| {
+			let entry = rating.moderate(conn, &actor.id, action, &reason)?;
+
+			let hidden = match action {
+				ModerationAction::HIDE => true,
+				ModerationAction::RESTORE => false,
+				ModerationAction::DISMISS => rating.hidden,
+			};
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::RatingModerate,
+				format!("rating:{}", rating.id),
+			)
+			.bot(&rating.bot_id)
+			.before(json!({ "hidden": rating.hidden }))
+			.after(json!({
+				"hidden": hidden,
+				"action": String::from(action),
+				"reason": reason,
+			}))
+			.insert(conn)?;
+
+			QueryResult::Ok(entry)
+		})?;
+
+		Ok(Json(json!({
+			"action": EncodableBotRatingAction::from(action, Some(actor.clone()), &app.storage),
+		})))
+	})
+	.await
+}
@@ -0,0 +1,218 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::Paginate;
+use crate::models::audit::{AuditAction, NewAuditEntry};
+use crate::models::bot::BotStatus;
+use crate::models::report::{BotReportChanges, ReportReason, ReportStatus};
+use crate::models::{BotDenial, BotReport};
+use crate::schema::{bot_reports, bots};
+use crate::task::spawn_blocking;
+use crate::util::errors::{
+	api_error, invalid_field, not_found, AppResult, BoxedAppError, ErrorCode,
+};
+use crate::util::RequestUtils;
+use crate::views::EncodableBotReport;
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// The maximum length of the resolution notes of a report.
+const MAX_RESOLUTION_LENGTH: usize = 2000;
+
+/// Handles the `GET /admin/reports` route.
+///
+/// Lists the reports, oldest first. Only pending reports are listed unless
+/// `?status=` is given.
+pub async fn list(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder().gather(&req)?;
+
+	let query = req.query();
+	let status = query
+		.get("status")
+		.map(|s| s.parse::<ReportStatus>())
+		.transpose()?;
+	let reason = query
+		.get("reason")
+		.map(|s| s.parse::<ReportReason>())
+		.transpose()?;
+
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		AuthCheck::only_cookie().require_staff().check(&req, conn)?;
+
+		let mut reports = bot_reports::table
+			.select(BotReport::as_select())
+			.order(bot_reports::id.asc())
+			.into_boxed();
+
+		reports = match status {
+			Some(status) => reports.filter(bot_reports::status.eq(status)),
+			None => reports.filter(
+				bot_reports::status.eq_any([ReportStatus::OPEN, ReportStatus::INVESTIGATING]),
+			),
+		};
+
+		if let Some(reason) = reason {
+			reports = reports.filter(bot_reports::reason.eq(reason));
+		}
+
+		let data: Paginated<BotReport> = reports.pages_pagination(options).load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let reports = data
+			.into_iter()
+			.map(EncodableBotReport::from)
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"reports": reports,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestReportUpdate {
+	pub status: Option<String>,
+	pub resolution: Option<String>,
+	/// Changes the status of the reported bot as part of handling the report.
+	pub bot_status: Option<String>,
+}
+
+/// Handles the `PATCH /admin/reports/:report_id` route.
+pub async fn update(
+	app: AppState,
+	Path(report_id): Path<i32>,
+	parts: Parts,
+	Json(update): Json<RequestReportUpdate>,
+) -> AppResult<Json<Value>> {
+	let status = update
+		.status
+		.map(|s| s.parse::<ReportStatus>())
+		.transpose()?;
+	let bot_status = update
+		.bot_status
+		.map(|s| s.parse::<BotStatus>())
+		.transpose()?;
+
+	let resolution = update.resolution.map(|r| r.trim().to_string());
+	if resolution
+		.as_ref()
+		.is_some_and(|r| r.chars().count() > MAX_RESOLUTION_LENGTH)
+	{
+		return Err(invalid_field(
+			"/resolution",
+			ErrorCode::TooLong,
+			format!("resolution must be at most {MAX_RESOLUTION_LENGTH} characters long"),
+		));
+	}
+
+	if status.is_none() && resolution.is_none() && bot_status.is_none() {
+		return Err(api_error(ErrorCode::NothingToUpdate, "nothing to update"));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie()
+			.require_staff()
+			.check(&parts, conn)?;
+		let actor = auth.user();
+
+		let report = conn.transaction(|conn| {
+			let report: BotReport = bot_reports::table
+				.find(report_id)
+				.select(BotReport::as_select())
+				.for_update()
+				.first(conn)
+				.optional()?
+				.ok_or_else(not_found)?;
+
+			if let Some(bot_status) = bot_status {
+				let previous: BotStatus = bots::table
+					.find(&report.bot_id)
+					.select(bots::status)
+					.first(conn)?;
+
+				NewAuditEntry::new(
+					&parts,
+					&auth,
+					AuditAction::BotStatusChange,
+					format!("bot:{}", report.bot_id),
+				)
+				.bot(&report.bot_id)
+				.before(json!({ "status": String::from(previous) }))
+				.after(json!({ "status": String::from(bot_status), "report": report.id }))
+				.insert(conn)?;
+			}
+
+			match bot_status {
+				Some(BotStatus::DENIED) => {
+					let reason = resolution
+						.clone()
+						.unwrap_or_else(|| format!("Denied after report #{}", report.id));
+
+					BotDenial::deny(conn, &report.bot_id, &actor.id, &reason)?;
+				}
+				Some(bot_status) => {
+					diesel::update(bots::table.find(&report.bot_id))
+						.set(bots::status.eq(bot_status))
+						.execute(conn)?;
+				}
+				None => {}
+			}
+
+			let changes = BotReportChanges {
+				status,
+				handled_by: &actor.id,
+				resolution: resolution.as_deref(),
+				bot_status,
+			};
+
+			let updated: BotReport = diesel::update(&report)
+				.set(&changes)
+				.returning(BotReport::as_returning())
+				.get_result(conn)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::ReportUpdate,
+				format!("report:{}", report.id),
+			)
+			.bot(&report.bot_id)
+			.before(json!({
+				"status": String::from(report.status),
+				"resolution": report.resolution,
+			}))
+			.after(json!({
+				"status": String::from(updated.status),
+				"resolution": updated.resolution,
+				"bot_status": updated.bot_status.map(String::from),
+			}))
+			.insert(conn)?;
+
+			Ok::<_, BoxedAppError>(updated)
+		})?;
+
+		Ok(Json(json!({
+			"report": EncodableBotReport::from(report),
+		})))
+	})
+	.await
+}
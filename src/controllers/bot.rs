@@ -1,4 +1,12 @@
+pub mod audit;
+pub mod certification;
+pub mod images;
+pub mod invite;
 pub mod manage;
 pub mod metadata;
 pub mod owners;
+pub mod ratings;
+pub mod reports;
+pub mod search;
+pub mod translations;
 pub mod votes;
@@ -0,0 +1,72 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::Paginate;
+use crate::models::audit::AuditEntry;
+use crate::models::{Bot, BotOwner};
+use crate::schema::audit_log;
+use crate::task::spawn_blocking;
+use crate::util::errors::{api_error, bot_not_found, AppResult, ErrorCode};
+use crate::util::RequestUtils;
+use crate::views::EncodableAuditEntry;
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// Handles the `GET /bots/:bot_id/audit` route.
+///
+/// Lists the history of a bot, newest first. Only visible to its owners and
+/// the staff team.
+pub async fn list(app: AppState, Path(bot_id): Path<String>, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder().gather(&req)?;
+
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&req, conn)?;
+		let user = auth.user();
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		if !user.is_staff() && !BotOwner::is_owner(conn, &bot.id, &user.id)? {
+			return Err(api_error(
+				ErrorCode::NotBotOwner,
+				"only the owners of the bot can see its history",
+			));
+		}
+
+		let data: Paginated<AuditEntry> = audit_log::table
+			.filter(audit_log::bot_id.eq(&bot.id))
+			.select(audit_log::all_columns)
+			.order(audit_log::id.desc())
+			.pages_pagination(options)
+			.load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		// IP addresses of staff members are not shared with the owners
+		let entries = data
+			.into_iter()
+			.map(|entry| EncodableAuditEntry::from(entry, user.is_staff()))
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"entries": entries,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
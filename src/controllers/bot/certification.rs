@@ -0,0 +1,134 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::models::certification::{
+	ApplicationStatus, CertificationApplication, CertificationEligibility,
+};
+use crate::models::{Bot, BotOwner};
+use crate::task::spawn_blocking;
+use crate::util::errors::{api_error, bot_not_found, invalid_field, AppResult, ErrorCode};
+use crate::views::{EncodableCertificationApplication, EncodableCertificationEligibility};
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// Handles the `GET /bots/:bot_id/certification` route.
+///
+/// Shows whether the bot can apply for certification, and its latest application.
+pub async fn show(app: AppState, Path(bot_id): Path<String>, req: Parts) -> AppResult<Json<Value>> {
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&req, conn)?;
+		let user = auth.user();
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		if !user.is_staff() && !BotOwner::is_owner(conn, &bot.id, &user.id)? {
+			return Err(api_error(
+				ErrorCode::NotBotOwner,
+				"only the owners of the bot can see its certification status",
+			));
+		}
+
+		let eligibility = CertificationEligibility::for_bot(conn, &bot)?;
+		let application = CertificationApplication::latest(conn, &bot.id)?;
+
+		Ok(Json(json!({
+			"certified": bot.certified,
+			"eligibility": EncodableCertificationEligibility::from(eligibility),
+			"application": application.map(EncodableCertificationApplication::from),
+		})))
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestCertification {
+	pub message: Option<String>,
+}
+
+/// Handles the `POST /bots/:bot_id/certification` route.
+pub async fn apply(
+	app: AppState,
+	Path(bot_id): Path<String>,
+	parts: Parts,
+	Json(request): Json<RequestCertification>,
+) -> AppResult<Json<Value>> {
+	let message = request
+		.message
+		.as_deref()
+		.map(str::trim)
+		.filter(|message| !message.is_empty())
+		.map(ToString::to_string);
+
+	if message.as_ref().is_some_and(|message| {
+		message.chars().count() > CertificationApplication::MAX_MESSAGE_LENGTH
+	}) {
+		return Err(invalid_field(
+			"/message",
+			ErrorCode::TooLong,
+			format!(
+				"message must be at most {} characters long",
+				CertificationApplication::MAX_MESSAGE_LENGTH
+			),
+		));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&parts, conn)?;
+		let user = auth.user();
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		if !BotOwner::is_owner(conn, &bot.id, &user.id)? {
+			return Err(api_error(
+				ErrorCode::NotBotOwner,
+				"only the owners of the bot can apply for certification",
+			));
+		}
+
+		if bot.certified {
+			return Err(api_error(
+				ErrorCode::AlreadyCertified,
+				"this bot is already certified",
+			));
+		}
+
+		let latest = CertificationApplication::latest(conn, &bot.id)?;
+		if latest.is_some_and(|application| application.status == ApplicationStatus::PENDING) {
+			return Err(api_error(
+				ErrorCode::ApplicationPending,
+				"this bot already has an application waiting for review",
+			));
+		}
+
+		let eligibility = CertificationEligibility::for_bot(conn, &bot)?;
+		if !eligibility.is_eligible() {
+			return Err(api_error(
+				ErrorCode::CertificationRequirementsNotMet,
+				"this bot does not meet the requirements for certification yet",
+			));
+		}
+
+		let application =
+			CertificationApplication::create(conn, &bot.id, &user.id, message.as_deref())?;
+
+		Ok(Json(json!({
+			"application": EncodableCertificationApplication::from(application),
+		})))
+	})
+	.await
+}
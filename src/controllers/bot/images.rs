@@ -0,0 +1,292 @@
+use crate::app::AppState;
+use crate::auth::{AuthCheck, Authentication};
+use crate::controllers::helpers::bots::find_owned_bot;
+use crate::controllers::helpers::images::{discard, process, store};
+use crate::controllers::helpers::ok_true;
+use crate::models::audit::{AuditAction, NewAuditEntry};
+use crate::models::image::{ImageKind, NewImage, StoredImage};
+use crate::models::token::EndpointScope;
+use crate::models::util::diesel::Conn;
+use crate::models::{Bot, Image};
+use crate::rate_limiter::{LimitedAction, RateLimitKey};
+use crate::schema::{bots, images};
+use crate::task::spawn_blocking;
+use crate::util::errors::{api_error, bot_not_found, not_found, AppResult, ErrorCode};
+use crate::views::EncodableImage;
+use axum::body::Bytes;
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::response::Response;
+use axum::Json;
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// Handles the `PUT /bots/:bot_id/banner` route.
+///
+/// The body is the image file, which replaces the current banner of the bot.
+pub async fn update_banner(
+	app: AppState,
+	Path(bot_id): Path<String>,
+	parts: Parts,
+	body: Bytes,
+) -> AppResult<Json<Value>> {
+	let (auth, bot) = authorize_upload(&app, &parts, bot_id, ImageKind::BotBanner).await?;
+
+	let processed = process(ImageKind::BotBanner, body).await?;
+	let new_image = NewImage::for_bot(ImageKind::BotBanner, &bot.id, &processed);
+
+	let banner = new_image.variant_key(new_image.width);
+
+	let image = store(&app, new_image, processed, move |conn, new_image| {
+		conn.transaction(|conn| {
+			let previous = bot_banner(conn, &bot.id)?;
+			if let Some(previous) = &previous {
+				previous.delete(conn)?;
+			}
+
+			let image = new_image.insert(conn)?;
+
+			diesel::update(bots::table.find(&bot.id))
+				.set(bots::banner.eq(&banner))
+				.execute(conn)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BotBannerUpdate,
+				format!("bot:{}", bot.id),
+			)
+			.bot(&bot.id)
+			.before(json!({ "banner": bot.banner }))
+			.after(json!({ "banner": banner }))
+			.insert(conn)?;
+
+			Ok((image, previous))
+		})
+	})
+	.await?;
+
+	Ok(Json(json!({
+		"banner": EncodableImage::new(&image, &app.storage),
+	})))
+}
+
+/// Handles the `DELETE /bots/:bot_id/banner` route.
+pub async fn delete_banner(
+	app: AppState,
+	Path(bot_id): Path<String>,
+	parts: Parts,
+) -> AppResult<Response> {
+	let conn = app.db_write().await?;
+	let deleted = spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = check_auth(conn, &parts, &bot_id)?;
+		let bot = find_owned_bot(conn, &bot_id, &auth.user().id, "change its images")?;
+
+		conn.transaction(|conn| {
+			let banner = bot_banner(conn, &bot.id)?.ok_or_else(not_found)?;
+			banner.delete(conn)?;
+
+			diesel::update(bots::table.find(&bot.id))
+				.set(bots::banner.eq(None::<String>))
+				.execute(conn)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BotBannerDelete,
+				format!("bot:{}", bot.id),
+			)
+			.bot(&bot.id)
+			.before(json!({ "banner": bot.banner }))
+			.insert(conn)?;
+
+			AppResult::Ok(banner)
+		})
+	})
+	.await?;
+
+	discard(&app.storage, deleted.object_keys()).await;
+
+	ok_true()
+}
+
+/// Handles the `GET /bots/:bot_id/screenshots` route.
+pub async fn screenshots(app: AppState, Path(bot_id): Path<String>) -> AppResult<Json<Value>> {
+	let conn = app.db_read().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		let screenshots = Image::screenshots(conn, &bot.id)?
+			.iter()
+			.map(|image| EncodableImage::new(image, &app.storage))
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({ "screenshots": screenshots })))
+	})
+	.await
+}
+
+/// Handles the `POST /bots/:bot_id/screenshots` route.
+///
+/// The body is the image file, which is added at the end of the gallery.
+pub async fn add_screenshot(
+	app: AppState,
+	Path(bot_id): Path<String>,
+	parts: Parts,
+	body: Bytes,
+) -> AppResult<Json<Value>> {
+	let (auth, bot) = authorize_upload(&app, &parts, bot_id, ImageKind::BotScreenshot).await?;
+
+	let processed = process(ImageKind::BotScreenshot, body).await?;
+	let new_image = NewImage::for_bot(ImageKind::BotScreenshot, &bot.id, &processed);
+
+	let image = store(&app, new_image, processed, move |conn, new_image| {
+		conn.transaction(|conn| {
+			check_screenshot_count(conn, &bot.id)?;
+
+			let last_position = Image::of_bot(&bot.id, ImageKind::BotScreenshot)
+				.select(diesel::dsl::max(images::position))
+				.first::<Option<i32>>(conn)?;
+
+			let image = NewImage {
+				position: last_position.map_or(0, |position| position + 1),
+				..new_image.clone()
+			}
+			.insert(conn)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BotScreenshotAdd,
+				format!("bot:{}", bot.id),
+			)
+			.bot(&bot.id)
+			.after(json!({ "screenshot": image.id }))
+			.insert(conn)?;
+
+			Ok((image, None))
+		})
+	})
+	.await?;
+
+	Ok(Json(json!({
+		"screenshot": EncodableImage::new(&image, &app.storage),
+	})))
+}
+
+/// Handles the `DELETE /bots/:bot_id/screenshots/:image_id` route.
+pub async fn delete_screenshot(
+	app: AppState,
+	Path((bot_id, image_id)): Path<(String, i32)>,
+	parts: Parts,
+) -> AppResult<Response> {
+	let conn = app.db_write().await?;
+	let deleted = spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = check_auth(conn, &parts, &bot_id)?;
+		let bot = find_owned_bot(conn, &bot_id, &auth.user().id, "change its images")?;
+
+		conn.transaction(|conn| {
+			let screenshot: Image = Image::of_bot(&bot.id, ImageKind::BotScreenshot)
+				.filter(images::id.eq(image_id))
+				.select(Image::as_select())
+				.first(conn)
+				.optional()?
+				.ok_or_else(not_found)?;
+
+			screenshot.delete(conn)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BotScreenshotDelete,
+				format!("bot:{}", bot.id),
+			)
+			.bot(&bot.id)
+			.before(json!({ "screenshot": screenshot.id }))
+			.insert(conn)?;
+
+			AppResult::Ok(screenshot)
+		})
+	})
+	.await?;
+
+	discard(&app.storage, deleted.object_keys()).await;
+
+	ok_true()
+}
+
+/// Checks that the user can upload an image of the kind to the bot, before
+/// the image is processed.
+async fn authorize_upload(
+	app: &AppState,
+	parts: &Parts,
+	bot_id: String,
+	kind: ImageKind,
+) -> AppResult<(Authentication, Bot)> {
+	let app = app.clone();
+	let parts = parts.clone();
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = check_auth(conn, &parts, &bot_id)?;
+		let bot = find_owned_bot(conn, &bot_id, &auth.user().id, "change its images")?;
+
+		if kind == ImageKind::BotScreenshot {
+			check_screenshot_count(conn, &bot.id)?;
+		}
+
+		app.rate_limiter.check_rate_limit(
+			RateLimitKey::User(&auth.user().id),
+			LimitedAction::ImageUpload,
+			conn,
+		)?;
+
+		Ok((auth, bot))
+	})
+	.await
+}
+
+fn check_auth(conn: &mut impl Conn, parts: &Parts, bot_id: &str) -> AppResult<Authentication> {
+	AuthCheck::default()
+		.with_endpoint_scope(EndpointScope::PublishUpdate)
+		.for_bot(bot_id)
+		.check(parts, conn)
+}
+
+fn bot_banner(conn: &mut impl Conn, bot_id: &str) -> QueryResult<Option<Image>> {
+	Image::of_bot(bot_id, ImageKind::BotBanner)
+		.select(Image::as_select())
+		.first(conn)
+		.optional()
+}
+
+fn check_screenshot_count(conn: &mut impl Conn, bot_id: &str) -> AppResult<()> {
+	let count: i64 = Image::of_bot(bot_id, ImageKind::BotScreenshot)
+		.select(count_star())
+		.first(conn)?;
+
+	if count >= Image::MAX_SCREENSHOTS {
+		return Err(api_error(
+			ErrorCode::TooManyScreenshots,
+			format!(
+				"a bot can have at most {} screenshots",
+				Image::MAX_SCREENSHOTS
+			),
+		));
+	}
+
+	Ok(())
+}
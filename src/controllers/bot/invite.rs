@@ -0,0 +1,88 @@
+use crate::app::AppState;
+use crate::discord::invite::{
+	BotInvite, Permissions, SCOPES, SCOPE_APPLICATIONS_COMMANDS, SCOPE_BOT,
+};
+use crate::models::Bot;
+use crate::util::errors::{api_error, AppResult, ErrorCode};
+use crate::util::RequestUtils;
+use crate::views::EncodableBotInvite;
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::Json;
+use serde_json::Value;
+
+/// Handles the `GET /bots/:bot_id/invite` route.
+///
+/// Generates an invite link for the bot. `?permissions=` takes either a
+/// comma-separated list of permission names, like `SEND_MESSAGES,EMBED_LINKS`,
+/// or a permission bitfield. `?scopes=` takes a comma-separated list of
+/// scopes, and defaults to `bot,applications.commands`.
+pub async fn generate(
+	state: AppState,
+	Path(id): Path<String>,
+	req: Parts,
+) -> AppResult<Json<Value>> {
+	let query = req.query();
+
+	let permissions = match query.get("permissions").map(|p| p.trim()) {
+		None | Some("") => Permissions::default(),
+		Some(bits) if bits.starts_with(|c: char| c.is_ascii_digit()) => {
+			let bits = bits.parse().map_err(|_| {
+				api_error(
+					ErrorCode::InvalidValue,
+					format!("`{bits}` is not a valid permission bitfield"),
+				)
+			})?;
+			Permissions::from_bits(bits)
+		}
+		Some(names) => {
+			Permissions::from_names(names.split(',').map(str::trim)).map_err(|name| {
+				api_error(
+					ErrorCode::InvalidValue,
+					format!("`{name}` is not a Discord permission"),
+				)
+			})?
+		}
+	};
+
+	let scopes = match query.get("scopes") {
+		Some(scopes) => scopes
+			.split(',')
+			.map(str::trim)
+			.filter(|scope| !scope.is_empty())
+			.map(String::from)
+			.collect(),
+		None => vec![SCOPE_BOT.into(), SCOPE_APPLICATIONS_COMMANDS.into()],
+	};
+
+	if let Some(scope) = scopes
+		.iter()
+		.find(|scope| !SCOPES.contains(&scope.as_str()))
+	{
+		let allowed = SCOPES.join("`, `");
+		return Err(api_error(
+			ErrorCode::InvalidValue,
+			format!("`{scope}` is not a valid scope, the allowed scopes are `{allowed}`"),
+		));
+	}
+
+	if scopes.is_empty() {
+		return Err(api_error(
+			ErrorCode::MissingValue,
+			"at least one scope must be given",
+		));
+	}
+
+	let mut conn = state.db_read().await?;
+	let bot = Bot::find(&mut conn, &id).await?;
+
+	let invite = BotInvite {
+		client_id: bot.id,
+		scopes,
+		permissions,
+	};
+
+	Ok(Json(
+		json!({ "invite": EncodableBotInvite::new(invite.url(), invite) }),
+	))
+}
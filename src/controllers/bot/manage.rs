@@ -1,19 +1,31 @@
 use crate::app::AppState;
 use crate::auth::AuthCheck;
-use crate::middleware::log_request::{RequestLog, RequestLogExt};
-use crate::models::bot::{NewBot, NewBotBuilder};
+use crate::controllers::helpers::validation::{Rule, Validator};
+use crate::discord;
+use crate::discord::invite::{BotInvite, Permissions};
+use crate::markdown;
+use crate::middleware::log_request::RequestLogExt;
+use crate::models::audit::{AuditAction, NewAuditEntry};
+use crate::models::bot::{BotChanges, BotStatus, NewBot, NewBotBuilder};
+use crate::models::language::Language;
 use crate::models::token::EndpointScope;
 use crate::models::util::diesel::Conn;
-use crate::models::Category;
+use crate::models::{Bot, BotDenial, BotOwner, BotResubmission, Category};
+use crate::rate_limiter::{LimitedAction, RateLimitKey};
 use crate::schema::*;
 use crate::task::spawn_blocking;
-use crate::util::errors::{bad_request, server_error, AppResult};
-use crate::views::{EncodableBot, GoodBot, PublishWarnings};
+use crate::util::errors::{
+	api_error, bot_not_found, invalid_field, AppResult, BoxedAppError, ErrorCode,
+};
+use crate::views::{
+	EncodableBot, EncodableBotDenial, EncodableBotResubmission, GoodBot, PublishWarnings,
+};
+use axum::extract::Path;
 use axum::http::request::Parts;
 use axum::Json;
-use diesel::dsl::{exists, select};
 use diesel::prelude::*;
 use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
 
 #[derive(Deserialize)]
 pub struct RequestNewBot {
@@ -23,18 +35,66 @@ pub struct RequestNewBot {
 	pub prefix: String,
 	pub is_slash: bool,
 	pub invite_link: Option<String>,
+	pub github: Option<String>,
+	pub website: Option<String>,
+	pub support_server: Option<String>,
+	/// ISO 639-1 codes of the languages the bot speaks.
+	#[serde(default)]
+	pub supported_languages: Vec<String>,
 	pub categories: Vec<String>,
 }
 
+impl RequestNewBot {
+	/// Trims the text fields, so that the stored values are the validated ones.
+	fn trim(&mut self) {
+		trim(&mut self.description);
+		trim(&mut self.short_description);
+		trim(&mut self.prefix);
+		trim_optional(&mut self.invite_link);
+		trim_optional(&mut self.github);
+		trim_optional(&mut self.website);
+		trim_optional(&mut self.support_server);
+	}
+
+	fn validate(&self) -> AppResult<()> {
+		let mut v = Validator::default();
+		v.check("/description", &self.description, DESCRIPTION_RULES);
+		v.check(
+			"/short_description",
+			&self.short_description,
+			SHORT_DESCRIPTION_RULES,
+		);
+		v.check("/prefix", &self.prefix, PREFIX_RULES);
+		v.check_optional(
+			"/invite_link",
+			self.invite_link.as_deref(),
+			&invite_link_rules(&self.id),
+		);
+		v.check_optional("/github", self.github.as_deref(), GITHUB_RULES);
+		v.check_optional("/website", self.website.as_deref(), WEBSITE_RULES);
+		v.check_optional(
+			"/support_server",
+			self.support_server.as_deref(),
+			SUPPORT_SERVER_RULES,
+		);
+		check_languages(&mut v, &self.supported_languages);
+		check_categories(&mut v, &self.categories);
+		v.finish()
+	}
+}
+
 /// Handles the `POST /bots/new` route.
 pub async fn publish(
 	app: AppState,
 	parts: Parts,
-	Json(bot): Json<RequestNewBot>,
+	Json(mut bot): Json<RequestNewBot>,
 ) -> AppResult<Json<GoodBot>> {
 	let request_log = parts.request_log().clone();
 	request_log.add("bot_id", bot.id.clone());
 
+	bot.trim();
+	bot.validate()?;
+
 	let conn = app.db_write().await?;
 	spawn_blocking(move || {
         let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
@@ -48,19 +108,35 @@ pub async fn publish(
 
         let categories = bot.categories.clone();
 
-        if categories.len() < 2 || categories.len() > 8 {
-            return Err(bad_request("2 to 8 categories are expected at most."));
-        }
+        app.rate_limiter
+            .check_rate_limit(RateLimitKey::User(&user.id), LimitedAction::PublishNew, conn)?;
 
-        conn.transaction(|conn| {
+        let published = conn.transaction(|conn| {
             let categories = categories.iter().map(|c| c.as_str()).collect::<Vec<_>>();
             let bot_id = bot.id.as_str();
 
-            if bot_exists(bot_id, conn)? {
-                return Err(bad_request("bot already exists"));
+            match existing_status(bot_id, conn)? {
+                Some(BotStatus::DENIED) => {
+                    return Err(api_error(ErrorCode::BotDenied, format!(
+                        "bot `{bot_id}` was denied, use `POST /bots/{bot_id}/resubmit` to submit it again"
+                    )));
+                }
+                Some(_) => return Err(api_error(ErrorCode::BotAlreadyExists, "bot already exists")),
+                None => {}
             }
 
-            let bot_info = get_bot_information(&app.clone(), &request_log, bot_id)?;
+            let bot_info = discord::get_bot_information(&app.http, &app.config.discord, bot_id);
+            app.instance_metrics.discord_request("bot_information", &bot_info);
+
+            let bot_info = bot_info
+                .map_err(|err| {
+                    request_log.add("cause", err);
+                    let domain = app.config.domain_name.as_str();
+                    api_error(ErrorCode::DiscordUnavailable, format!("Error getting bot information from Discord. If the error persists, please report it on our Discord server https://dc.{domain}"))
+                })?;
+
+            let description_html = markdown::render(&bot.description, app.config.image_proxy_url.as_ref());
+            let description_html_proxy = markdown::proxy_key(app.config.image_proxy_url.as_ref());
 
             let persist = NewBot::new(NewBotBuilder {
                 id: bot_id,
@@ -68,91 +144,474 @@ pub async fn publish(
                 avatar: Some(bot_info.bot.avatar.as_str()),
                 banner: None,
                 description: bot.description.as_str(),
+                description_html: &description_html,
+                description_html_proxy: &description_html_proxy,
                 short_description: bot.short_description.as_str(),
                 prefix: bot.prefix.as_str(),
                 is_slash: bot.is_slash,
-                github: None,
-                website: None,
+                github: bot.github.as_deref(),
+                website: bot.website.as_deref(),
                 invite_link: bot.invite_link.as_deref(),
                 imported_from: None,
-                support_server: None,
-                supported_languages: vec![None],
+                support_server: bot.support_server.as_deref(),
+                supported_languages: normalize_languages(&bot.supported_languages),
                 guild_count: bot_info.bot.approximate_guild_count,
             });
 
             let bot = persist.create(conn, user.id.clone())?;
 
+            let parent_categories = Category::with_subcategories(conn, &categories)?;
+            if !parent_categories.is_empty() {
+                return Err(parent_categories_error(&parent_categories));
+            }
+
             let unknown_categories = Category::update_bot(conn, bot_id, &categories)?;
             if !unknown_categories.is_empty() {
-                let unknown_categories = unknown_categories.join(", ");
-                let domain = &app.config.domain_name;
-                return Err(bad_request(format!("The following category slugs are not currently supported on {domain}: {unknown_categories}\n\nSee https://{domain}/category_slugs for a list of supported slugs.")));
+                return Err(unknown_categories_error(&app, &unknown_categories));
+            }
+
+            NewAuditEntry::new(&parts, &auth, AuditAction::BotPublish, format!("bot:{bot_id}"))
+                .bot(bot_id)
+                .after(json!({
+                    "status": String::from(bot.status),
+                    "categories": categories,
+                }))
+                .insert(conn)?;
+
+            let mut other = vec![];
+
+            let invite = bot.invite_link.as_deref().and_then(BotInvite::parse);
+            if invite.is_some_and(|invite| invite.permissions.contains(Permissions::ADMINISTRATOR)) {
+                other.push("the invite link requests the Administrator permission, which gives the bot every permission in the server. Consider requesting only the permissions the bot needs".to_string());
             }
 
             let warnings = PublishWarnings {
                 invalid_categories: vec![],
-                other: vec![],
+                other,
             };
 
             Ok(Json(GoodBot {
                 bot: EncodableBot::from_minimal(bot),
                 warnings,
             }))
-        })
+        })?;
+
+        app.instance_metrics.bots_published_total.inc();
+
+        Ok(published)
     })
         .await
 }
 
-fn bot_exists(id: &str, conn: &mut impl Conn) -> QueryResult<bool> {
-	select(exists(bots::table.filter(bots::id.eq(id)))).get_result(conn)
+#[derive(Deserialize)]
+pub struct RequestResubmitBot {
+	pub description: Option<String>,
+	pub short_description: Option<String>,
+	pub prefix: Option<String>,
+	pub is_slash: Option<bool>,
+	pub invite_link: Option<String>,
+	pub github: Option<String>,
+	pub website: Option<String>,
+	pub support_server: Option<String>,
+	pub supported_languages: Option<Vec<String>>,
+	pub categories: Option<Vec<String>>,
+	/// Message to the reviewers explaining what changed since the denial.
+	pub appeal: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct APIBot {
-	pub application: Application,
-	pub bot: Bot,
+impl RequestResubmitBot {
+	/// Trims the text fields, so that the stored values are the validated ones.
+	fn trim(&mut self) {
+		trim_optional(&mut self.description);
+		trim_optional(&mut self.short_description);
+		trim_optional(&mut self.prefix);
+		trim_optional(&mut self.invite_link);
+		trim_optional(&mut self.github);
+		trim_optional(&mut self.website);
+		trim_optional(&mut self.support_server);
+		trim_optional(&mut self.appeal);
+	}
+
+	/// Whether the bot is resubmitted without any change.
+	fn is_empty(&self) -> bool {
+		self.description.is_none()
+			&& self.short_description.is_none()
+			&& self.prefix.is_none()
+			&& self.is_slash.is_none()
+			&& self.invite_link.is_none()
+			&& self.github.is_none()
+			&& self.website.is_none()
+			&& self.support_server.is_none()
+			&& self.supported_languages.is_none()
+			&& self.categories.is_none()
+	}
+
+	fn validate(&self, bot_id: &str) -> AppResult<()> {
+		let mut v = Validator::default();
+		v.check_optional(
+			"/description",
+			self.description.as_deref(),
+			DESCRIPTION_RULES,
+		);
+		v.check_optional(
+			"/short_description",
+			self.short_description.as_deref(),
+			SHORT_DESCRIPTION_RULES,
+		);
+		v.check_optional("/prefix", self.prefix.as_deref(), PREFIX_RULES);
+		v.check_optional(
+			"/invite_link",
+			self.invite_link.as_deref(),
+			&invite_link_rules(bot_id),
+		);
+		v.check_optional("/github", self.github.as_deref(), GITHUB_RULES);
+		v.check_optional("/website", self.website.as_deref(), WEBSITE_RULES);
+		v.check_optional(
+			"/support_server",
+			self.support_server.as_deref(),
+			SUPPORT_SERVER_RULES,
+		);
+		if let Some(languages) = &self.supported_languages {
+			check_languages(&mut v, languages);
+		}
+		if let Some(categories) = &self.categories {
+			check_categories(&mut v, categories);
+		}
+		v.check_optional(
+			"/appeal",
+			self.appeal.as_deref(),
+			&[Rule::MaxLength(BotResubmission::MAX_APPEAL_LENGTH)],
+		);
+		v.finish()
+	}
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct Application {
-	pub id: String,
-	pub name: String,
-	pub icon: String,
-	pub description: String,
-	pub is_verified: bool,
-	pub bot_public: bool,
-	// maybe I'm going to use this in a future.
-	// pub tags: Vec<String>,
+/// Handles the `POST /bots/:bot_id/resubmit` route.
+///
+/// Applies the given edits to a denied bot and moves it back to the review queue.
+pub async fn resubmit(
+	app: AppState,
+	Path(bot_id): Path<String>,
+	parts: Parts,
+	Json(mut resubmission): Json<RequestResubmitBot>,
+) -> AppResult<Json<Value>> {
+	resubmission.trim();
+	resubmission.validate(&bot_id)?;
+
+	let appeal = resubmission
+		.appeal
+		.as_deref()
+		.filter(|appeal| !appeal.is_empty())
+		.map(ToString::to_string);
+
+	if resubmission.is_empty() && appeal.is_none() {
+		return Err(api_error(
+			ErrorCode::NothingToUpdate,
+			"a resubmission needs at least one change or an appeal",
+		));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::default()
+			.with_endpoint_scope(EndpointScope::PublishUpdate)
+			.for_bot(&bot_id)
+			.check(&parts, conn)?;
+		let user = auth.user();
+
+		let description_html = resubmission
+			.description
+			.as_deref()
+			.map(|description| markdown::render(description, app.config.image_proxy_url.as_ref()));
+		let description_html_proxy = markdown::proxy_key(app.config.image_proxy_url.as_ref());
+
+		let supported_languages = resubmission
+			.supported_languages
+			.as_deref()
+			.map(normalize_languages);
+
+		let changes = BotChanges {
+			description: resubmission.description.as_deref(),
+			description_html: description_html.as_deref(),
+			description_html_version: description_html
+				.is_some()
+				.then_some(markdown::POLICY_VERSION),
+			description_html_proxy: description_html
+				.is_some()
+				.then_some(description_html_proxy.as_str()),
+			short_description: resubmission.short_description.as_deref(),
+			prefix: resubmission.prefix.as_deref(),
+			is_slash: resubmission.is_slash,
+			invite_link: resubmission.invite_link.as_deref(),
+			github: resubmission.github.as_deref(),
+			website: resubmission.website.as_deref(),
+			support_server: resubmission.support_server.as_deref(),
+			supported_languages: supported_languages.as_deref(),
+		};
+
+		let resubmission = conn.transaction(|conn| {
+			// The bot is locked until the end of the transaction, so that
+			// concurrent requests can't resubmit it twice.
+			let bot: Bot = Bot::by_id(&bot_id)
+				.for_update()
+				.first(conn)
+				.optional()?
+				.ok_or_else(|| bot_not_found(&bot_id))?;
+
+			if !BotOwner::is_owner(conn, &bot.id, &user.id)? {
+				return Err(api_error(
+					ErrorCode::NotBotOwner,
+					"only the owners of the bot can resubmit it",
+				));
+			}
+
+			if bot.status != BotStatus::DENIED {
+				return Err(api_error(
+					ErrorCode::BotNotDenied,
+					"only denied bots can be resubmitted",
+				));
+			}
+
+			if let Some(denial) = BotDenial::latest(conn, &bot.id)? {
+				let resubmittable_at = denial.resubmittable_at();
+				if chrono::Utc::now().naive_utc() < resubmittable_at {
+					let date = chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(
+						resubmittable_at,
+						chrono::Utc,
+					);
+
+					return Err(api_error(
+						ErrorCode::BotDenied,
+						format!(
+							"this bot was denied recently, it can be resubmitted after {}",
+							date.to_rfc3339()
+						),
+					));
+				}
+			}
+
+			if !changes.is_empty() {
+				diesel::update(&bot).set(&changes).execute(conn)?;
+			}
+
+			if let Some(categories) = &resubmission.categories {
+				let categories = categories.iter().map(String::as_str).collect::<Vec<_>>();
+
+				let parent_categories = Category::with_subcategories(conn, &categories)?;
+				if !parent_categories.is_empty() {
+					return Err(parent_categories_error(&parent_categories));
+				}
+
+				let unknown_categories = Category::update_bot(conn, &bot.id, &categories)?;
+				if !unknown_categories.is_empty() {
+					return Err(unknown_categories_error(&app, &unknown_categories));
+				}
+			}
+
+			let created = BotResubmission::create(conn, &bot.id, &user.id, appeal.as_deref())?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BotResubmit,
+				format!("bot:{}", bot.id),
+			)
+			.bot(&bot.id)
+			.before(json!({ "status": String::from(bot.status) }))
+			.after(json!({
+				"status": String::from(BotStatus::PENDING),
+				"description_changed": changes.description.is_some(),
+				"short_description": changes.short_description,
+				"prefix": changes.prefix,
+				"is_slash": changes.is_slash,
+				"invite_link": changes.invite_link,
+				"github": changes.github,
+				"website": changes.website,
+				"support_server": changes.support_server,
+				"supported_languages": changes.supported_languages,
+				"categories": resubmission.categories,
+				"appeal": created.appeal,
+			}))
+			.insert(conn)?;
+
+			Ok::<_, BoxedAppError>(created)
+		})?;
+
+		let bot: Bot = Bot::by_id(&bot_id).first(conn)?;
+
+		Ok(Json(json!({
+			"bot": EncodableBot::from_minimal(bot),
+			"resubmission": EncodableBotResubmission::from(resubmission),
+		})))
+	})
+	.await
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct Bot {
-	pub id: String,
-	pub username: String,
-	pub avatar: String,
-	pub bot: bool,
-	pub approximate_guild_count: i32,
-}
-
-/// Gets information about the bot directly from the Discord API.
-fn get_bot_information(
-	state: &AppState,
-	request_log: &RequestLog,
-	bot_id: &str,
-) -> AppResult<APIBot> {
-	let domain = state.config.domain_name.as_str();
-	let bot = state
-        .http
-        .get(format!(
-            "https://discord.com/api/v9/oauth2/authorize?client_id={bot_id}&scope=bot"
-        ))
-        .header("Authorization", &state.config.discord.user_token)
-        .send()?
-        .json::<APIBot>()
-        .map_err(|err| {
-            request_log.add("cause", err);
-            server_error(format!("Error getting bot information from Discord. If the error persists, please report it on our Discord server https://dc.{domain}"))
-        })?;
+/// Handles the `GET /bots/:bot_id/denials` route.
+///
+/// Lists the denials of a bot, newest first. Only visible to the owners of the bot.
+pub async fn denials(
+	app: AppState,
+	Path(bot_id): Path<String>,
+	req: Parts,
+) -> AppResult<Json<Value>> {
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&req, conn)?;
+		let user = auth.user();
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		if !user.is_staff() && !BotOwner::is_owner(conn, &bot.id, &user.id)? {
+			return Err(api_error(
+				ErrorCode::NotBotOwner,
+				"only the owners of the bot can see its denials",
+			));
+		}
+
+		let denials = BotDenial::belonging_to(&bot)
+			.select(BotDenial::as_select())
+			.order(bot_denials::id.desc())
+			.load(conn)?
+			.into_iter()
+			.map(EncodableBotDenial::from)
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({ "denials": denials })))
+	})
+	.await
+}
+
+pub(super) const DESCRIPTION_RULES: &[Rule<'_>] = &[
+	Rule::Required,
+	Rule::MaxLength(Bot::MAX_DESCRIPTION_LENGTH),
+	Rule::SafeMarkdown,
+];
+
+pub(super) const SHORT_DESCRIPTION_RULES: &[Rule<'_>] = &[
+	Rule::Required,
+	Rule::MaxLength(Bot::MAX_SHORT_DESCRIPTION_LENGTH),
+	Rule::PlainText,
+];
+
+const PREFIX_RULES: &[Rule<'_>] = &[
+	Rule::Required,
+	Rule::MaxLength(Bot::MAX_PREFIX_LENGTH),
+	Rule::Prefix,
+];
+
+const GITHUB_RULES: &[Rule<'_>] = &[
+	Rule::MaxLength(Bot::MAX_URL_LENGTH),
+	Rule::Url(&["github.com"]),
+];
+
+const WEBSITE_RULES: &[Rule<'_>] = &[Rule::MaxLength(Bot::MAX_URL_LENGTH), Rule::Url(&[])];
+
+const SUPPORT_SERVER_RULES: &[Rule<'_>] =
+	&[Rule::MaxLength(Bot::MAX_URL_LENGTH), Rule::DiscordInvite];
+
+fn invite_link_rules(bot_id: &str) -> [Rule<'_>; 2] {
+	[
+		Rule::MaxLength(Bot::MAX_URL_LENGTH),
+		Rule::BotInvite(bot_id),
+	]
+}
+
+fn check_categories(v: &mut Validator, categories: &[String]) {
+	if categories.len() < 2 || categories.len() > 8 {
+		v.add(
+			"/categories",
+			ErrorCode::InvalidCategoryCount,
+			"2 to 8 categories are expected at most.",
+		);
+	}
+}
+
+fn check_languages(v: &mut Validator, languages: &[String]) {
+	if languages.len() > Bot::MAX_SUPPORTED_LANGUAGES {
+		v.add(
+			"/supported_languages",
+			ErrorCode::OutOfRange,
+			format!(
+				"a bot can speak at most {} languages",
+				Bot::MAX_SUPPORTED_LANGUAGES
+			),
+		);
+		return;
+	}
+
+	for (index, code) in languages.iter().enumerate() {
+		let pointer = format!("/supported_languages/{index}");
+
+		match Language::find(code) {
+			None => v.add(
+				pointer,
+				ErrorCode::InvalidValue,
+				format!("`{code}` is not an ISO 639-1 language code, see `GET /languages`"),
+			),
+			Some(language)
+				if languages[..index]
+					.iter()
+					.any(|other| other.eq_ignore_ascii_case(language.code)) =>
+			{
+				v.add(
+					pointer,
+					ErrorCode::InvalidValue,
+					format!("`{code}` is listed more than once"),
+				)
+			}
+			Some(_) => {}
+		}
+	}
+}
+
+fn trim(value: &mut String) {
+	let trimmed = value.trim();
+	if trimmed.len() != value.len() {
+		*value = trimmed.to_string();
+	}
+}
+
+fn trim_optional(value: &mut Option<String>) {
+	if let Some(value) = value {
+		trim(value);
+	}
+}
+
+/// Lowercases the validated language codes.
+fn normalize_languages(languages: &[String]) -> Vec<String> {
+	languages
+		.iter()
+		.filter_map(|code| Language::find(code))
+		.map(|language| language.code.to_string())
+		.collect()
+}
+
+fn unknown_categories_error(app: &AppState, unknown_categories: &[String]) -> BoxedAppError {
+	let unknown_categories = unknown_categories.join(", ");
+	let domain = &app.config.domain_name;
+
+	invalid_field("/categories", ErrorCode::InvalidCategory, format!("The following category slugs are not currently supported on {domain}: {unknown_categories}\n\nSee https://{domain}/category_slugs for a list of supported slugs."))
+}
+
+fn parent_categories_error(parent_categories: &[String]) -> BoxedAppError {
+	let parent_categories = parent_categories.join(", ");
+
+	invalid_field("/categories", ErrorCode::InvalidCategory, format!("The following categories have subcategories, please pick the most specific ones instead: {parent_categories}"))
+}
 
-	Ok(bot)
+/// Returns the status of the bot if it was already published.
+fn existing_status(id: &str, conn: &mut impl Conn) -> QueryResult<Option<BotStatus>> {
+	bots::table
+		.find(id)
+		.select(bots::status)
+		.first(conn)
+		.optional()
 }
@@ -1,22 +1,28 @@
 use crate::app::AppState;
 use crate::models::category::{BotCategory, Category};
-use crate::models::Bot;
-use crate::schema::categories;
-use crate::util::errors::{bad_request, AppResult, BoxedAppError};
+use crate::models::image::ImageKind;
+use crate::models::{Bot, BotTranslation, Image};
+use crate::schema::{bot_translations, categories, images};
+use crate::util::errors::{api_error, AppResult, BoxedAppError, ErrorCode};
 use crate::util::request_helper::RequestUtils;
-use crate::views::{EncodableBot, EncodableCategory};
+use crate::views::{EncodableBot, EncodableCategory, EncodableImage};
 
 use axum::extract::Path;
+use axum::http::header;
 use axum::http::request::Parts;
+use axum::response::{IntoResponse, Response};
 use axum::Json;
 
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
-use serde_json::Value;
 use std::str::FromStr;
 
 /// Handles the `GET /bots/:bot_id`
-pub async fn show(app: AppState, Path(id): Path<String>, req: Parts) -> AppResult<Json<Value>> {
+///
+/// The descriptions are translated to the language given by `?lang=`, or else
+/// to the most preferred language of the `Accept-Language` header that the
+/// bot has a translation to.
+pub async fn show(app: AppState, Path(id): Path<String>, req: Parts) -> AppResult<Response> {
 	let mut conn = app.db_read().await?;
 
 	let id = id.as_str();
@@ -42,7 +48,48 @@ pub async fn show(app: AppState, Path(id): Path<String>, req: Parts) -> AppResul
 		None
 	};
 
-	let encodable_bot = EncodableBot::from(bot.clone(), cats.as_deref());
+	let translations: Vec<BotTranslation> = BotTranslation::belonging_to(&bot)
+		.select(BotTranslation::as_select())
+		.order(bot_translations::language.asc())
+		.load(&mut conn)
+		.await?;
+
+	let images: Vec<Image> = images::table
+		.filter(images::bot_id.eq(&bot.id))
+		.order((images::position.asc(), images::id.asc()))
+		.select(Image::as_select())
+		.load(&mut conn)
+		.await?;
+
+	let mut banner = None;
+	let mut screenshots = vec![];
+	for image in &images {
+		let encodable = EncodableImage::new(image, &app.storage);
+		match image.kind {
+			ImageKind::BotScreenshot => screenshots.push(encodable),
+			_ => banner = Some(encodable),
+		}
+	}
+
+	let languages = translations
+		.iter()
+		.map(|translation| translation.language.clone())
+		.collect::<Vec<_>>();
+
+	let ranges = match req.query().get("lang") {
+		Some(lang) => vec![lang.to_ascii_lowercase()],
+		None => req.accepted_languages(),
+	};
+
+	let mut encodable_bot = EncodableBot::from(bot.clone(), cats.as_deref());
+	if let Some(translation) = BotTranslation::negotiate(translations, &ranges) {
+		encodable_bot = encodable_bot.translate(translation);
+	}
+
+	let content_language = encodable_bot
+		.language
+		.clone()
+		.unwrap_or_else(|| BotTranslation::DEFAULT_LANGUAGE.into());
 
 	let encodable_cats = cats.map(|cats| {
 		cats.into_iter()
@@ -50,10 +97,22 @@ pub async fn show(app: AppState, Path(id): Path<String>, req: Parts) -> AppResul
 			.collect::<Vec<EncodableCategory>>()
 	});
 
-	Ok(Json(serde_json::json!({
+	let body = Json(serde_json::json!({
 		"bot": encodable_bot,
 		"categories": encodable_cats,
-	})))
+		"translations": languages,
+		"images": {
+			"banner": banner,
+			"screenshots": screenshots,
+		},
+	}));
+
+	let headers = [
+		(header::CONTENT_LANGUAGE, content_language),
+		(header::VARY, header::ACCEPT_LANGUAGE.to_string()),
+	];
+
+	Ok((headers, body).into_response())
 }
 
 #[derive(Debug)]
@@ -83,7 +142,7 @@ impl FromStr for ShowIncludeMode {
 				"" => {}
 				"full" => mode = Self { categories: true },
 				"categories" => mode.categories = true,
-				_ => return Err(bad_request(Self::INVALID_COMPONENT)),
+				_ => return Err(api_error(ErrorCode::InvalidValue, Self::INVALID_COMPONENT)),
 			}
 		}
 
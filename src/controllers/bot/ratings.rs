@@ -0,0 +1,354 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::{ok_true, Paginate};
+use crate::models::bot::BotStatus;
+use crate::models::rating::NewBotRating;
+use crate::models::util::diesel::Conn;
+use crate::models::{Bot, BotOwner, BotRating, BotRatingFlag, BotRatingReply, User};
+use crate::schema::{bot_rating_replies, bot_ratings, users};
+use crate::task::spawn_blocking;
+use crate::util::errors::{
+	api_error, bot_not_found, invalid_field, not_found, AppResult, ErrorCode,
+};
+use crate::util::RequestUtils;
+use crate::views::{EncodableBotRating, EncodableBotRatingFlag, EncodableBotRatingReply};
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::response::Response;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// Handles the `GET /bots/:bot_id/ratings` route.
+pub async fn list(app: AppState, Path(bot_id): Path<String>, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder().gather(&req)?;
+
+	let conn = app.db_read().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		let query = req.query();
+		let sort = query.get("sort").map_or("recent", String::as_str);
+
+		let ratings = BotRating::belonging_to(&bot)
+			.inner_join(users::table)
+			.left_join(bot_rating_replies::table)
+			.filter(bot_ratings::hidden.eq(false))
+			.select((
+				bot_ratings::all_columns,
+				users::all_columns,
+				bot_rating_replies::all_columns.nullable(),
+			))
+			.into_boxed();
+
+		let ratings = match sort {
+			"highest" => ratings.order((bot_ratings::rating.desc(), bot_ratings::id.desc())),
+			"lowest" => ratings.order((bot_ratings::rating.asc(), bot_ratings::id.desc())),
+			_ => ratings.order(bot_ratings::id.desc()),
+		};
+
+		let data: Paginated<(BotRating, User, Option<BotRatingReply>)> =
+			ratings.pages_pagination(options).load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let ratings = data
+			.into_iter()
+			.map(|(rating, user, reply)| {
+				EncodableBotRating::from(rating, user, reply, &app.storage)
+			})
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"ratings": ratings,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestBotRating {
+	pub rating: i16,
+	pub body: String,
+}
+
+/// Handles the `PUT /bots/:bot_id/ratings` route.
+///
+/// Creates the rating of the current user for the bot, or edits it if it already exists.
+pub async fn update(
+	app: AppState,
+	Path(bot_id): Path<String>,
+	parts: Parts,
+	Json(new): Json<RequestBotRating>,
+) -> AppResult<Json<Value>> {
+	if !(BotRating::MIN_RATING..=BotRating::MAX_RATING).contains(&new.rating) {
+		return Err(invalid_field(
+			"/rating",
+			ErrorCode::OutOfRange,
+			format!(
+				"rating must be between {} and {} stars",
+				BotRating::MIN_RATING,
+				BotRating::MAX_RATING
+			),
+		));
+	}
+
+	let body = new.body.trim().to_string();
+	if body.is_empty() {
+		return Err(invalid_field(
+			"/body",
+			ErrorCode::MissingValue,
+			"body must have a value",
+		));
+	}
+
+	if body.chars().count() > BotRating::MAX_BODY_LENGTH {
+		return Err(invalid_field(
+			"/body",
+			ErrorCode::TooLong,
+			format!(
+				"body must be at most {} characters long",
+				BotRating::MAX_BODY_LENGTH
+			),
+		));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&parts, conn)?;
+		let user = auth.user();
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		if bot.status != BotStatus::APPROVED {
+			return Err(api_error(
+				ErrorCode::BotNotApproved,
+				"only approved bots can be rated",
+			));
+		}
+
+		if BotOwner::is_owner(conn, &bot.id, &user.id)? {
+			return Err(api_error(
+				ErrorCode::OwnBotRating,
+				"you cannot rate your own bot",
+			));
+		}
+
+		let rating = NewBotRating::new(&bot.id, &user.id, new.rating, &body).upsert(conn)?;
+		let reply = BotRatingReply::belonging_to(&rating)
+			.select(BotRatingReply::as_select())
+			.first(conn)
+			.optional()?;
+
+		Ok(Json(json!({
+			"rating": EncodableBotRating::from(rating, user.clone(), reply, &app.storage),
+		})))
+	})
+	.await
+}
+
+/// Handles the `DELETE /bots/:bot_id/ratings` route.
+///
+/// Deletes the rating of the current user for the bot.
+pub async fn delete(app: AppState, Path(bot_id): Path<String>, req: Parts) -> AppResult<Response> {
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&req, conn)?;
+		let user = auth.user();
+
+		let deleted = diesel::delete(
+			bot_ratings::table
+				.filter(bot_ratings::bot_id.eq(&bot_id))
+				.filter(bot_ratings::user_id.eq(&user.id)),
+		)
+		.execute(conn)?;
+
+		if deleted == 0 {
+			return Err(not_found());
+		}
+
+		ok_true()
+	})
+	.await
+}
+
+/// Finds a rating of the given bot, failing with `404 Not Found` if the
+/// rating doesn't exist or belongs to another bot.
+fn find_rating(conn: &mut impl Conn, bot_id: &str, rating_id: i32) -> AppResult<BotRating> {
+	bot_ratings::table
+		.find(rating_id)
+		.filter(bot_ratings::bot_id.eq(bot_id))
+		.select(BotRating::as_select())
+		.first(conn)
+		.optional()?
+		.ok_or_else(not_found)
+}
+
+#[derive(Deserialize)]
+pub struct RequestBotRatingReply {
+	pub body: String,
+}
+
+/// Handles the `PUT /bots/:bot_id/ratings/:rating_id/reply` route.
+///
+/// Creates the reply of an owner of the bot to a rating, or edits it if it already exists.
+pub async fn reply(
+	app: AppState,
+	Path((bot_id, rating_id)): Path<(String, i32)>,
+	parts: Parts,
+	Json(new): Json<RequestBotRatingReply>,
+) -> AppResult<Json<Value>> {
+	let body = new.body.trim().to_string();
+	if body.is_empty() {
+		return Err(invalid_field(
+			"/body",
+			ErrorCode::MissingValue,
+			"body must have a value",
+		));
+	}
+
+	if body.chars().count() > BotRatingReply::MAX_BODY_LENGTH {
+		return Err(invalid_field(
+			"/body",
+			ErrorCode::TooLong,
+			format!(
+				"body must be at most {} characters long",
+				BotRatingReply::MAX_BODY_LENGTH
+			),
+		));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&parts, conn)?;
+		let user = auth.user();
+
+		if !BotOwner::is_owner(conn, &bot_id, &user.id)? {
+			return Err(api_error(
+				ErrorCode::NotBotOwner,
+				"only the owners of the bot can reply to its ratings",
+			));
+		}
+
+		let rating = find_rating(conn, &bot_id, rating_id)?;
+		let reply = BotRatingReply::upsert(conn, rating.id, &user.id, &body)?;
+
+		Ok(Json(json!({
+			"reply": EncodableBotRatingReply::from(reply),
+		})))
+	})
+	.await
+}
+
+/// Handles the `DELETE /bots/:bot_id/ratings/:rating_id/reply` route.
+pub async fn delete_reply(
+	app: AppState,
+	Path((bot_id, rating_id)): Path<(String, i32)>,
+	req: Parts,
+) -> AppResult<Response> {
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&req, conn)?;
+		let user = auth.user();
+
+		if !BotOwner::is_owner(conn, &bot_id, &user.id)? {
+			return Err(api_error(
+				ErrorCode::NotBotOwner,
+				"only the owners of the bot can reply to its ratings",
+			));
+		}
+
+		let rating = find_rating(conn, &bot_id, rating_id)?;
+
+		let deleted = diesel::delete(BotRatingReply::belonging_to(&rating)).execute(conn)?;
+		if deleted == 0 {
+			return Err(not_found());
+		}
+
+		ok_true()
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestBotRatingFlag {
+	pub reason: String,
+}
+
+/// Handles the `POST /bots/:bot_id/ratings/:rating_id/flag` route.
+///
+/// Flags a rating for review by the staff team.
+pub async fn flag(
+	app: AppState,
+	Path((bot_id, rating_id)): Path<(String, i32)>,
+	parts: Parts,
+	Json(new): Json<RequestBotRatingFlag>,
+) -> AppResult<Json<Value>> {
+	let reason = new.reason.trim().to_string();
+	if reason.is_empty() {
+		return Err(invalid_field(
+			"/reason",
+			ErrorCode::MissingValue,
+			"reason must have a value",
+		));
+	}
+
+	if reason.chars().count() > BotRatingFlag::MAX_REASON_LENGTH {
+		return Err(invalid_field(
+			"/reason",
+			ErrorCode::TooLong,
+			format!(
+				"reason must be at most {} characters long",
+				BotRatingFlag::MAX_REASON_LENGTH
+			),
+		));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&parts, conn)?;
+		let user = auth.user();
+
+		let rating = find_rating(conn, &bot_id, rating_id)?;
+		if rating.user_id == user.id {
+			return Err(api_error(
+				ErrorCode::OwnRatingFlag,
+				"you cannot flag your own rating",
+			));
+		}
+
+		let flag = BotRatingFlag::upsert(conn, rating.id, &user.id, &reason)?;
+
+		Ok(Json(json!({
+			"flag": EncodableBotRatingFlag::from(flag),
+		})))
+	})
+	.await
+}
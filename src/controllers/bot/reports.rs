@@ -0,0 +1,92 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::models::report::{NewBotReport, ReportReason};
+use crate::models::{Bot, BotOwner, BotReport};
+use crate::rate_limiter::{LimitedAction, RateLimitKey};
+use crate::task::spawn_blocking;
+use crate::util::errors::{api_error, bot_not_found, invalid_field, AppResult, ErrorCode};
+use crate::views::EncodableBotReport;
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+#[derive(Deserialize)]
+pub struct RequestBotReport {
+	pub reason: String,
+	pub body: String,
+}
+
+/// Handles the `POST /bots/:bot_id/reports` route.
+///
+/// Reports a bot to the staff team.
+pub async fn create(
+	app: AppState,
+	Path(bot_id): Path<String>,
+	parts: Parts,
+	Json(new): Json<RequestBotReport>,
+) -> AppResult<Json<Value>> {
+	let reason: ReportReason = new.reason.parse()?;
+
+	let body = new.body.trim().to_string();
+	if body.is_empty() {
+		return Err(invalid_field(
+			"/body",
+			ErrorCode::MissingValue,
+			"body must have a value",
+		));
+	}
+
+	if body.chars().count() > BotReport::MAX_BODY_LENGTH {
+		return Err(invalid_field(
+			"/body",
+			ErrorCode::TooLong,
+			format!(
+				"body must be at most {} characters long",
+				BotReport::MAX_BODY_LENGTH
+			),
+		));
+	}
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&parts, conn)?;
+		let user = auth.user();
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		if BotOwner::is_owner(conn, &bot.id, &user.id)? {
+			return Err(api_error(
+				ErrorCode::OwnBotReport,
+				"you cannot report your own bot",
+			));
+		}
+
+		if BotReport::has_pending(conn, &bot.id, &user.id)? {
+			return Err(api_error(
+				ErrorCode::AlreadyReported,
+				"you already reported this bot, the staff team will review it soon",
+			));
+		}
+
+		app.rate_limiter.check_rate_limit(
+			RateLimitKey::User(&user.id),
+			LimitedAction::Report,
+			conn,
+		)?;
+
+		let report = NewBotReport::new(&bot.id, &user.id, reason, &body).create(conn)?;
+
+		Ok(Json(json!({
+			"report": EncodableBotReport::from(report),
+		})))
+	})
+	.await
+}
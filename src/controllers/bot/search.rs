@@ -0,0 +1,165 @@
+use crate::app::AppState;
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::Paginate;
+use crate::models::bot::BotStatus;
+use crate::models::language::Language;
+use crate::models::util::diesel::Conn;
+use crate::models::{Bot, BotCategory};
+use crate::schema::{bots, bots_categories, categories};
+use crate::task::spawn_blocking;
+use crate::util::errors::{api_error, AppResult, ErrorCode};
+use crate::util::RequestUtils;
+use crate::views::EncodableBot;
+use axum::http::request::Parts;
+use axum::Json;
+use diesel::dsl::sql;
+use diesel::expression::SqlLiteral;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::query_builder::QueryFragment;
+use diesel::sql_types::{Bool, Float, Text};
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// Handles the `GET /bots` route.
+///
+/// Lists the approved bots, sorted by one of the following `?sort=` values:
+///
+/// - `new` (default): most recently created first
+/// - `recent-updates`: most recently updated first
+/// - `alpha`: alphabetically by name
+/// - `guilds`: highest approximate guild count first
+/// - `top-rated`: highest [`weighted_rating`] first
+///
+/// `?language=` only keeps the bots that speak the language with the given
+/// ISO 639-1 code.
+///
+/// `?q=` only keeps the bots whose name or descriptions, in any of the
+/// languages they are translated to, match all of its words.
+pub async fn search(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder()
+		.limit_page_numbers()
+		.gather(&req)?;
+
+	let conn = app.db_read().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let params = req.query();
+		let sort = params.get("sort").map(String::as_str);
+
+		let mut query = bots::table
+			.filter(bots::status.eq(BotStatus::APPROVED))
+			.into_boxed();
+
+		if let Some(code) = params.get("language") {
+			let language = Language::find(code).ok_or_else(|| {
+				api_error(
+					ErrorCode::InvalidValue,
+					format!("`{code}` is not an ISO 639-1 language code, see `GET /languages`"),
+				)
+			})?;
+
+			query = query.filter(bots::supported_languages.contains(vec![language.code]));
+		}
+
+		if let Some(q) = params.get("q").map(|q| q.trim()).filter(|q| !q.is_empty()) {
+			query = query.filter(matches_text(q));
+		}
+
+		let data: Paginated<Bot> = sort_bots(query, sort)
+			.pages_pagination(options)
+			.load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let bots = encode_bots(conn, data.into_iter().collect())?;
+
+		Ok(Json(json!({
+			"bots": bots,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
+
+/// Whether the name or descriptions of the bot, or of one of its translations,
+/// match the words of the text. The expressions are the ones of the
+/// `bots_search_idx` and `bot_translations_search_idx` indexes.
+fn matches_text(text: &str) -> SqlLiteral<Bool, impl QueryFragment<Pg> + Send> {
+	sql::<Bool>(
+		"(to_tsvector('simple', bots.name || ' ' || bots.short_description || ' ' || bots.description) @@ plainto_tsquery('simple', ",
+	)
+	.bind::<Text, _>(text.to_string())
+	.sql(
+		") OR EXISTS (SELECT 1 FROM bot_translations WHERE bot_translations.bot_id = bots.id \
+		 AND to_tsvector('simple', bot_translations.short_description || ' ' || bot_translations.description) @@ plainto_tsquery('simple', ",
+	)
+	.bind::<Text, _>(text.to_string())
+	.sql(")))")
+}
+
+/// Applies one of the supported `?sort=` values of the bot listings to the query.
+pub(crate) fn sort_bots<'a>(
+	query: bots::BoxedQuery<'a, Pg>,
+	sort: Option<&str>,
+) -> bots::BoxedQuery<'a, Pg> {
+	match sort {
+		Some("alpha") => query.order((bots::name.asc(), bots::id.asc())),
+		Some("recent-updates") => query.order((bots::updated_at.desc(), bots::id.asc())),
+		Some("guilds") => query.order((bots::guild_count.desc(), bots::id.asc())),
+		Some("top-rated") => query.order((
+			weighted_rating().desc(),
+			bots::rating_count.desc(),
+			bots::id.asc(),
+		)),
+		_ => query.order((bots::created_at.desc(), bots::id.asc())),
+	}
+}
+
+/// The rating a bot is assumed to have before it is rated.
+const PRIOR_RATING: f32 = 3.0;
+/// How many ratings the prior rating weighs as.
+const PRIOR_RATING_COUNT: i32 = 10;
+
+/// The average rating of the bot, pulled towards [`PRIOR_RATING`] as if it had
+/// [`PRIOR_RATING_COUNT`] more ratings, so that a single 5 stars rating
+/// doesn't outrank hundreds of slightly lower ones.
+pub(crate) fn weighted_rating() -> SqlLiteral<Float> {
+	sql::<Float>(&format!(
+		"((bots.rating_average * bots.rating_count + {PRIOR_RATING:.1} * {PRIOR_RATING_COUNT}) \
+		 / (bots.rating_count + {PRIOR_RATING_COUNT}))"
+	))
+}
+
+/// Encodes a list of bots together with the slugs of their categories.
+pub(crate) fn encode_bots(
+	conn: &mut impl Conn,
+	bot_list: Vec<Bot>,
+) -> QueryResult<Vec<EncodableBot>> {
+	let cats = BotCategory::belonging_to(&bot_list)
+		.inner_join(categories::table)
+		.select((bots_categories::bot_id, categories::slug))
+		.load::<(String, String)>(conn)?;
+
+	let encodable_bots = bot_list
+		.into_iter()
+		.map(|bot| {
+			let cat_slugs = cats
+				.iter()
+				.filter(|(id, _)| *id == bot.id)
+				.map(|(_, slug)| slug.clone())
+				.collect::<Vec<_>>();
+
+			EncodableBot::from_with_no_desc(bot, cat_slugs)
+		})
+		.collect();
+
+	Ok(encodable_bots)
+}
@@ -0,0 +1,180 @@
+use crate::app::AppState;
+use crate::auth::AuthCheck;
+use crate::controllers::bot::manage::{DESCRIPTION_RULES, SHORT_DESCRIPTION_RULES};
+use crate::controllers::helpers::bots::find_owned_bot;
+use crate::controllers::helpers::ok_true;
+use crate::controllers::helpers::validation::Validator;
+use crate::models::audit::{AuditAction, NewAuditEntry};
+use crate::models::language::Language;
+use crate::models::token::EndpointScope;
+use crate::models::translation::NewBotTranslation;
+use crate::models::{Bot, BotTranslation};
+use crate::task::spawn_blocking;
+use crate::util::errors::{api_error, bot_not_found, not_found, AppResult, ErrorCode};
+use crate::views::EncodableBotTranslation;
+use axum::extract::Path;
+use axum::http::request::Parts;
+use axum::response::Response;
+use axum::Json;
+use diesel::prelude::*;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// Handles the `GET /bots/:bot_id/translations` route.
+pub async fn list(app: AppState, Path(bot_id): Path<String>) -> AppResult<Json<Value>> {
+	let conn = app.db_read().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let bot: Bot = Bot::by_id(&bot_id)
+			.first(conn)
+			.optional()?
+			.ok_or_else(|| bot_not_found(&bot_id))?;
+
+		let translations = BotTranslation::for_bot(conn, &bot.id)?
+			.into_iter()
+			.map(EncodableBotTranslation::from)
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"default_language": BotTranslation::DEFAULT_LANGUAGE,
+			"translations": translations,
+		})))
+	})
+	.await
+}
+
+#[derive(Deserialize)]
+pub struct RequestBotTranslation {
+	pub short_description: String,
+	pub description: String,
+}
+
+impl RequestBotTranslation {
+	fn validate(&self) -> AppResult<()> {
+		let mut v = Validator::default();
+		v.check(
+			"/short_description",
+			&self.short_description,
+			SHORT_DESCRIPTION_RULES,
+		);
+		v.check("/description", &self.description, DESCRIPTION_RULES);
+		v.finish()
+	}
+}
+
+/// Handles the `PUT /bots/:bot_id/translations/:language` route.
+///
+/// Creates the translation of the descriptions of the bot to the language, or
+/// replaces it if it already exists.
+pub async fn update(
+	app: AppState,
+	Path((bot_id, language)): Path<(String, String)>,
+	parts: Parts,
+	Json(request): Json<RequestBotTranslation>,
+) -> AppResult<Json<Value>> {
+	let language = parse_language(&language)?;
+	request.validate()?;
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::default()
+			.with_endpoint_scope(EndpointScope::PublishUpdate)
+			.for_bot(&bot_id)
+			.check(&parts, conn)?;
+
+		let bot = find_owned_bot(conn, &bot_id, &auth.user().id, "translate it")?;
+
+		let translation = conn.transaction(|conn| {
+			let translation = NewBotTranslation::new(
+				&bot.id,
+				language,
+				request.short_description.trim(),
+				request.description.trim(),
+				app.config.image_proxy_url.as_ref(),
+			)
+			.save(conn)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BotTranslationUpdate,
+				format!("bot:{}", bot.id),
+			)
+			.bot(&bot.id)
+			.after(json!({
+				"language": translation.language,
+				"short_description": translation.short_description,
+			}))
+			.insert(conn)?;
+
+			QueryResult::Ok(translation)
+		})?;
+
+		Ok(Json(json!({
+			"translation": EncodableBotTranslation::from(translation),
+		})))
+	})
+	.await
+}
+
+/// Handles the `DELETE /bots/:bot_id/translations/:language` route.
+pub async fn delete(
+	app: AppState,
+	Path((bot_id, language)): Path<(String, String)>,
+	parts: Parts,
+) -> AppResult<Response> {
+	let language = parse_language(&language)?;
+
+	let conn = app.db_write().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::default()
+			.with_endpoint_scope(EndpointScope::PublishUpdate)
+			.for_bot(&bot_id)
+			.check(&parts, conn)?;
+
+		let bot = find_owned_bot(conn, &bot_id, &auth.user().id, "translate it")?;
+
+		conn.transaction(|conn| {
+			if !BotTranslation::delete(conn, &bot.id, language)? {
+				return Err(not_found());
+			}
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::BotTranslationDelete,
+				format!("bot:{}", bot.id),
+			)
+			.bot(&bot.id)
+			.before(json!({ "language": language }))
+			.insert(conn)?;
+
+			ok_true()
+		})
+	})
+	.await
+}
+
+/// Parses the language of a translation, which can't be the default language.
+fn parse_language(language: &str) -> AppResult<&'static str> {
+	let language = Language::find(language).ok_or_else(|| {
+		api_error(
+			ErrorCode::InvalidValue,
+			format!("`{language}` is not a supported language"),
+		)
+	})?;
+
+	if language.code == BotTranslation::DEFAULT_LANGUAGE {
+		return Err(api_error(
+			ErrorCode::InvalidValue,
+			"the default descriptions of the bot are in this language, edit them instead",
+		));
+	}
+
+	Ok(language.code)
+}
@@ -1,5 +1,6 @@
 use crate::auth::AuthCheck;
 use crate::models::vote::NewBotVote;
+use crate::rate_limiter::{LimitedAction, RateLimitKey};
 use crate::task::spawn_blocking;
 use crate::util::errors::bot_not_found;
 use crate::views::EncodableBotVote;
@@ -51,7 +52,6 @@ pub async fn votes(app: AppState, Path(bot_id): Path<String>) -> AppResult<Json<
 	})))
 }
 
-// TODO: 12h user ratelimit
 /// Handles the `POST /bots/:bot_id/votes` route.
 pub async fn vote(
 	app: AppState,
@@ -66,7 +66,7 @@ pub async fn vote(
 		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
 
 		// Make sure user is logged in
-		let _ = AuthCheck::only_cookie().check(&req, conn)?.user_id();
+		let user_id = AuthCheck::only_cookie().check(&req, conn)?.user_id();
 		let bot_id = bot_id.as_str();
 
 		// Check if bot exists
@@ -75,9 +75,17 @@ pub async fn vote(
 			.optional()?
 			.ok_or_else(|| bot_not_found(bot_id))?;
 
+		app.rate_limiter.check_rate_limit(
+			RateLimitKey::UserBot(&user_id, bot_id),
+			LimitedAction::Vote,
+			conn,
+		)?;
+
 		let new_vote = NewBotVote::new(bot_id);
 		let vote = new_vote.create(conn)?;
 
+		app.instance_metrics.votes_total.inc();
+
 		Ok(Json(EncodableBotVote::from(vote)))
 	})
 	.await
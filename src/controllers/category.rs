@@ -1,7 +1,10 @@
+use super::bot::search::{encode_bots, sort_bots};
 use super::helpers::pagination::*;
+use super::helpers::Paginate;
 use crate::app::AppState;
-use crate::models::Category;
-use crate::schema::categories;
+use crate::models::bot::BotStatus;
+use crate::models::{Bot, Category};
+use crate::schema::{bots, bots_categories, categories};
 use crate::task::spawn_blocking;
 use crate::util::errors::AppResult;
 use crate::util::RequestUtils;
@@ -46,6 +49,17 @@ pub async fn show(state: AppState, Path(slug): Path<String>) -> AppResult<Json<V
 		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
 
 		let cat: Category = Category::by_slug(&slug).first(conn)?;
+		let bots_cnt = cat.subtree_bots_cnt(conn)?;
+		let subcats = cat
+			.subcategories(conn)?
+			.into_iter()
+			.map(Category::into)
+			.collect();
+		let parents = cat
+			.parent_categories(conn)?
+			.into_iter()
+			.map(Category::into)
+			.collect();
 
 		let cat = EncodableCategory::from(cat);
 		let cat_with_subcats = EncodableCategoryWithSubcategories {
@@ -54,7 +68,9 @@ pub async fn show(state: AppState, Path(slug): Path<String>) -> AppResult<Json<V
 			slug: cat.slug,
 			description: cat.description,
 			created_at: cat.created_at,
-			bots_cnt: cat.bots_cnt,
+			bots_cnt,
+			subcategories: subcats,
+			parent_categories: parents,
 		};
 
 		Ok(Json(json!({ "category": cat_with_subcats })))
@@ -62,6 +78,59 @@ pub async fn show(state: AppState, Path(slug): Path<String>) -> AppResult<Json<V
 	.await
 }
 
+/// Handles the `GET /categories/:category_id/bots` route.
+///
+/// Lists the approved bots of the category and of its subcategories, with the
+/// same `?sort=` values as `GET /bots`.
+pub async fn bots(app: AppState, Path(slug): Path<String>, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder()
+		.limit_page_numbers()
+		.gather(&req)?;
+
+	let conn = app.db_read().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let cat: Category = Category::by_slug(&slug).first(conn)?;
+
+		let category_ids = categories::table
+			.filter(Category::in_subtree(&cat.slug))
+			.select(categories::id);
+
+		let bot_ids = bots_categories::table
+			.filter(bots_categories::category_id.eq_any(category_ids))
+			.select(bots_categories::bot_id);
+
+		let query = bots::table
+			.filter(bots::status.eq(BotStatus::APPROVED))
+			.filter(bots::id.eq_any(bot_ids))
+			.into_boxed();
+
+		let params = req.query();
+		let sort = params.get("sort").map(String::as_str);
+
+		let data: Paginated<Bot> = sort_bots(query, sort)
+			.pages_pagination(options)
+			.load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let bots = encode_bots(conn, data.into_iter().collect())?;
+
+		Ok(Json(json!({
+			"bots": bots,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
+
 /// Handles the `GET /category_slugs` route.
 pub async fn slugs(state: AppState) -> AppResult<Json<Value>> {
 	let conn = state.db_read().await?;
@@ -0,0 +1,134 @@
+use crate::app::AppState;
+use crate::boot::migrations::{self, MigrationStatus};
+use crate::task::spawn_blocking;
+use crate::util::errors::AppResult;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use diesel::IntoSql;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use diesel_async::pooled_connection::deadpool::Pool;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// How long a single dependency check may take before it is considered failed.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Handles the `GET /health/live` route.
+///
+/// Only tells that the process is up and serving requests.
+pub async fn live() -> Json<Value> {
+	Json(json!({ "status": "ok" }))
+}
+
+#[derive(Serialize)]
+struct Check {
+	ok: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	latency_ms: Option<u128>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	error: Option<String>,
+}
+
+impl Check {
+	fn failed(error: impl ToString) -> Self {
+		Check {
+			ok: false,
+			latency_ms: None,
+			error: Some(error.to_string()),
+		}
+	}
+}
+
+/// Handles the `GET /health/ready` route.
+///
+/// Checks the database pools and the state of the migrations, and responds with
+/// `503 Service Unavailable` if the instance should not receive traffic. An
+/// unavailable replica is reported but does not fail the check, since reads
+/// fall back to the primary database.
+pub async fn ready(app: AppState) -> AppResult<Response> {
+	let primary = check_pool(&app.primary_database).await;
+
+	let replica = match &app.replica_database {
+		Some(pool) => Some(check_pool(pool).await),
+		None => None,
+	};
+
+	let migrations = if primary.ok {
+		migration_status(&app).await
+	} else {
+		Err("the primary database is unavailable".to_string())
+	};
+
+	let migrations = match migrations {
+		Ok(status) => json!({
+			"ok": status.pending.is_empty() && !status.is_schema_ahead(),
+			"pending": status.pending,
+			"unknown": status.unknown,
+		}),
+		Err(error) => json!({ "ok": false, "error": error }),
+	};
+
+	let ready = primary.ok && migrations["ok"] == true;
+	let status = if ready {
+		StatusCode::OK
+	} else {
+		StatusCode::SERVICE_UNAVAILABLE
+	};
+
+	let body = json!({
+		"status": if ready { "ok" } else { "unavailable" },
+		"read_only": app.config.db.are_all_read_only(),
+		"checks": {
+			"primary": primary,
+			"replica": replica,
+			"migrations": migrations,
+		},
+	});
+
+	Ok((status, Json(body)).into_response())
+}
+
+/// Runs a `SELECT 1` on a connection of the pool, including the time it
+/// takes to obtain the connection.
+async fn check_pool(pool: &Pool<AsyncPgConnection>) -> Check {
+	let start = Instant::now();
+
+	let check = async {
+		let mut conn = pool.get().await.map_err(|err| err.to_string())?;
+		diesel::select(1.into_sql::<diesel::sql_types::Integer>())
+			.execute(&mut conn)
+			.await
+			.map_err(|err| err.to_string())
+	};
+
+	match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+		Ok(Ok(_)) => Check {
+			ok: true,
+			latency_ms: Some(start.elapsed().as_millis()),
+			error: None,
+		},
+		Ok(Err(error)) => Check::failed(error),
+		Err(_) => Check::failed(format!("timed out after {CHECK_TIMEOUT:?}")),
+	}
+}
+
+/// Compares the embedded migrations with the ones applied to the primary database.
+async fn migration_status(app: &AppState) -> Result<MigrationStatus, String> {
+	let conn = app
+		.primary_database
+		.get()
+		.await
+		.map_err(|err| err.to_string())?;
+
+	let status = spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+		migrations::status(conn)
+	});
+
+	match tokio::time::timeout(CHECK_TIMEOUT, status).await {
+		Ok(result) => result.map_err(|err| err.to_string()),
+		Err(_) => Err(format!("timed out after {CHECK_TIMEOUT:?}")),
+	}
+}
@@ -2,7 +2,10 @@ use crate::util::errors::AppResult;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 
+pub(crate) mod bots;
+pub(crate) mod images;
 pub(crate) mod pagination;
+pub(crate) mod validation;
 
 pub(crate) use self::pagination::Paginate;
 
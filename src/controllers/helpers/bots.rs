@@ -0,0 +1,31 @@
+//! Lookups of bots shared by the routes of their owners.
+
+use crate::models::util::diesel::Conn;
+use crate::models::{Bot, BotOwner};
+use crate::util::errors::{api_error, bot_not_found, AppResult, ErrorCode};
+use diesel::prelude::*;
+
+/// Finds the bot, failing unless the user is one of its owners.
+///
+/// `action` completes the error message, e.g. `translate it` for "only the
+/// owners of the bot can translate it".
+pub fn find_owned_bot(
+	conn: &mut impl Conn,
+	bot_id: &str,
+	user_id: &str,
+	action: &str,
+) -> AppResult<Bot> {
+	let bot: Bot = Bot::by_id(bot_id)
+		.first(conn)
+		.optional()?
+		.ok_or_else(|| bot_not_found(bot_id))?;
+
+	if !BotOwner::is_owner(conn, &bot.id, user_id)? {
+		return Err(api_error(
+			ErrorCode::NotBotOwner,
+			format!("only the owners of the bot can {action}"),
+		));
+	}
+
+	Ok(bot)
+}
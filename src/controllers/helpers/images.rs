@@ -0,0 +1,80 @@
+//! Uploads of images, shared by the banners and the screenshots.
+
+use crate::app::AppState;
+use crate::images::{self, ProcessedImage};
+use crate::models::image::{Image, ImageKind, NewImage, StoredImage};
+use crate::storage::Storage;
+use crate::task::spawn_blocking;
+use crate::util::errors::AppResult;
+use axum::body::Bytes;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use diesel_async::pooled_connection::deadpool::Object;
+use diesel_async::AsyncPgConnection;
+use tracing::warn;
+
+type SyncConnection = AsyncConnectionWrapper<Object<AsyncPgConnection>>;
+
+/// Validates the uploaded image and generates its variants.
+pub async fn process(kind: ImageKind, body: Bytes) -> AppResult<ProcessedImage> {
+	spawn_blocking(move || Ok(images::process(kind.spec(), &body)?)).await
+}
+
+/// Uploads the variants of the image, then saves it in the database with
+/// `save`, which returns the saved image and the one it replaces, if any.
+///
+/// The uploaded files are deleted if saving fails, and the ones of the
+/// replaced image once it succeeds.
+pub async fn store<F>(
+	app: &AppState,
+	image: NewImage,
+	processed: ProcessedImage,
+	save: F,
+) -> AppResult<Image>
+where
+	F: FnOnce(&mut SyncConnection, &NewImage) -> AppResult<(Image, Option<Image>)> + Send + 'static,
+{
+	let content_type = processed.format.content_type();
+	for variant in processed.variants {
+		let key = image.variant_key(variant.width as i32);
+		if let Err(error) = app.storage.upload(&key, variant.bytes, content_type).await {
+			discard(&app.storage, image.object_keys()).await;
+			return Err(error.into());
+		}
+	}
+
+	let keys = image.object_keys();
+
+	let conn = app.db_write().await;
+	let saved = match conn {
+		Ok(conn) => {
+			spawn_blocking(move || {
+				let conn: &mut SyncConnection = &mut conn.into();
+				save(conn, &image)
+			})
+			.await
+		}
+		Err(error) => Err(error.into()),
+	};
+
+	match saved {
+		Ok((image, replaced)) => {
+			if let Some(replaced) = replaced {
+				discard(&app.storage, replaced.object_keys()).await;
+			}
+
+			Ok(image)
+		}
+		Err(error) => {
+			discard(&app.storage, keys).await;
+			Err(error)
+		}
+	}
+}
+
+/// Deletes the stored files of an image. The failures are only logged, as
+/// leftover files waste space but are otherwise harmless.
+pub async fn discard(storage: &Storage, keys: Vec<String>) {
+	if let Err(error) = storage.delete(keys).await {
+		warn!(%error, "Failed to delete the files of an image");
+	}
+}
@@ -4,7 +4,7 @@ use crate::middleware::app::RequestApp;
 use crate::middleware::log_request::RequestLogExt;
 use crate::middleware::real_ip::RealIp;
 use crate::models::helpers::with_count::*;
-use crate::util::errors::{bad_request, AppResult};
+use crate::util::errors::{api_error, AppResult, BoxedAppError, ErrorCode};
 use crate::util::{HeaderMapExt, RequestUtils};
 
 use axum::http::header;
@@ -80,19 +80,21 @@ impl PaginationOptionsBuilder {
 		let seek_param = params.get("seek");
 
 		if seek_param.is_some() && page_param.is_some() {
-			return Err(bad_request(
+			return Err(invalid_pagination(
 				"providing both ?page= and ?seek= is unsupported",
 			));
 		}
 
 		let page = if let Some(s) = page_param {
 			if !self.enable_pages {
-				return Err(bad_request("?page= is not supported for this request"));
+				return Err(invalid_pagination(
+					"?page= is not supported for this request",
+				));
 			}
 
-			let numeric_page = s.parse().map_err(bad_request)?;
+			let numeric_page = s.parse().map_err(invalid_pagination)?;
 			if numeric_page < 1 {
-				return Err(bad_request(format_args!(
+				return Err(invalid_pagination(format_args!(
 					"page indexing starts from 1, page {numeric_page} is invalid",
 				)));
 			}
@@ -112,14 +114,16 @@ impl PaginationOptionsBuilder {
 					let error =
                             format!("Page {numeric_page} is unavailable for performance reasons. Please take a look at https://crates.io/data-access for alternatives.");
 
-					return Err(bad_request(error));
+					return Err(invalid_pagination(error));
 				}
 			}
 
 			Page::Numeric(numeric_page)
 		} else if let Some(s) = seek_param {
 			if !self.enable_seek {
-				return Err(bad_request("?seek= is not supported for this request"));
+				return Err(invalid_pagination(
+					"?seek= is not supported for this request",
+				));
 			}
 
 			Page::Seek(RawSeekPayload(s.clone()))
@@ -129,14 +133,14 @@ impl PaginationOptionsBuilder {
 
 		let per_page = params
 			.get("per_page")
-			.map(|s| s.parse().map_err(bad_request))
+			.map(|s| s.parse().map_err(invalid_pagination))
 			.unwrap_or(Ok(DEFAULT_PER_PAGE))?;
 		if per_page > MAX_PER_PAGE {
-			return Err(bad_request(format_args!(
+			return Err(invalid_pagination(format_args!(
 				"cannot request more than {MAX_PER_PAGE} items",
 			)));
 		} else if per_page < 1 {
-			return Err(bad_request(format_args!(
+			return Err(invalid_pagination(format_args!(
 				"cannot request less than 1 item, per_page {per_page} is invalid",
 			)));
 		}
@@ -295,7 +299,7 @@ pub(crate) struct RawSeekPayload(String);
 
 impl RawSeekPayload {
 	pub(crate) fn decode<D: for<'a> Deserialize<'a>>(&self) -> AppResult<D> {
-		decode_seek(&self.0).map_err(|_| bad_request("invalid seek parameter"))
+		decode_seek(&self.0).map_err(|_| invalid_pagination("invalid seek parameter"))
 	}
 }
 
@@ -335,6 +339,10 @@ fn is_useragent_or_ip_blocked<T: RequestPartsExt>(config: &Server, req: &T) -> b
 /// The payload is base64-encoded to hint that it shouldn't be manually constructed. There is no
 /// technical measure to prevent API consumers for manually creating or modifying them, but
 /// hopefully the base64 will be enough to convey that doing it is unsupported.
+fn invalid_pagination<S: ToString>(error: S) -> BoxedAppError {
+	api_error(ErrorCode::InvalidPagination, error.to_string())
+}
+
 pub(crate) fn encode_seek<S: Serialize>(params: S) -> AppResult<String> {
 	let encoded = general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(&params)?);
 	Ok(encoded)
@@ -0,0 +1,376 @@
+//! Declarative validation of the request payloads.
+//!
+//! Each field of a payload is checked against a list of [`Rule`]s by a
+//! [`Validator`], which reports every invalid field at once, each with its
+//! JSON pointer.
+
+use crate::discord::invite::BotInvite;
+use crate::util::errors::{invalid_fields, AppResult, ErrorCode, ErrorObject, ErrorSource};
+use std::borrow::Cow;
+use url::Url;
+
+/// The HTML elements that have no place in a description.
+const UNSAFE_ELEMENTS: &[&str] = &[
+	"script", "iframe", "frame", "object", "embed", "style", "form", "meta", "link", "base",
+];
+
+/// The URL schemes that have no place in the links and images of a description.
+const UNSAFE_SCHEMES: &[&str] = &["javascript:", "vbscript:", "data:text/html"];
+
+/// The hosts of the Discord server invites.
+const DISCORD_INVITE_HOSTS: &[&str] = &["discord.gg", "discord.com", "discordapp.com"];
+
+/// A check of a field, applied to its trimmed value.
+#[derive(Debug, Clone, Copy)]
+pub enum Rule<'a> {
+	/// The value must not be empty.
+	Required,
+	/// The value must be at most this many characters long.
+	MaxLength(usize),
+	/// A single line of text without HTML tags.
+	PlainText,
+	/// Markdown without scripts, frames, event handlers or other active content.
+	SafeMarkdown,
+	/// A command prefix, without whitespace or mentions.
+	Prefix,
+	/// An `http` or `https` URL, on one of the given hosts or their
+	/// subdomains if any are given.
+	Url(&'a [&'a str]),
+	/// A Discord server invite, e.g. `https://discord.gg/abc123`.
+	DiscordInvite,
+	/// A Discord OAuth2 URL that adds the bot with the given id, with the
+	/// `bot` or `applications.commands` scope.
+	BotInvite(&'a str),
+}
+
+impl Rule<'_> {
+	fn check(&self, name: &str, value: &str) -> Result<(), (ErrorCode, String)> {
+		match *self {
+			Rule::Required if value.is_empty() => {
+				Err((ErrorCode::MissingValue, format!("{name} must have a value")))
+			}
+			Rule::MaxLength(max) if value.chars().count() > max => Err((
+				ErrorCode::TooLong,
+				format!("{name} must be at most {max} characters long"),
+			)),
+			Rule::PlainText if !is_plain_text(value) => Err((
+				ErrorCode::InvalidFormat,
+				format!("{name} must be a single line of text without HTML tags"),
+			)),
+			Rule::SafeMarkdown if !is_safe_markdown(value) => Err((
+				ErrorCode::UnsafeContent,
+				format!("{name} must not contain scripts, frames, event handlers or other active content"),
+			)),
+			Rule::Prefix if !is_valid_prefix(value) => Err((
+				ErrorCode::InvalidFormat,
+				format!("{name} must not contain whitespace or mentions"),
+			)),
+			Rule::Url(hosts) if !is_url_on_hosts(value, hosts) => {
+				let detail = match hosts {
+					[] => format!("{name} must be an http or https URL"),
+					hosts => format!("{name} must be a URL on {}", hosts.join(" or ")),
+				};
+				Err((ErrorCode::InvalidUrl, detail))
+			}
+			Rule::DiscordInvite if !is_discord_invite(value) => Err((
+				ErrorCode::InvalidInvite,
+				format!("{name} must be a Discord invite, e.g. https://discord.gg/abc123"),
+			)),
+			Rule::BotInvite(bot_id) if !is_bot_invite(value, bot_id) => Err((
+				ErrorCode::InvalidInvite,
+				format!(
+					"{name} must be a Discord OAuth2 URL with `client_id={bot_id}` and the `bot` or `applications.commands` scope"
+				),
+			)),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Collects the invalid fields of a payload.
+#[derive(Debug, Default)]
+pub struct Validator {
+	errors: Vec<ErrorObject>,
+}
+
+impl Validator {
+	/// Checks a field against the rules, stopping at the first one it breaks.
+	pub fn check(&mut self, pointer: &'static str, value: &str, rules: &[Rule<'_>]) {
+		let name = pointer.rsplit('/').next().unwrap_or(pointer);
+
+		if let Some((code, detail)) = rules
+			.iter()
+			.find_map(|rule| rule.check(name, value.trim()).err())
+		{
+			self.add(pointer, code, detail);
+		}
+	}
+
+	/// Checks a field that may be missing from the payload.
+	pub fn check_optional(
+		&mut self,
+		pointer: &'static str,
+		value: Option<&str>,
+		rules: &[Rule<'_>],
+	) {
+		if let Some(value) = value {
+			self.check(pointer, value, rules);
+		}
+	}
+
+	/// Reports an invalid field that no [`Rule`] covers.
+	pub fn add(
+		&mut self,
+		pointer: impl Into<Cow<'static, str>>,
+		code: ErrorCode,
+		detail: impl Into<Cow<'static, str>>,
+	) {
+		self.errors.push(ErrorObject {
+			code,
+			detail: detail.into(),
+			source: Some(ErrorSource {
+				pointer: pointer.into(),
+			}),
+		});
+	}
+
+	/// Fails with all the invalid fields, if any.
+	pub fn finish(self) -> AppResult<()> {
+		match self.errors.is_empty() {
+			true => Ok(()),
+			false => Err(invalid_fields(self.errors)),
+		}
+	}
+}
+
+fn is_plain_text(value: &str) -> bool {
+	!value.chars().any(char::is_control) && !contains_html_tag(value)
+}
+
+/// Whether the value has something that looks like an opening or closing tag.
+fn contains_html_tag(value: &str) -> bool {
+	value.match_indices('<').any(|(index, _)| {
+		let rest = &value[index + 1..];
+		let rest = rest.strip_prefix('/').unwrap_or(rest);
+		rest.starts_with(|c: char| c.is_ascii_alphabetic())
+	})
+}
+
+/// Only the HTML tags and the targets of the links are checked, so that text
+/// like `Written in JavaScript: fast` is allowed. The rendered HTML is
+/// sanitized anyway, see [`crate::markdown`].
+fn is_safe_markdown(value: &str) -> bool {
+	let lowercase = value.to_lowercase();
+
+	// The targets of the Markdown links and images, `[text](url)`, and of the
+	// link reference definitions, `[text]: url`.
+	let mut link_targets = lowercase
+		.match_indices("](")
+		.chain(lowercase.match_indices("]:"))
+		.map(|(i, _)| lowercase[i + 2..].trim_start().trim_start_matches('<'));
+
+	if link_targets.any(has_unsafe_scheme) {
+		return false;
+	}
+
+	// Only a `<` directly followed by a letter opens a tag, `1 < 2` is text.
+	let tags = lowercase
+		.split('<')
+		.skip(1)
+		.filter(|tag| tag.starts_with(|c: char| c.is_ascii_alphabetic()))
+		.map(|tag| tag.split('>').next().unwrap_or_default());
+
+	for tag in tags {
+		let name_end = tag
+			.find(|c: char| !c.is_ascii_alphanumeric())
+			.unwrap_or(tag.len());
+		let (name, attributes) = tag.split_at(name_end);
+
+		// Autolinks, like `<javascript:alert(1)>`.
+		if UNSAFE_ELEMENTS.contains(&name) || has_unsafe_scheme(tag) {
+			return false;
+		}
+
+		// Event handlers, like `<img src=x onerror=alert(1)>`.
+		if attributes
+			.split(|c: char| c.is_whitespace() || c == '/')
+			.any(|attribute| attribute.starts_with("on") && attribute.contains('='))
+		{
+			return false;
+		}
+
+		// The values of the attributes, like `<a href="javascript:alert(1)">`.
+		let mut values = attributes.match_indices('=').map(|(i, _)| {
+			attributes[i + 1..]
+				.trim_start()
+				.trim_start_matches(['"', '\''])
+		});
+		if values.any(has_unsafe_scheme) {
+			return false;
+		}
+	}
+
+	true
+}
+
+fn has_unsafe_scheme(url: &str) -> bool {
+	UNSAFE_SCHEMES.iter().any(|scheme| url.starts_with(scheme))
+}
+
+fn is_valid_prefix(value: &str) -> bool {
+	!value.chars().any(|c| c.is_whitespace() || c.is_control())
+		&& !value.contains("@everyone")
+		&& !value.contains("@here")
+}
+
+fn parse_http_url(value: &str) -> Option<Url> {
+	let url = Url::parse(value).ok()?;
+	let is_http = matches!(url.scheme(), "http" | "https");
+	(is_http && url.host_str().is_some()).then_some(url)
+}
+
+/// Whether the host is one of the hosts or a subdomain of one of them.
+fn is_on_hosts(url: &Url, hosts: &[&str]) -> bool {
+	let host = url.host_str().unwrap_or_default();
+
+	hosts.iter().any(|expected| {
+		host == *expected
+			|| host
+				.strip_suffix(expected)
+				.is_some_and(|subdomain| subdomain.ends_with('.'))
+	})
+}
+
+fn is_url_on_hosts(value: &str, hosts: &[&str]) -> bool {
+	parse_http_url(value).is_some_and(|url| hosts.is_empty() || is_on_hosts(&url, hosts))
+}
+
+fn is_discord_invite(value: &str) -> bool {
+	let Some(url) = parse_http_url(value) else {
+		return false;
+	};
+
+	if !is_on_hosts(&url, DISCORD_INVITE_HOSTS) {
+		return false;
+	}
+
+	let code = match url.host_str() {
+		Some("discord.gg") => url.path().strip_prefix('/'),
+		_ => url.path().strip_prefix("/invite/"),
+	};
+
+	code.is_some_and(|code| {
+		(2..=32).contains(&code.len())
+			&& code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+	})
+}
+
+fn is_bot_invite(value: &str, bot_id: &str) -> bool {
+	BotInvite::parse(value).is_some_and(|invite| invite.client_id == bot_id && invite.adds_bot())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reports_every_invalid_field() {
+		let mut v = Validator::default();
+		v.check("/description", "", &[Rule::Required, Rule::MaxLength(10)]);
+		v.check("/prefix", "! ", &[Rule::Required, Rule::Prefix]);
+		v.check("/short_description", "short", &[Rule::MaxLength(3)]);
+		v.check_optional("/website", None, &[Rule::Url(&[])]);
+
+		let codes = v
+			.errors
+			.iter()
+			.map(|error| (error.source.as_ref().unwrap().pointer.as_ref(), error.code))
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			codes,
+			[
+				("/description", ErrorCode::MissingValue),
+				("/short_description", ErrorCode::TooLong),
+			]
+		);
+	}
+
+	#[test]
+	fn markdown() {
+		assert!(is_safe_markdown(
+			"**bold** <b>html</b> [link](https://example.com)"
+		));
+		assert!(!is_safe_markdown("<SCRIPT>alert(1)</SCRIPT>"));
+		assert!(!is_safe_markdown("<img src=x onerror=alert(1)>"));
+		assert!(!is_safe_markdown("[click](javascript:alert(1))"));
+		assert!(is_safe_markdown("commands run on = servers"));
+		assert!(!is_safe_markdown("<iframe src=https://example.com>"));
+		assert!(!is_safe_markdown("<a href = 'JavaScript:alert(1)'>x</a>"));
+		assert!(!is_safe_markdown("[click]: javascript:alert(1)"));
+		assert!(!is_safe_markdown("<javascript:alert(1)>"));
+		assert!(!is_safe_markdown("![x]( <data:text/html,hi>)"));
+	}
+
+	#[test]
+	fn innocent_markdown() {
+		assert!(is_safe_markdown("Written in JavaScript: fast"));
+		assert!(is_safe_markdown("one=two"));
+		assert!(is_safe_markdown("when x < y and one=two"));
+		assert!(is_safe_markdown(
+			"<b>Note:</b> use <linkedin> for data:text/html"
+		));
+		assert!(is_safe_markdown("[docs](https://example.com/javascript:)"));
+	}
+
+	#[test]
+	fn plain_text() {
+		assert!(is_plain_text("A bot where 1 < 2 and 3 > 2"));
+		assert!(!is_plain_text("A <b>bold</b> bot"));
+		assert!(!is_plain_text("two\nlines"));
+	}
+
+	#[test]
+	fn prefixes() {
+		assert!(is_valid_prefix("!"));
+		assert!(is_valid_prefix("?bot"));
+		assert!(!is_valid_prefix("my bot"));
+		assert!(!is_valid_prefix("@everyone"));
+	}
+
+	#[test]
+	fn urls() {
+		assert!(is_url_on_hosts("https://example.com", &[]));
+		assert!(!is_url_on_hosts("ftp://example.com", &[]));
+		assert!(is_url_on_hosts(
+			"https://github.com/dbots/izumo",
+			&["github.com"]
+		));
+		assert!(is_url_on_hosts(
+			"https://www.github.com/dbots",
+			&["github.com"]
+		));
+		assert!(!is_url_on_hosts(
+			"https://notgithub.com/dbots",
+			&["github.com"]
+		));
+	}
+
+	#[test]
+	fn invites() {
+		assert!(is_discord_invite("https://discord.gg/XaSVMz7eng"));
+		assert!(is_discord_invite("https://discord.com/invite/XaSVMz7eng"));
+		assert!(!is_discord_invite("https://discord.gg/"));
+		assert!(!is_discord_invite("https://example.com/XaSVMz7eng"));
+
+		let bot_id = "1234567890";
+		let invite = "https://discord.com/oauth2/authorize?client_id=1234567890&scope=bot";
+		assert!(is_bot_invite(invite, bot_id));
+		assert!(!is_bot_invite(invite, "42"));
+		assert!(!is_bot_invite("https://discord.gg/XaSVMz7eng", bot_id));
+		assert!(!is_bot_invite(
+			"https://discord.com/oauth2/authorize?client_id=1234567890&scope=identify",
+			bot_id
+		));
+	}
+}
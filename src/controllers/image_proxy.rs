@@ -0,0 +1,82 @@
+use crate::app::AppState;
+use crate::image_proxy::etag_matches;
+use crate::middleware::real_ip::RealIp;
+use crate::rate_limiter::{LimitedAction, RateLimitKey};
+use crate::task::spawn_blocking;
+use crate::util::errors::{api_error, not_found, AppResult, ErrorCode};
+use crate::util::RequestUtils;
+use axum::http::request::Parts;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+
+/// How long the browsers keep the proxied images, in seconds.
+const MAX_AGE: u64 = 86400;
+
+/// Handles the `GET /images/proxy` route.
+///
+/// Serves the image at `?url=` from the cache of the proxy, see
+/// [`crate::image_proxy`]. The route is not found when the proxy is disabled.
+/// Anonymous, so the requests are rate limited by IP address.
+pub async fn proxy(app: AppState, req: Parts) -> AppResult<Response> {
+	let Some(image_proxy) = &app.image_proxy else {
+		return Err(not_found());
+	};
+
+	let query = req.query();
+	let url = query
+		.get("url")
+		.ok_or_else(|| api_error(ErrorCode::MissingValue, "the `url` parameter is required"))?;
+	let url = image_proxy.parse_url(url)?;
+
+	let real_ip = req.extensions.get::<RealIp>().map(|ip| **ip);
+	let conn = app.db_write().await?;
+	let image = spawn_blocking(move || {
+		// The connection is released before fetching the image.
+		{
+			let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+			if let Some(ip) = real_ip {
+				app.rate_limiter.check_rate_limit(
+					RateLimitKey::Ip(ip),
+					LimitedAction::ImageProxy,
+					conn,
+				)?;
+			}
+		}
+
+		let image_proxy = app.image_proxy.as_ref().ok_or_else(not_found)?;
+		AppResult::Ok(image_proxy.get(&url)?)
+	})
+	.await?;
+
+	let cache_control = format!("public, max-age={MAX_AGE}");
+
+	if etag_matches(req.headers.get(header::IF_NONE_MATCH), &image.etag) {
+		return Ok((
+			StatusCode::NOT_MODIFIED,
+			[
+				(header::ETAG, image.etag),
+				(header::CACHE_CONTROL, cache_control),
+			],
+		)
+			.into_response());
+	}
+
+	Ok((
+		StatusCode::OK,
+		[
+			(header::CONTENT_TYPE, image.content_type),
+			(header::ETAG, image.etag),
+			(header::CACHE_CONTROL, cache_control),
+			// The images are served from our origin, they must never be
+			// interpreted as anything else.
+			(header::X_CONTENT_TYPE_OPTIONS, "nosniff".into()),
+			(
+				header::CONTENT_SECURITY_POLICY,
+				"default-src 'none'; sandbox".into(),
+			),
+		],
+		image.body,
+	)
+		.into_response())
+}
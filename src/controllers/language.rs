@@ -0,0 +1,34 @@
+use crate::app::AppState;
+use crate::models::language::Language;
+use crate::task::spawn_blocking;
+use crate::util::errors::AppResult;
+use crate::views::EncodableLanguage;
+use axum::Json;
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
+
+/// Handles the `GET /languages` route.
+///
+/// Lists the languages of the registry, sorted by name, together with how many
+/// approved bots speak each of them.
+pub async fn index(app: AppState) -> AppResult<Json<Value>> {
+	let conn = app.db_read().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let counts = Language::bot_counts(conn)?;
+
+		let mut languages = Language::all()
+			.map(|language| EncodableLanguage {
+				code: language.code,
+				name: language.name,
+				bots_cnt: counts.get(language.code).copied().unwrap_or_default(),
+			})
+			.collect::<Vec<_>>();
+
+		languages.sort_by_key(|language| language.name);
+
+		Ok(Json(json!({ "languages": languages })))
+	})
+	.await
+}
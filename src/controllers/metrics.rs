@@ -0,0 +1,43 @@
+use crate::app::AppState;
+use crate::task::spawn_blocking;
+use crate::util::errors::{custom, not_found, AppResult, BoxedAppError};
+use axum::http::request::Parts;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum_extra::headers::authorization::Bearer;
+use axum_extra::headers::{Authorization, HeaderMapExt};
+use prometheus::{Encoder, TextEncoder};
+
+/// Handles the `GET /metrics` route.
+pub async fn prometheus(app: AppState, req: Parts) -> AppResult<Response> {
+	if let Some(expected_token) = &app.config.metrics_authorization_token {
+		let provided_token = req
+			.headers
+			.typed_get::<Authorization<Bearer>>()
+			.map(|header| header.token().to_string());
+
+		if provided_token.as_deref() != Some(expected_token.as_str()) {
+			return Err(custom(
+				StatusCode::UNAUTHORIZED,
+				"Invalid or missing metrics authorization token",
+			));
+		}
+	} else {
+		// To avoid accidentally leaking metrics if the environment variable is not set, prevent
+		// access to the metrics endpoint if the authorization token is not configured.
+		return Err(not_found());
+	}
+
+	let metrics =
+		spawn_blocking(move || Ok::<_, BoxedAppError>(app.instance_metrics.gather(&app)?)).await?;
+
+	let mut output = Vec::new();
+	TextEncoder::new().encode(&metrics, &mut output)?;
+
+	Ok((
+		StatusCode::OK,
+		[(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+		output,
+	)
+		.into_response())
+}
@@ -1,3 +1,4 @@
+use crate::models::audit::{AuditAction, NewAuditEntry};
 use crate::models::ApiToken;
 use crate::schema::api_tokens;
 use crate::util::rfc3339;
@@ -6,8 +7,9 @@ use crate::views::EncodableApiTokenWithToken;
 use crate::app::AppState;
 use crate::auth::AuthCheck;
 use crate::models::token::EndpointScope;
+use crate::rate_limiter::{LimitedAction, RateLimitKey};
 use crate::task::spawn_blocking;
-use crate::util::errors::{bad_request, AppResult};
+use crate::util::errors::{api_error, invalid_field, AppResult, ErrorCode};
 use axum::extract::{Path, Query};
 use axum::http::request::Parts;
 use axum::http::StatusCode;
@@ -86,7 +88,11 @@ pub async fn new(
 	Json(new): Json<NewApiTokenRequest>,
 ) -> AppResult<Json<Value>> {
 	if new.api_token.name.is_empty() {
-		return Err(bad_request("name must have a value"));
+		return Err(invalid_field(
+			"/api_token/name",
+			ErrorCode::MissingValue,
+			"name must have a value",
+		));
 	}
 
 	let conn = app.db_write().await?;
@@ -95,7 +101,8 @@ pub async fn new(
 
 		let auth = AuthCheck::default().check(&parts, conn)?;
 		if auth.api_token_id().is_some() {
-			return Err(bad_request(
+			return Err(api_error(
+				ErrorCode::ApiTokenNotAllowed,
 				"cannot use an API token to create a new API token",
 			));
 		}
@@ -106,30 +113,62 @@ pub async fn new(
 		let max_token_per_user = 500;
 		let count: i64 = ApiToken::belonging_to(user).count().get_result(conn)?;
 		if count >= max_token_per_user {
-			return Err(bad_request(format!(
-				"maximum tokens per user is: {max_token_per_user}"
-			)));
+			return Err(api_error(
+				ErrorCode::TokenLimitReached,
+				format!("maximum tokens per user is: {max_token_per_user}"),
+			));
 		}
 
+		app.rate_limiter.check_rate_limit(
+			RateLimitKey::User(&user.id),
+			LimitedAction::TokenCreate,
+			conn,
+		)?;
+
 		let endpoint_scopes = new
 			.api_token
 			.endpoint_scopes
 			.map(|scopes| {
 				scopes
 					.into_iter()
-					.map(|scope| EndpointScope::try_from(scope.as_bytes()))
+					.enumerate()
+					.map(|(index, scope)| {
+						EndpointScope::try_from(scope.as_bytes()).map_err(|_err| {
+							invalid_field(
+								format!("/api_token/endpoint_scopes/{index}"),
+								ErrorCode::InvalidValue,
+								"invalid endpoint scope",
+							)
+						})
+					})
 					.collect::<Result<Vec<_>, _>>()
 			})
-			.transpose()
-			.map_err(|_err| bad_request("invalid endpoint scope"))?;
+			.transpose()?;
 
-		let api_token = ApiToken::insert_with_scopes(
-			conn,
-			user.id.as_str(),
-			&new.api_token.name,
-			endpoint_scopes,
-			new.api_token.expired_at,
-		)?;
+		let api_token = conn.transaction(|conn| {
+			let api_token = ApiToken::insert_with_scopes(
+				conn,
+				user.id.as_str(),
+				&new.api_token.name,
+				endpoint_scopes,
+				new.api_token.expired_at,
+			)?;
+
+			NewAuditEntry::new(
+				&parts,
+				&auth,
+				AuditAction::TokenCreate,
+				format!("token:{}", api_token.model.id),
+			)
+			.after(json!({
+				"name": api_token.model.token_name,
+				"endpoint_scopes": api_token.model.endpoint_scopes,
+				"expired_at": api_token.model.expired_at,
+			}))
+			.insert(conn)?;
+
+			QueryResult::Ok(api_token)
+		})?;
 
 		let api_token = EncodableApiTokenWithToken::from(api_token);
 
@@ -164,9 +203,20 @@ pub async fn revoke(app: AppState, Path(id): Path<i32>, req: Parts) -> AppResult
 
 		let auth = AuthCheck::default().check(&req, conn)?;
 		let user = auth.user();
-		diesel::update(ApiToken::belonging_to(user).find(id))
-			.set(api_tokens::revoked.eq(true))
-			.execute(conn)?;
+
+		conn.transaction(|conn| {
+			let revoked = diesel::update(ApiToken::belonging_to(user).find(id))
+				.filter(api_tokens::revoked.eq(false))
+				.set(api_tokens::revoked.eq(true))
+				.execute(conn)?;
+
+			if revoked > 0 {
+				NewAuditEntry::new(&req, &auth, AuditAction::TokenRevoke, format!("token:{id}"))
+					.insert(conn)?;
+			}
+
+			QueryResult::Ok(())
+		})?;
 
 		Ok(Json(json!({})))
 	})
@@ -182,11 +232,26 @@ pub async fn revoke_current(app: AppState, req: Parts) -> AppResult<Response> {
 		let auth = AuthCheck::default().check(&req, conn)?;
 		let api_token_id = auth
 			.api_token_id()
-			.ok_or_else(|| bad_request("token not provided"))?;
+			.ok_or_else(|| api_error(ErrorCode::TokenNotProvided, "token not provided"))?;
+
+		conn.transaction(|conn| {
+			let revoked = diesel::update(api_tokens::table.find(api_token_id))
+				.filter(api_tokens::revoked.eq(false))
+				.set(api_tokens::revoked.eq(true))
+				.execute(conn)?;
+
+			if revoked > 0 {
+				NewAuditEntry::new(
+					&req,
+					&auth,
+					AuditAction::TokenRevoke,
+					format!("token:{api_token_id}"),
+				)
+				.insert(conn)?;
+			}
 
-		diesel::update(api_tokens::table.filter(api_tokens::id.eq(api_token_id)))
-			.set(api_tokens::revoked.eq(true))
-			.execute(conn)?;
+			QueryResult::Ok(())
+		})?;
 
 		Ok(StatusCode::NO_CONTENT.into_response())
 	})
@@ -1,3 +1,13 @@
+use crate::controllers::helpers::images::{discard, process, store};
+use crate::controllers::helpers::pagination::{Paginated, PaginationOptions};
+use crate::controllers::helpers::{ok_true, Paginate};
+use crate::models::image::{ImageKind, NewImage, StoredImage};
+use crate::models::Image;
+use crate::rate_limiter::{LimitedAction, RateLimitKey};
+use crate::schema::{bot_rating_replies, bot_ratings, users};
+use crate::util::errors::not_found;
+use crate::util::RequestUtils;
+use crate::views::{EncodableBotRating, EncodableImage};
 use crate::{
 	app::AppState,
 	auth::AuthCheck,
@@ -6,11 +16,15 @@ use crate::{
 	views::{EncodableMe, EncodablePrivateUser},
 };
 use crate::{
-	models::{BotOwner, User},
+	models::{BotOwner, BotRating, BotRatingReply, User},
 	views::OwnedBot,
 };
+use axum::body::Bytes;
+use axum::response::Response;
 use axum::{http::request::Parts, Json};
+use diesel::prelude::*;
 use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use serde_json::Value;
 
 /// Handles the `GET /me` route.
 pub async fn me(app: AppState, req: Parts) -> AppResult<Json<EncodableMe>> {
@@ -28,9 +42,131 @@ pub async fn me(app: AppState, req: Parts) -> AppResult<Json<EncodableMe>> {
 		let owned_bots: Vec<OwnedBot> = owned_bots.into_iter().map(OwnedBot::from).collect();
 
 		Ok(Json(EncodableMe {
-			user: EncodablePrivateUser::from(user),
+			user: EncodablePrivateUser::from(user, &app.storage),
 			owned_bots,
 		}))
 	})
 	.await
 }
+
+/// Handles the `GET /me/ratings` route.
+pub async fn ratings(app: AppState, req: Parts) -> AppResult<Json<Value>> {
+	let options = PaginationOptions::builder().gather(&req)?;
+
+	let conn = app.db_read_prefer_primary().await?;
+	spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let auth = AuthCheck::only_cookie().check(&req, conn)?;
+		let user = auth.user();
+
+		let data: Paginated<(BotRating, Option<BotRatingReply>)> = BotRating::belonging_to(user)
+			.left_join(bot_rating_replies::table)
+			.select((
+				bot_ratings::all_columns,
+				bot_rating_replies::all_columns.nullable(),
+			))
+			.order(bot_ratings::id.desc())
+			.pages_pagination(options)
+			.load(conn)?;
+
+		let total = data.total();
+		let next_page = data.next_page_params().map(|p| req.query_with_params(p));
+		let prev_page = data.prev_page_params().map(|p| req.query_with_params(p));
+
+		let ratings = data
+			.into_iter()
+			.map(|(rating, reply)| {
+				EncodableBotRating::from(rating, user.clone(), reply, &app.storage)
+			})
+			.collect::<Vec<_>>();
+
+		Ok(Json(json!({
+			"ratings": ratings,
+			"meta": {
+				"total": total,
+				"next_page": next_page,
+				"prev_page": prev_page,
+			},
+		})))
+	})
+	.await
+}
+
+/// Handles the `PUT /me/banner` route.
+///
+/// The body is the image file, which replaces the current banner of the user.
+pub async fn update_banner(app: AppState, req: Parts, body: Bytes) -> AppResult<Json<Value>> {
+	let user_id = {
+		let app = app.clone();
+		let conn = app.db_write().await?;
+
+		spawn_blocking(move || {
+			let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+			let user_id = AuthCheck::only_cookie().check(&req, conn)?.user_id();
+
+			app.rate_limiter.check_rate_limit(
+				RateLimitKey::User(&user_id),
+				LimitedAction::ImageUpload,
+				conn,
+			)?;
+
+			AppResult::Ok(user_id)
+		})
+		.await?
+	};
+
+	let processed = process(ImageKind::UserBanner, body).await?;
+	let new_image = NewImage::for_user(ImageKind::UserBanner, &user_id, &processed);
+
+	let banner = new_image.variant_key(new_image.width);
+
+	let image = store(&app, new_image, processed, move |conn, new_image| {
+		conn.transaction(|conn| {
+			let previous = Image::user_banner(conn, &user_id)?;
+			if let Some(previous) = &previous {
+				previous.delete(conn)?;
+			}
+
+			let image = new_image.insert(conn)?;
+
+			diesel::update(users::table.find(&user_id))
+				.set(users::banner.eq(&banner))
+				.execute(conn)?;
+
+			Ok((image, previous))
+		})
+	})
+	.await?;
+
+	Ok(Json(json!({
+		"banner": EncodableImage::new(&image, &app.storage),
+	})))
+}
+
+/// Handles the `DELETE /me/banner` route.
+pub async fn delete_banner(app: AppState, req: Parts) -> AppResult<Response> {
+	let conn = app.db_write().await?;
+	let deleted = spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+
+		let user_id = AuthCheck::only_cookie().check(&req, conn)?.user_id();
+
+		conn.transaction(|conn| {
+			let banner = Image::user_banner(conn, &user_id)?.ok_or_else(not_found)?;
+			banner.delete(conn)?;
+
+			diesel::update(users::table.find(&user_id))
+				.set(users::banner.eq(None::<String>))
+				.execute(conn)?;
+
+			AppResult::Ok(banner)
+		})
+	})
+	.await?;
+
+	discard(&app.storage, deleted.object_keys()).await;
+
+	ok_true()
+}
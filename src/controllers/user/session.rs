@@ -1,12 +1,16 @@
 use crate::app::AppState;
+use crate::middleware::real_ip::RealIp;
+use crate::middleware::request_id::{ForwardRequestId, RequestId};
 use crate::middleware::session::encode;
 use crate::middleware::{log_request::RequestLogExt, session::SessionExtension};
 use crate::models::user::NewUser;
 use crate::models::util::diesel::Conn;
 use crate::models::User;
+use crate::rate_limiter::{LimitedAction, RateLimitKey};
 use crate::schema::users;
 use crate::task::spawn_blocking;
-use crate::util::errors::{bad_request, server_error, AppResult, BoxedAppError, ReadOnlyMode};
+use crate::util::errors::{api_error, AppResult, BoxedAppError, ErrorCode, ReadOnlyMode};
+use crate::util::tracing::{trace_context_headers, PropagateTraceContext};
 use crate::views::EncodableMe;
 use axum::extract::{FromRequestParts, Query};
 use axum::http::request::Parts;
@@ -19,6 +23,7 @@ use oauth2::{
 };
 use oauth2::{AuthUrl, ClientId, ClientSecret, RedirectUrl, TokenUrl};
 use serde_json::Value;
+use tracing::info_span;
 pub const COOKIE_AUTH_CSRF_STATE: &str = "auth_csrf_state";
 pub const COOKIE_AUTH_CODE_VERIFIER: &str = "auth_code_verifier";
 
@@ -35,7 +40,7 @@ struct DiscordUser {
 	id: String,
 	username: String,
 
-	// For get the actual image we need to use: "https://cdn.discordapp.com/avatars/{id}/{avatar_hash}.png"
+	// The URL of the image is built with `crate::discord::cdn::CdnUrl`.
 	#[serde(rename = "avatar")]
 	avatar_hash: String,
 }
@@ -88,6 +93,7 @@ pub async fn authorize(
 ) -> AppResult<Json<Value>> {
 	let app_clone = app.clone();
 	let request_log = req.request_log().clone();
+	let real_ip = req.extensions.get::<RealIp>().map(|ip| **ip);
 
 	let conn = app.db_write().await?;
 	let res = spawn_blocking(move || {
@@ -98,7 +104,7 @@ pub async fn authorize(
 			.as_ref()
 			.map_or(false, |s| s.secret() == &query.state)
 		{
-			return Err(bad_request("Invalid CSRF state"));
+			return Err(api_error(ErrorCode::InvalidCsrfState, "Invalid CSRF state"));
 		}
 
 		let pkce_code_verifier = session
@@ -106,28 +112,65 @@ pub async fn authorize(
 			.map(PkceCodeVerifier::new);
 
 		if pkce_code_verifier.is_none() {
-			return Err(bad_request("Missing code verifier"));
+			return Err(api_error(
+				ErrorCode::MissingCodeVerifier,
+				"Missing code verifier",
+			));
+		}
+
+		if let Some(ip) = real_ip {
+			app.rate_limiter
+				.check_rate_limit(RateLimitKey::Ip(ip), LimitedAction::Login, conn)?;
 		}
 
 		let client = get_oauth_client(&app)?;
 		let code = AuthorizationCode::new(query.code);
 
-		let token_response = client
-			.exchange_code(code)
-			.set_pkce_verifier(pkce_code_verifier.unwrap())
-			.request(http_client)?;
+		let token_response =
+			info_span!("discord.oauth2_token", otel.kind = "client").in_scope(|| {
+				client
+					.exchange_code(code)
+					.set_pkce_verifier(pkce_code_verifier.unwrap())
+					.request(|mut request: oauth2::HttpRequest| {
+						let mut headers = trace_context_headers();
+						if let Some(request_id) = RequestId::current() {
+							headers.insert("x-request-id".into(), request_id.to_string());
+						}
+
+						for (name, value) in headers {
+							if let (Ok(name), Ok(value)) = (
+								oauth2::http::HeaderName::from_bytes(name.as_bytes()),
+								oauth2::http::HeaderValue::from_str(&value),
+							) {
+								request.headers.insert(name, value);
+							}
+						}
+
+						http_client(request)
+					})
+			});
+		app.instance_metrics
+			.discord_request("oauth2_token", &token_response);
+		let token_response = token_response?;
 
 		let access_token = token_response.access_token().secret();
-		let discord_user = app
-			.http
-			.get("https://discord.com/api/users/@me")
-			.bearer_auth(access_token)
-			.send()?
-			.json::<DiscordUser>()
-			.map_err(|err| {
-				request_log.add("cause", err);
-				server_error("Error obtaining token")
-			})?;
+		let discord_user =
+			info_span!("discord.current_user", otel.kind = "client").in_scope(|| {
+				app.http
+					.get("https://discord.com/api/users/@me")
+					.bearer_auth(access_token)
+					.forward_request_id()
+					.propagate_trace_context()
+					.send()
+					.and_then(|response| response.json::<DiscordUser>())
+			});
+		app.instance_metrics
+			.discord_request("current_user", &discord_user);
+
+		let discord_user = discord_user.map_err(|err| {
+			request_log.add("cause", err);
+			api_error(ErrorCode::DiscordUnavailable, "Error obtaining token")
+		})?;
 
 		let user = save_user_to_database(&discord_user, access_token, conn)?;
 
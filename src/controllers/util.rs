@@ -1,7 +1,6 @@
 use crate::middleware::app::RequestApp;
 use crate::middleware::log_request::RequestLogExt;
-use crate::util::errors::forbidden;
-use crate::util::errors::AppResult;
+use crate::util::errors::{api_error, AppResult, ErrorCode};
 use crate::util::BytesRequest;
 use axum::http::request::Parts;
 use axum::http::{Extensions, HeaderMap, HeaderValue, Method, Request, Uri, Version};
@@ -27,7 +26,7 @@ pub fn verify_origin<T: RequestPartsExt>(req: &T) -> AppResult<()> {
 
 		req.request_log().add("cause", error_message);
 
-		return Err(forbidden("invalid origin header"));
+		return Err(api_error(ErrorCode::InvalidOrigin, "invalid origin header"));
 	}
 	Ok(())
 }
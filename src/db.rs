@@ -1,14 +1,20 @@
+use diesel::connection::{Instrumentation, InstrumentationEvent};
 use diesel::{Connection, ConnectionResult, PgConnection, QueryResult};
 use diesel_async::pooled_connection::deadpool::{Hook, HookError};
 use diesel_async::pooled_connection::ManagerConfig;
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 use secrecy::ExposeSecret;
 use std::time::Duration;
 use tokio_postgres::NoTls;
+use tracing::{field, info_span, Span};
 use url::Url;
 
 use crate::config;
 
+/// The migrations of the `migrations/` directory, embedded at compile time.
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
 pub fn oneoff_connection_with_config(
 	config: &config::database_pools::DatabasePools,
 ) -> ConnectionResult<PgConnection> {
@@ -106,3 +112,105 @@ impl From<ConnectionConfig> for Hook<AsyncPgConnection> {
 		})
 	}
 }
+
+/// The maximum length of the SQL recorded in the query spans.
+const MAX_STATEMENT_LENGTH: usize = 2000;
+
+/// Records a `db.query` span for every query of the connections created
+/// from now on, exported with the other spans when OpenTelemetry is enabled.
+pub fn instrument_queries() {
+	let result =
+		diesel::connection::set_default_instrumentation(|| Some(Box::new(QuerySpans::default())));
+
+	if let Err(error) = result {
+		tracing::warn!(%error, "Failed to instrument the database queries");
+	}
+}
+
+#[derive(Default)]
+struct QuerySpans {
+	current: Option<Span>,
+}
+
+impl Instrumentation for QuerySpans {
+	fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+		match event {
+			InstrumentationEvent::StartQuery { query, .. } => {
+				let statement = sanitize_sql(&query.to_string());
+				let operation = statement.split_whitespace().next().unwrap_or_default();
+
+				self.current = Some(info_span!(
+					"db.query",
+					otel.name = %operation,
+					otel.kind = "client",
+					otel.status_code = field::Empty,
+					db.system = "postgresql",
+					db.operation = %operation,
+					db.statement = %statement,
+					error.message = field::Empty,
+				));
+			}
+			InstrumentationEvent::FinishQuery { error, .. } => {
+				if let (Some(span), Some(error)) = (self.current.take(), error) {
+					span.record("otel.status_code", "error");
+					span.record("error.message", field::display(error));
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+/// Removes the values from a query before it is recorded: the binds that
+/// diesel appends after the SQL, and the string literals.
+fn sanitize_sql(query: &str) -> String {
+	let sql = query.split(" -- binds: ").next().unwrap_or_default();
+
+	let mut sanitized = String::with_capacity(sql.len());
+	let mut chars = sql.chars().peekable();
+	while let Some(c) = chars.next() {
+		if c != '\'' {
+			sanitized.push(c);
+			continue;
+		}
+
+		// Skips the literal, where `''` is an escaped quote.
+		while let Some(c) = chars.next() {
+			if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+				break;
+			}
+		}
+		sanitized.push_str("'?'");
+	}
+
+	if sanitized.len() > MAX_STATEMENT_LENGTH {
+		let mut end = MAX_STATEMENT_LENGTH;
+		while !sanitized.is_char_boundary(end) {
+			end -= 1;
+		}
+		sanitized.truncate(end);
+		sanitized.push_str("...");
+	}
+
+	sanitized
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sanitize_sql_removes_values() {
+		assert_eq!(
+			sanitize_sql(
+				r#"SELECT "users"."id" FROM "users" WHERE "users"."id" = $1 -- binds: ["123"]"#
+			),
+			r#"SELECT "users"."id" FROM "users" WHERE "users"."id" = $1"#
+		);
+		assert_eq!(
+			sanitize_sql("SET application_name = 'izumo'"),
+			"SET application_name = '?'"
+		);
+		assert_eq!(sanitize_sql("SELECT 'it''s', 1"), "SELECT '?', 1");
+	}
+}
@@ -0,0 +1,54 @@
+//! Clients for the parts of the Discord API used by dbots.fun.
+
+use crate::config::discord::DiscordConfig;
+use crate::middleware::request_id::ForwardRequestId;
+use crate::util::tracing::PropagateTraceContext;
+use reqwest::blocking::Client;
+use tracing::instrument;
+
+pub mod cdn;
+pub mod invite;
+
+#[derive(Serialize, Deserialize)]
+pub struct APIBot {
+	pub application: Application,
+	pub bot: Bot,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Application {
+	pub id: String,
+	pub name: String,
+	pub icon: String,
+	pub description: String,
+	pub is_verified: bool,
+	pub bot_public: bool,
+	// maybe I'm going to use this in a future.
+	// pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Bot {
+	pub id: String,
+	pub username: String,
+	pub avatar: String,
+	pub bot: bool,
+	pub approximate_guild_count: i32,
+}
+
+/// Gets information about the bot directly from the Discord API.
+#[instrument(name = "discord.get_bot_information", skip_all, fields(otel.kind = "client", %bot_id))]
+pub fn get_bot_information(
+	http: &Client,
+	config: &DiscordConfig,
+	bot_id: &str,
+) -> reqwest::Result<APIBot> {
+	http.get(format!(
+		"https://discord.com/api/v9/oauth2/authorize?client_id={bot_id}&scope=bot"
+	))
+	.header("Authorization", &config.user_token)
+	.forward_request_id()
+	.propagate_trace_context()
+	.send()?
+	.json::<APIBot>()
+}
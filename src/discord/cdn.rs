@@ -0,0 +1,203 @@
+//! URLs of the images hosted on the Discord CDN.
+//!
+//! See <https://discord.com/developers/docs/reference#image-formatting>.
+
+use std::fmt;
+
+const BASE_URL: &str = "https://cdn.discordapp.com";
+
+/// The hosts the images of Discord are served from.
+pub const HOSTS: &[&str] = &["cdn.discordapp.com", "media.discordapp.net"];
+
+/// The number of default avatars, picked from the ID of the user.
+const DEFAULT_AVATARS: u64 = 6;
+
+const MIN_SIZE: u16 = 16;
+const MAX_SIZE: u16 = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+	Png,
+	Jpeg,
+	WebP,
+	Gif,
+}
+
+impl Format {
+	fn extension(self) -> &'static str {
+		match self {
+			Format::Png => "png",
+			Format::Jpeg => "jpg",
+			Format::WebP => "webp",
+			Format::Gif => "gif",
+		}
+	}
+}
+
+/// The URL of an image on the CDN, in its default format (GIF for animated
+/// images, PNG otherwise) and size unless requested otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdnUrl {
+	/// The path of the image, without its extension.
+	path: String,
+	/// Whether the image is animated, known from the `a_` prefix of its hash.
+	animated: bool,
+	format: Option<Format>,
+	size: Option<u16>,
+}
+
+impl CdnUrl {
+	/// The avatar of a user or a bot.
+	pub fn avatar(user_id: &str, hash: &str) -> Self {
+		Self::hashed(format!("avatars/{user_id}"), hash)
+	}
+
+	/// The profile banner of a user or a bot.
+	pub fn banner(user_id: &str, hash: &str) -> Self {
+		Self::hashed(format!("banners/{user_id}"), hash)
+	}
+
+	/// The default avatar shown for the users without an avatar, which is
+	/// only available as PNG.
+	pub fn default_avatar(user_id: &str) -> Self {
+		let index = user_id
+			.parse::<u64>()
+			.map_or(0, |id| (id >> 22) % DEFAULT_AVATARS);
+
+		Self {
+			path: format!("embed/avatars/{index}"),
+			animated: false,
+			format: None,
+			size: None,
+		}
+	}
+
+	/// The avatar of the user, or the default avatar if they have none.
+	pub fn user_avatar(user_id: &str, hash: Option<&str>) -> Self {
+		match hash.filter(|hash| !hash.is_empty()) {
+			Some(hash) => Self::avatar(user_id, hash),
+			None => Self::default_avatar(user_id),
+		}
+	}
+
+	fn hashed(path: String, hash: &str) -> Self {
+		Self {
+			animated: hash.starts_with("a_"),
+			path: format!("{path}/{hash}"),
+			format: None,
+			size: None,
+		}
+	}
+
+	pub fn is_animated(&self) -> bool {
+		self.animated
+	}
+
+	/// Requests the image in this size, rounded up to the next power of two
+	/// between 16 and 4096.
+	pub fn size(mut self, size: u16) -> Self {
+		self.size = Some(size.clamp(MIN_SIZE, MAX_SIZE).next_power_of_two());
+		self
+	}
+
+	/// Requests the image in this format. Still images are never requested
+	/// as GIF, which the CDN refuses.
+	pub fn format(mut self, format: Format) -> Self {
+		if format != Format::Gif || self.animated {
+			self.format = Some(format);
+		}
+		self
+	}
+
+	/// Requests the first frame of an animated image.
+	pub fn still(mut self) -> Self {
+		self.animated = false;
+		if self.format == Some(Format::Gif) {
+			self.format = None;
+		}
+		self
+	}
+}
+
+impl fmt::Display for CdnUrl {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let format = self.format.unwrap_or(match self.animated {
+			true => Format::Gif,
+			false => Format::Png,
+		});
+
+		write!(f, "{BASE_URL}/{}.{}", self.path, format.extension())?;
+
+		if let Some(size) = self.size {
+			write!(f, "?size={size}")?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn builds_avatars() {
+		assert_eq!(
+			CdnUrl::avatar("80351110224678912", "8342729096ea3675442027381ff50dfe").to_string(),
+			"https://cdn.discordapp.com/avatars/80351110224678912/8342729096ea3675442027381ff50dfe.png"
+		);
+		assert_eq!(
+			CdnUrl::user_avatar("80351110224678912", Some("a_1269e74af4df7417b13759eae50c83dc"))
+				.size(100)
+				.to_string(),
+			"https://cdn.discordapp.com/avatars/80351110224678912/a_1269e74af4df7417b13759eae50c83dc.gif?size=128"
+		);
+		assert_eq!(
+			CdnUrl::banner("1", "a_abc").still().size(9000).to_string(),
+			"https://cdn.discordapp.com/banners/1/a_abc.png?size=4096"
+		);
+	}
+
+	#[test]
+	fn builds_default_avatars() {
+		// (80351110224678912 >> 22) % 6 == 5
+		assert_eq!(
+			CdnUrl::user_avatar("80351110224678912", None).to_string(),
+			"https://cdn.discordapp.com/embed/avatars/5.png"
+		);
+		assert_eq!(
+			CdnUrl::user_avatar("80351110224678912", Some("")),
+			CdnUrl::default_avatar("80351110224678912")
+		);
+		assert_eq!(
+			CdnUrl::default_avatar("not a snowflake").to_string(),
+			"https://cdn.discordapp.com/embed/avatars/0.png"
+		);
+	}
+
+	#[test]
+	fn picks_formats() {
+		let still = CdnUrl::avatar("1", "abc");
+		let animated = CdnUrl::avatar("1", "a_abc");
+
+		assert!(!still.is_animated());
+		assert!(animated.is_animated());
+
+		assert_eq!(
+			still.clone().format(Format::Gif).to_string(),
+			"https://cdn.discordapp.com/avatars/1/abc.png"
+		);
+		assert_eq!(
+			still.format(Format::WebP).size(64).to_string(),
+			"https://cdn.discordapp.com/avatars/1/abc.webp?size=64"
+		);
+		assert_eq!(
+			animated.clone().format(Format::WebP).to_string(),
+			"https://cdn.discordapp.com/avatars/1/a_abc.webp"
+		);
+		assert_eq!(
+			animated.format(Format::Gif).still().to_string(),
+			"https://cdn.discordapp.com/avatars/1/a_abc.png"
+		);
+	}
+}
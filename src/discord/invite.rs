@@ -0,0 +1,261 @@
+//! Discord OAuth2 URLs that add a bot to a server, and the permissions they
+//! request.
+//!
+//! See <https://discord.com/developers/docs/topics/oauth2#bot-authorization-flow>.
+
+use std::fmt;
+use url::Url;
+
+/// The scope that adds the bot user to the server.
+pub const SCOPE_BOT: &str = "bot";
+/// The scope that lets the bot create slash commands in the server.
+pub const SCOPE_APPLICATIONS_COMMANDS: &str = "applications.commands";
+
+/// The hosts of the Discord OAuth2 URLs.
+const HOSTS: &[&str] = &["discord.com", "discordapp.com"];
+
+/// The scopes a bot invite may request.
+pub const SCOPES: &[&str] = &[SCOPE_BOT, SCOPE_APPLICATIONS_COMMANDS];
+
+/// The named permissions of the bitfield, in bit order.
+///
+/// See <https://discord.com/developers/docs/topics/permissions#permissions-bitwise-permission-flags>.
+const PERMISSIONS: &[(&str, u32)] = &[
+	("CREATE_INSTANT_INVITE", 0),
+	("KICK_MEMBERS", 1),
+	("BAN_MEMBERS", 2),
+	("ADMINISTRATOR", 3),
+	("MANAGE_CHANNELS", 4),
+	("MANAGE_GUILD", 5),
+	("ADD_REACTIONS", 6),
+	("VIEW_AUDIT_LOG", 7),
+	("PRIORITY_SPEAKER", 8),
+	("STREAM", 9),
+	("VIEW_CHANNEL", 10),
+	("SEND_MESSAGES", 11),
+	("SEND_TTS_MESSAGES", 12),
+	("MANAGE_MESSAGES", 13),
+	("EMBED_LINKS", 14),
+	("ATTACH_FILES", 15),
+	("READ_MESSAGE_HISTORY", 16),
+	("MENTION_EVERYONE", 17),
+	("USE_EXTERNAL_EMOJIS", 18),
+	("VIEW_GUILD_INSIGHTS", 19),
+	("CONNECT", 20),
+	("SPEAK", 21),
+	("MUTE_MEMBERS", 22),
+	("DEAFEN_MEMBERS", 23),
+	("MOVE_MEMBERS", 24),
+	("USE_VAD", 25),
+	("CHANGE_NICKNAME", 26),
+	("MANAGE_NICKNAMES", 27),
+	("MANAGE_ROLES", 28),
+	("MANAGE_WEBHOOKS", 29),
+	("MANAGE_GUILD_EXPRESSIONS", 30),
+	("USE_APPLICATION_COMMANDS", 31),
+	("REQUEST_TO_SPEAK", 32),
+	("MANAGE_EVENTS", 33),
+	("MANAGE_THREADS", 34),
+	("CREATE_PUBLIC_THREADS", 35),
+	("CREATE_PRIVATE_THREADS", 36),
+	("USE_EXTERNAL_STICKERS", 37),
+	("SEND_MESSAGES_IN_THREADS", 38),
+	("USE_EMBEDDED_ACTIVITIES", 39),
+	("MODERATE_MEMBERS", 40),
+	("VIEW_CREATOR_MONETIZATION_ANALYTICS", 41),
+	("USE_SOUNDBOARD", 42),
+	("CREATE_GUILD_EXPRESSIONS", 43),
+	("CREATE_EVENTS", 44),
+	("USE_EXTERNAL_SOUNDS", 45),
+	("SEND_VOICE_MESSAGES", 46),
+	("SEND_POLLS", 49),
+	("USE_EXTERNAL_APPS", 50),
+];
+
+/// A Discord permission bitfield.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Permissions(u64);
+
+impl Permissions {
+	pub const ADMINISTRATOR: Permissions = Permissions(1 << 3);
+
+	pub fn from_bits(bits: u64) -> Self {
+		Self(bits)
+	}
+
+	/// Parses the names of the permissions, returning the first unknown name
+	/// as the error.
+	pub fn from_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<Self, &'a str> {
+		names
+			.into_iter()
+			.try_fold(Self::default(), |permissions, name| {
+				let (_, bit) = PERMISSIONS
+					.iter()
+					.find(|(known, _)| known.eq_ignore_ascii_case(name))
+					.ok_or(name)?;
+
+				Ok(Self(permissions.0 | 1 << bit))
+			})
+	}
+
+	pub fn bits(self) -> u64 {
+		self.0
+	}
+
+	pub fn contains(self, other: Permissions) -> bool {
+		self.0 & other.0 == other.0
+	}
+
+	/// The names of the permissions, ignoring the bits without a name.
+	pub fn names(self) -> Vec<&'static str> {
+		PERMISSIONS
+			.iter()
+			.filter(|(_, bit)| self.0 & 1 << bit != 0)
+			.map(|(name, _)| *name)
+			.collect()
+	}
+}
+
+impl fmt::Display for Permissions {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+/// The parts of an OAuth2 URL that adds a bot to a server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotInvite {
+	pub client_id: String,
+	pub scopes: Vec<String>,
+	pub permissions: Permissions,
+}
+
+impl BotInvite {
+	/// Parses a URL like
+	/// `https://discord.com/oauth2/authorize?client_id=…&permissions=8&scope=bot`.
+	///
+	/// Returns `None` if the URL is not a Discord OAuth2 URL, or has no or
+	/// an invalid `client_id` or `permissions`.
+	pub fn parse(value: &str) -> Option<Self> {
+		let url = Url::parse(value).ok()?;
+
+		let host = url.host_str()?;
+		let is_discord = HOSTS.iter().any(|expected| {
+			host == *expected
+				|| host
+					.strip_suffix(expected)
+					.is_some_and(|subdomain| subdomain.ends_with('.'))
+		});
+
+		let is_oauth2 = matches!(url.path(), "/oauth2/authorize" | "/api/oauth2/authorize");
+		if url.scheme() != "https" || !is_discord || !is_oauth2 {
+			return None;
+		}
+
+		let mut client_id = None;
+		let mut scopes = vec![];
+		let mut permissions = Permissions::default();
+
+		for (key, value) in url.query_pairs() {
+			match key.as_ref() {
+				"client_id" => client_id = Some(value.into_owned()),
+				"scope" => scopes = value.split_whitespace().map(Into::into).collect(),
+				"permissions" => permissions = Permissions(value.parse().ok()?),
+				_ => {}
+			}
+		}
+
+		let client_id =
+			client_id.filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))?;
+
+		Some(Self {
+			client_id,
+			scopes,
+			permissions,
+		})
+	}
+
+	/// Whether the invite adds the bot user or its slash commands.
+	pub fn adds_bot(&self) -> bool {
+		self.scopes
+			.iter()
+			.any(|scope| SCOPES.contains(&scope.as_str()))
+	}
+
+	/// The OAuth2 URL of the invite.
+	pub fn url(&self) -> String {
+		let mut url = Url::parse("https://discord.com/oauth2/authorize").expect("valid URL");
+
+		url.query_pairs_mut()
+			.append_pair("client_id", &self.client_id)
+			.append_pair("permissions", &self.permissions.to_string())
+			.append_pair("scope", &self.scopes.join(" "));
+
+		url.into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_invites() {
+		let invite = BotInvite::parse(
+			"https://discord.com/oauth2/authorize?client_id=1234567890&permissions=2048&scope=bot%20applications.commands",
+		)
+		.unwrap();
+
+		assert_eq!(invite.client_id, "1234567890");
+		assert_eq!(invite.scopes, ["bot", "applications.commands"]);
+		assert_eq!(invite.permissions.names(), ["SEND_MESSAGES"]);
+		assert!(invite.adds_bot());
+
+		let invite = BotInvite::parse(
+			"https://discordapp.com/api/oauth2/authorize?client_id=1&scope=identify",
+		)
+		.unwrap();
+		assert_eq!(invite.permissions, Permissions::default());
+		assert!(!invite.adds_bot());
+
+		assert!(BotInvite::parse("https://discord.gg/XaSVMz7eng").is_none());
+		assert!(BotInvite::parse("https://discord.com/oauth2/authorize?scope=bot").is_none());
+		assert!(BotInvite::parse("https://discord.com/oauth2/authorize?client_id=abc").is_none());
+		assert!(
+			BotInvite::parse("https://discord.com/oauth2/authorize?client_id=1&permissions=x")
+				.is_none()
+		);
+		assert!(BotInvite::parse("https://evil.com/oauth2/authorize?client_id=1").is_none());
+	}
+
+	#[test]
+	fn permission_names() {
+		let permissions = Permissions::from_names(["send_messages", "ADMINISTRATOR"]).unwrap();
+
+		assert_eq!(permissions.bits(), 2056);
+		assert!(permissions.contains(Permissions::ADMINISTRATOR));
+		assert_eq!(permissions.names(), ["ADMINISTRATOR", "SEND_MESSAGES"]);
+		assert_eq!(Permissions::from_names(["FLY"]), Err("FLY"));
+
+		// Bits without a name are kept, but not listed.
+		assert_eq!(
+			Permissions::from_bits(1 << 60 | 1).names(),
+			["CREATE_INSTANT_INVITE"]
+		);
+	}
+
+	#[test]
+	fn builds_urls() {
+		let invite = BotInvite {
+			client_id: "1234567890".into(),
+			scopes: vec!["bot".into(), "applications.commands".into()],
+			permissions: Permissions::from_bits(8),
+		};
+
+		assert_eq!(
+			invite.url(),
+			"https://discord.com/oauth2/authorize?client_id=1234567890&permissions=8&scope=bot+applications.commands"
+		);
+		assert_eq!(BotInvite::parse(&invite.url()), Some(invite));
+	}
+}
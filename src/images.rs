@@ -0,0 +1,292 @@
+//! Validation and resizing of the uploaded images.
+//!
+//! The images are decoded and encoded again, which drops their metadata
+//! (EXIF, XMP, ICC profiles…), after applying their EXIF orientation. Only the
+//! first frame of the animated images is kept.
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use std::io::Cursor;
+
+/// The quality of the JPEG images, from 1 to 100.
+const JPEG_QUALITY: u8 = 85;
+
+/// The constraints of a kind of image.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageSpec {
+	/// The maximum size of the uploaded file, in bytes.
+	pub max_bytes: usize,
+	pub min_width: u32,
+	pub min_height: u32,
+	pub max_width: u32,
+	pub max_height: u32,
+	/// The widths of the stored variants, from the largest. Images wider than
+	/// the first one are scaled down to it.
+	pub widths: &'static [u32],
+}
+
+impl ImageSpec {
+	pub const BANNER: ImageSpec = ImageSpec {
+		max_bytes: 8 * 1024 * 1024,
+		min_width: 600,
+		min_height: 200,
+		max_width: 4096,
+		max_height: 4096,
+		widths: &[1920, 960, 480],
+	};
+
+	pub const SCREENSHOT: ImageSpec = ImageSpec {
+		max_bytes: 8 * 1024 * 1024,
+		min_width: 320,
+		min_height: 180,
+		max_width: 4096,
+		max_height: 4096,
+		widths: &[1600, 800, 400],
+	};
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImageError {
+	#[error("the image must be a PNG, JPEG, WebP or GIF file")]
+	UnsupportedFormat,
+	#[error("the image must be at most {} MiB", .0 / 1024 / 1024)]
+	TooLarge(usize),
+	#[error(
+		"the image must be between {}x{} and {}x{} pixels, got {width}x{height}",
+		.spec.min_width, .spec.min_height, .spec.max_width, .spec.max_height
+	)]
+	InvalidDimensions {
+		spec: ImageSpec,
+		width: u32,
+		height: u32,
+	},
+	#[error("the image could not be decoded: {0}")]
+	Invalid(#[from] image::ImageError),
+}
+
+/// The format the images are stored in: PNG if they have transparency, JPEG
+/// otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoredFormat {
+	Png,
+	Jpeg,
+}
+
+impl StoredFormat {
+	pub fn extension(self) -> &'static str {
+		match self {
+			Self::Png => "png",
+			Self::Jpeg => "jpg",
+		}
+	}
+
+	pub fn content_type(self) -> &'static str {
+		match self {
+			Self::Png => "image/png",
+			Self::Jpeg => "image/jpeg",
+		}
+	}
+}
+
+/// A resized copy of an image, encoded in the [`StoredFormat`].
+#[derive(Debug)]
+pub struct Variant {
+	pub width: u32,
+	pub height: u32,
+	pub bytes: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct ProcessedImage {
+	pub format: StoredFormat,
+	/// The variants, from the largest.
+	pub variants: Vec<Variant>,
+}
+
+impl ProcessedImage {
+	/// The largest variant.
+	pub fn full(&self) -> &Variant {
+		&self.variants[0]
+	}
+}
+
+/// Validates the uploaded image and generates its variants.
+///
+/// This is CPU-bound, and should run on a blocking thread.
+pub fn process(spec: &ImageSpec, bytes: &[u8]) -> Result<ProcessedImage, ImageError> {
+	if bytes.len() > spec.max_bytes {
+		return Err(ImageError::TooLarge(spec.max_bytes));
+	}
+
+	let format = image::guess_format(bytes).map_err(|_| ImageError::UnsupportedFormat)?;
+	if !matches!(
+		format,
+		ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP | ImageFormat::Gif
+	) {
+		return Err(ImageError::UnsupportedFormat);
+	}
+
+	let mut decoder = ImageReader::with_format(Cursor::new(bytes), format).into_decoder()?;
+
+	// Checked before decoding, to not allocate the pixels of huge images.
+	let (width, height) = decoder.dimensions();
+	if width > spec.max_width || height > spec.max_height {
+		return Err(ImageError::InvalidDimensions {
+			spec: *spec,
+			width,
+			height,
+		});
+	}
+
+	let orientation = decoder.orientation()?;
+	let mut image = DynamicImage::from_decoder(decoder)?;
+	image.apply_orientation(orientation);
+
+	let (width, height) = (image.width(), image.height());
+	if width < spec.min_width || height < spec.min_height {
+		return Err(ImageError::InvalidDimensions {
+			spec: *spec,
+			width,
+			height,
+		});
+	}
+
+	let format = if image.color().has_alpha() {
+		StoredFormat::Png
+	} else {
+		StoredFormat::Jpeg
+	};
+
+	let full_width = width.min(spec.widths[0]);
+	let smaller = spec.widths.iter().copied().filter(|w| *w < full_width);
+
+	let variants = std::iter::once(full_width)
+		.chain(smaller)
+		.map(|width| {
+			let resized = if width == image.width() {
+				image.clone()
+			} else {
+				image.resize(width, u32::MAX, FilterType::CatmullRom)
+			};
+
+			Ok(Variant {
+				width: resized.width(),
+				height: resized.height(),
+				bytes: encode(&resized, format)?,
+			})
+		})
+		.collect::<Result<_, ImageError>>()?;
+
+	Ok(ProcessedImage { format, variants })
+}
+
+fn encode(image: &DynamicImage, format: StoredFormat) -> Result<Vec<u8>, image::ImageError> {
+	let mut bytes = vec![];
+
+	match format {
+		StoredFormat::Png => image
+			.to_rgba8()
+			.write_with_encoder(PngEncoder::new(&mut bytes))?,
+		StoredFormat::Jpeg => image
+			.to_rgb8()
+			.write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))?,
+	}
+
+	Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+	fn png(image: DynamicImage) -> Vec<u8> {
+		let mut bytes = vec![];
+		image
+			.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+			.unwrap();
+		bytes
+	}
+
+	#[test]
+	fn generates_variants() {
+		let bytes = png(RgbImage::from_pixel(1000, 400, Rgb([10, 20, 30])).into());
+		let processed = process(&ImageSpec::BANNER, &bytes).unwrap();
+
+		assert_eq!(processed.format, StoredFormat::Jpeg);
+
+		let sizes = processed
+			.variants
+			.iter()
+			.map(|variant| (variant.width, variant.height))
+			.collect::<Vec<_>>();
+		assert_eq!(sizes, [(1000, 400), (960, 384), (480, 192)]);
+
+		let decoded = image::load_from_memory(&processed.full().bytes).unwrap();
+		assert_eq!((decoded.width(), decoded.height()), (1000, 400));
+
+		// Wider images are scaled down to the largest width.
+		let bytes = png(RgbaImage::from_pixel(2400, 600, Rgba([0, 0, 0, 0])).into());
+		let processed = process(&ImageSpec::BANNER, &bytes).unwrap();
+
+		assert_eq!(processed.format, StoredFormat::Png);
+		assert_eq!(
+			(processed.full().width, processed.full().height),
+			(1920, 480)
+		);
+		assert_eq!(processed.variants.len(), 3);
+	}
+
+	#[test]
+	fn strips_metadata() {
+		let mut jpeg = vec![];
+		RgbImage::new(200, 400)
+			.write_with_encoder(JpegEncoder::new(&mut jpeg))
+			.unwrap();
+
+		// An EXIF segment with the orientation "rotate 90° clockwise".
+		let exif = b"Exif\0\0II*\0\x08\0\0\0\x01\0\x12\x01\x03\0\x01\0\0\0\x06\0\0\0\0\0\0\0";
+		let length = (exif.len() as u16 + 2).to_be_bytes();
+		let segment = [&[0xFF, 0xE1], &length[..], exif].concat();
+		jpeg.splice(2..2, segment);
+
+		let processed = process(&ImageSpec::SCREENSHOT, &jpeg).unwrap();
+		let full = processed.full();
+
+		assert_eq!((full.width, full.height), (400, 200));
+		assert!(!full.bytes.windows(4).any(|window| window == b"Exif"));
+	}
+
+	#[test]
+	fn rejects_invalid_images() {
+		assert!(matches!(
+			process(&ImageSpec::BANNER, b"\x89PNG\r\n\x1a\nbut not really"),
+			Err(ImageError::Invalid(_))
+		));
+		assert!(matches!(
+			process(&ImageSpec::BANNER, b"<svg></svg>"),
+			Err(ImageError::UnsupportedFormat)
+		));
+
+		let bytes = png(RgbImage::new(300, 300).into());
+		assert!(matches!(
+			process(&ImageSpec::BANNER, &bytes),
+			Err(ImageError::InvalidDimensions {
+				width: 300,
+				height: 300,
+				..
+			})
+		));
+
+		let spec = ImageSpec {
+			max_bytes: 16,
+			..ImageSpec::BANNER
+		};
+		assert!(matches!(
+			process(&spec, &bytes),
+			Err(ImageError::TooLarge(16))
+		));
+	}
+}
@@ -0,0 +1,33 @@
+#[macro_use]
+extern crate diesel;
+#[macro_use]
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+pub mod app;
+pub mod auth;
+pub mod blocklist;
+pub mod boot;
+pub mod config;
+pub mod controllers;
+pub mod db;
+pub mod discord;
+mod headers;
+pub mod image_proxy;
+pub mod images;
+pub mod markdown;
+pub mod metrics;
+pub mod middleware;
+pub mod models;
+pub mod rate_limiter;
+mod real_ip;
+pub mod router;
+#[rustfmt::skip]
+pub mod schema;
+pub mod sentry;
+mod sql;
+pub mod storage;
+pub mod task;
+pub mod util;
+pub mod views;
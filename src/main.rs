@@ -1,46 +1,108 @@
-#[macro_use]
-extern crate diesel;
-#[macro_use]
-extern crate serde;
-#[macro_use]
-extern crate serde_json;
-
+use clap::{Parser, Subcommand};
+use izumo::app::App;
+use izumo::config::server;
+use izumo::models::image::{DeletedImage, StoredImage};
+use izumo::router::build_handler;
+use izumo::util::signals::shutdown_signal;
+use izumo::{boot, db, sentry, task, util};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
-use tracing::info;
-
-use crate::app::App;
-use crate::config::server;
-use crate::router::build_handler;
-use crate::util::signals::shutdown_signal;
-
-mod app;
-mod auth;
-mod config;
-mod controllers;
-mod db;
-mod headers;
-mod middleware;
-mod models;
-mod real_ip;
-mod router;
-#[rustfmt::skip]
-mod schema;
-mod sentry;
-mod sql;
-mod task;
-mod util;
-mod views;
+use tracing::{info, info_span, instrument, warn, Instrument};
 
 const CORE_THREADS: usize = 4;
 
+/// How often the stored files of the images of the deleted bots and users
+/// are deleted.
+const PURGE_DELETED_IMAGES_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// How many deleted images are purged at once.
+const PURGE_DELETED_IMAGES_BATCH: i64 = 100;
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+	#[command(subcommand)]
+	command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Run the web server (default)
+	Server,
+	/// Apply the pending migrations embedded in the binary
+	Migrate {
+		/// Print the applied and pending migrations without applying them
+		#[arg(long)]
+		status: bool,
+	},
+	/// Sync `categories.toml` with the database
+	SyncCategories {
+		/// Print the changes without applying them
+		#[arg(long)]
+		dry_run: bool,
+	},
+}
+
 fn main() -> anyhow::Result<()> {
 	dotenvy::dotenv().ok();
 
+	let cli = Cli::parse();
+
 	// initialize tracing subscriber
 	util::tracing::init();
 
+	let result = match cli.command.unwrap_or(Command::Server) {
+		Command::Server => server(),
+		Command::Migrate { status } => migrate(status),
+		Command::SyncCategories { dry_run } => sync_categories(dry_run),
+	};
+
+	util::tracing::shutdown();
+
+	result
+}
+
+fn migrate(status: bool) -> anyhow::Result<()> {
+	let conn = &mut db::oneoff_connection()?;
+
+	if status {
+		print!("{}", boot::migrations::status(conn)?);
+		return Ok(());
+	}
+
+	let applied = boot::migrations::run_pending(conn)?;
+	if applied.is_empty() {
+		println!("no pending migrations");
+	}
+	for version in applied {
+		println!("applied {version}");
+	}
+
+	Ok(())
+}
+
+fn sync_categories(dry_run: bool) -> anyhow::Result<()> {
+	let fallback = boot::categories::fallback_from_environment()?;
+	let conn = &mut db::oneoff_connection()?;
+
+	let report = boot::categories::sync_with_connection(
+		boot::categories::CATEGORIES_TOML,
+		&fallback,
+		conn,
+		dry_run,
+	)?;
+
+	print!("{report}");
+	if dry_run && !report.is_empty() {
+		println!("dry run, no changes were applied");
+	}
+
+	Ok(())
+}
+
+fn server() -> anyhow::Result<()> {
 	// initialize sentry
 	let _guard = sentry::init();
 
@@ -58,6 +120,19 @@ fn main() -> anyhow::Result<()> {
 	let rt = builder.build()?;
 
 	rt.block_on(async {
+		migrate_on_startup(&app).await?;
+
+		if app.config.sync_categories && !app.config.db.are_all_read_only() {
+			sync_categories_on_startup(&app).await?;
+		}
+
+		if !app.config.db.are_all_read_only() {
+			tokio::spawn(render_outdated_descriptions(app.clone()));
+			tokio::spawn(purge_deleted_images_periodically(app.clone()));
+		}
+
+		tokio::spawn(refresh_blocklist_periodically(app.clone()));
+
 		let listener = TcpListener::bind((app.config.ip, app.config.port)).await?;
 
 		let axum_router = build_handler(app).into_make_service_with_connect_info::<SocketAddr>();
@@ -67,10 +142,182 @@ fn main() -> anyhow::Result<()> {
 
 		axum::serve(listener, axum_router)
 			.with_graceful_shutdown(shutdown_signal())
-			.await
+			.await?;
+
+		anyhow::Ok(())
 	})?;
 
 	info!("Server has gracefully shutdown!");
 
 	Ok(())
 }
+
+/// Applies the pending migrations if `RUN_MIGRATIONS` is set, and refuses to
+/// start if the database was migrated by a newer build.
+#[instrument(skip_all)]
+async fn migrate_on_startup(app: &Arc<App>) -> anyhow::Result<()> {
+	let app = app.clone();
+	let applied = task::spawn_blocking(move || {
+		let conn = &mut db::oneoff_connection_with_config(&app.config.db)?;
+
+		if app.config.run_migrations && !app.config.db.are_all_read_only() {
+			return boot::migrations::run_pending(conn);
+		}
+
+		let status = boot::migrations::status(conn)?;
+		boot::migrations::ensure_schema_not_ahead(&status)?;
+		if !status.pending.is_empty() {
+			warn!(pending = ?status.pending, "The database has pending migrations");
+		}
+
+		Ok(vec![])
+	})
+	.await?;
+
+	if !applied.is_empty() {
+		info!(?applied, "Applied pending migrations");
+	}
+
+	Ok(())
+}
+
+/// Syncs `categories.toml` with the database, when `SYNC_CATEGORIES_ON_STARTUP`
+/// is set.
+#[instrument(skip_all)]
+async fn sync_categories_on_startup(app: &App) -> anyhow::Result<()> {
+	use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+
+	let conn = app.db_write().await?;
+	let fallback = app.config.categories_fallback.clone();
+
+	let report = task::spawn_blocking(move || {
+		let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+		boot::categories::sync_with_connection(
+			boot::categories::CATEGORIES_TOML,
+			&fallback,
+			conn,
+			false,
+		)
+	})
+	.await?;
+
+	info!(
+		added = report.added.len(),
+		updated = report.updated.len(),
+		removed = report.removed.len(),
+		"Synced categories"
+	);
+
+	Ok(())
+}
+
+/// Renders the bot descriptions rendered with an older sanitizer policy or
+/// other image proxy settings, in the background so that the server doesn't
+/// wait for it to start.
+async fn render_outdated_descriptions(app: Arc<App>) {
+	use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+
+	let result = async {
+		let conn = app.db_write().await?;
+		let image_proxy = app.config.image_proxy_url.clone();
+
+		task::spawn_blocking(move || {
+			let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+			boot::descriptions::render_outdated(conn, image_proxy.as_ref())
+		})
+		.await
+	}
+	.instrument(info_span!(parent: None, "render_descriptions"))
+	.await;
+
+	match result {
+		Ok(Some(rendered)) if rendered > 0 => {
+			info!(rendered, "Rendered the bot descriptions again")
+		}
+		Ok(Some(_)) => {}
+		Ok(None) => info!("The bot descriptions are rendered by another process"),
+		Err(error) => warn!(%error, "Failed to render the bot descriptions again"),
+	}
+}
+
+/// Deletes the stored files of the images queued by the database when their
+/// bot or user is deleted.
+async fn purge_deleted_images_periodically(app: Arc<App>) {
+	use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+
+	let mut interval = tokio::time::interval(PURGE_DELETED_IMAGES_INTERVAL);
+
+	loop {
+		interval.tick().await;
+
+		let result = async {
+			let conn = app.db_write().await?;
+			let deleted = task::spawn_blocking(move || {
+				let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+				anyhow::Ok(DeletedImage::queued(conn, PURGE_DELETED_IMAGES_BATCH)?)
+			})
+			.await?;
+
+			if deleted.is_empty() {
+				return anyhow::Ok(());
+			}
+
+			let keys = deleted
+				.iter()
+				.flat_map(StoredImage::object_keys)
+				.collect::<Vec<_>>();
+			app.storage.delete(keys).await?;
+
+			// The images stay queued if their files couldn't be deleted, to be
+			// tried again on the next tick.
+			let ids = deleted.iter().map(|image| image.id).collect::<Vec<_>>();
+			let conn = app.db_write().await?;
+			task::spawn_blocking(move || {
+				let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+				anyhow::Ok(DeletedImage::dequeue(conn, &ids)?)
+			})
+			.await?;
+
+			info!(
+				images = deleted.len(),
+				"Deleted the files of deleted images"
+			);
+
+			anyhow::Ok(())
+		}
+		.instrument(info_span!(parent: None, "purge_deleted_images"))
+		.await;
+
+		if let Err(error) = result {
+			warn!(%error, "Failed to delete the files of deleted images");
+		}
+	}
+}
+
+/// Reloads the blocklist from the database every `BLOCKLIST_REFRESH_SECONDS`.
+async fn refresh_blocklist_periodically(app: Arc<App>) {
+	use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+
+	let mut interval = tokio::time::interval(app.config.blocklist_refresh_interval);
+
+	loop {
+		interval.tick().await;
+
+		let app = app.clone();
+		let result = async {
+			let conn = app.db_read_prefer_primary().await?;
+			task::spawn_blocking(move || {
+				let conn: &mut AsyncConnectionWrapper<_> = &mut conn.into();
+				app.blocklist.refresh(&app.config, conn)?;
+				anyhow::Ok(())
+			})
+			.await
+		}
+		.instrument(info_span!(parent: None, "refresh_blocklist"))
+		.await;
+
+		if let Err(error) = result {
+			warn!(%error, "Failed to refresh the blocklist");
+		}
+	}
+}
@@ -0,0 +1,254 @@
+//! Rendering of the bot descriptions from markdown to sanitized HTML.
+//!
+//! The descriptions are rendered when they are written, and stored next to
+//! their source in `bots.description_html`, so that every client shows the
+//! same, safe HTML.
+
+use ammonia::{Builder, UrlRelative};
+use pulldown_cmark::{html, Options, Parser};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use url::Url;
+
+/// The version of the sanitizer policy.
+///
+/// Bump it whenever the output of [`render`] changes: the descriptions
+/// rendered with an older version are rendered again in the background once
+/// the server starts.
+pub const POLICY_VERSION: i32 = 1;
+
+/// The HTML elements allowed in a description.
+const TAGS: &[&str] = &[
+	"a",
+	"blockquote",
+	"br",
+	"code",
+	"del",
+	"em",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"hr",
+	"img",
+	"input",
+	"li",
+	"ol",
+	"p",
+	"pre",
+	"strong",
+	"sup",
+	"table",
+	"tbody",
+	"td",
+	"th",
+	"thead",
+	"tr",
+	"ul",
+];
+
+/// The `rel` of the links, which are all written by the owners of the bots.
+const LINK_REL: &str = "nofollow noopener noreferrer ugc";
+
+/// The proxy the images of the descriptions are loaded through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageProxyUrl {
+	/// Base URL of the proxy, the images are loaded as `{url}?url={image}`.
+	pub url: Url,
+	/// The hosts the proxy loads images from, or `None` if it loads them from
+	/// any host. The images of the other hosts are hotlinked.
+	pub allowed_hosts: Option<Vec<String>>,
+}
+
+impl ImageProxyUrl {
+	fn proxies(&self, url: &Url) -> bool {
+		let Some(host) = url.host_str() else {
+			return false;
+		};
+
+		host != self.url.host_str().unwrap_or_default()
+			&& self
+				.allowed_hosts
+				.as_ref()
+				.is_none_or(|allowed| allowed.iter().any(|allowed| allowed == host))
+	}
+}
+
+/// Identifies the settings of the image proxy a description is rendered with.
+///
+/// The key is stored next to the rendered description, so that it is rendered
+/// again once the settings change, see [`crate::boot::descriptions`]. It is
+/// empty without a proxy.
+pub fn proxy_key(image_proxy: Option<&ImageProxyUrl>) -> String {
+	let Some(image_proxy) = image_proxy else {
+		return String::new();
+	};
+
+	let hosts = match &image_proxy.allowed_hosts {
+		Some(hosts) => {
+			let mut hosts = hosts.clone();
+			hosts.sort();
+			hosts.join(",")
+		}
+		None => "*".into(),
+	};
+
+	format!("{} {hosts}", image_proxy.url)
+}
+
+/// Renders the markdown to HTML, keeping only the allowed elements and
+/// attributes, and `http`, `https` and `mailto` links.
+///
+/// Images are loaded lazily, and through the image proxy if one is given,
+/// so that visitors don't make requests to the hosts chosen by the owners.
+pub fn render(source: &str, image_proxy: Option<&ImageProxyUrl>) -> String {
+	let options =
+		Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+
+	let mut unsafe_html = String::with_capacity(source.len() * 3 / 2);
+	html::push_html(&mut unsafe_html, Parser::new_ext(source, options));
+
+	let image_proxy = image_proxy.cloned();
+
+	let mut builder = Builder::default();
+	builder
+		.tags(TAGS.iter().copied().collect())
+		.tag_attributes(
+			[
+				("a", ["href", "title"].into()),
+				("img", ["src", "alt", "title", "width", "height"].into()),
+				("input", ["type", "checked", "disabled"].into()),
+				("th", ["align"].into()),
+				("td", ["align"].into()),
+			]
+			.into(),
+		)
+		.generic_attributes(HashSet::new())
+		.url_schemes(["http", "https", "mailto"].into())
+		.url_relative(UrlRelative::Deny)
+		.link_rel(Some(LINK_REL))
+		.set_tag_attribute_value("img", "loading", "lazy")
+		.set_tag_attribute_value("input", "disabled", "")
+		.attribute_filter(
+			move |element, attribute, value| match (element, attribute) {
+				// Task list items are the only inputs.
+				("input", "type") => (value == "checkbox").then_some(value.into()),
+				("img", "src") => Some(proxy_image(value, image_proxy.as_ref())),
+				_ => Some(value.into()),
+			},
+		);
+
+	builder.clean(&unsafe_html).to_string()
+}
+
+/// Rewrites the URL of an image to go through the image proxy, as
+/// `{proxy}?url={url}`, when the proxy loads images from its host.
+fn proxy_image<'a>(src: &'a str, image_proxy: Option<&ImageProxyUrl>) -> Cow<'a, str> {
+	let (Some(image_proxy), Ok(url)) = (image_proxy, Url::parse(src)) else {
+		return src.into();
+	};
+
+	if !matches!(url.scheme(), "http" | "https") || !image_proxy.proxies(&url) {
+		return src.into();
+	}
+
+	let mut proxied = image_proxy.url.clone();
+	proxied.query_pairs_mut().append_pair("url", url.as_str());
+	String::from(proxied).into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn renders_markdown() {
+		assert_eq!(
+			render("# Hi\n\n**bold** ~~gone~~ `code`", None),
+			"<h1>Hi</h1>\n<p><strong>bold</strong> <del>gone</del> <code>code</code></p>\n"
+		);
+		assert_eq!(
+			render("- [x] done", None),
+			"<ul>\n<li><input disabled=\"\" type=\"checkbox\" checked=\"\">\ndone</li>\n</ul>\n"
+		);
+	}
+
+	#[test]
+	fn removes_unsafe_html() {
+		assert_eq!(
+			render("<script>alert(1)</script><b onclick=\"x\">hi</b>", None),
+			"hi"
+		);
+		assert_eq!(
+			render("<input type=\"text\">", None),
+			"<input disabled=\"\">"
+		);
+		assert_eq!(
+			render("[a](javascript:alert(1)) [b](/relative)", None),
+			"<p><a rel=\"nofollow noopener noreferrer ugc\">a</a> <a rel=\"nofollow noopener noreferrer ugc\">b</a></p>\n"
+		);
+		assert_eq!(
+			render("[site](https://example.com)", None),
+			"<p><a href=\"https://example.com\" rel=\"nofollow noopener noreferrer ugc\">site</a></p>\n"
+		);
+	}
+
+	#[test]
+	fn proxies_images() {
+		let proxy = ImageProxyUrl {
+			url: Url::parse("https://dbots.fun/proxy").unwrap(),
+			allowed_hosts: None,
+		};
+
+		assert_eq!(
+			render("![cat](https://example.com/cat.png?size=2)", Some(&proxy)),
+			"<p><img src=\"https://dbots.fun/proxy?url=https%3A%2F%2Fexample.com%2Fcat.png%3Fsize%3D2\" alt=\"cat\" loading=\"lazy\"></p>\n"
+		);
+		assert_eq!(
+			render("![cat](https://example.com/cat.png)", None),
+			"<p><img src=\"https://example.com/cat.png\" alt=\"cat\" loading=\"lazy\"></p>\n"
+		);
+	}
+
+	#[test]
+	fn proxies_only_allowed_hosts() {
+		let proxy = ImageProxyUrl {
+			url: Url::parse("https://dbots.fun/images/proxy").unwrap(),
+			allowed_hosts: Some(vec!["cdn.discordapp.com".into()]),
+		};
+
+		assert_eq!(
+			render("![a](https://cdn.discordapp.com/a.png)", Some(&proxy)),
+			"<p><img src=\"https://dbots.fun/images/proxy?url=https%3A%2F%2Fcdn.discordapp.com%2Fa.png\" alt=\"a\" loading=\"lazy\"></p>\n"
+		);
+		assert_eq!(
+			render("![cat](https://example.com/cat.png)", Some(&proxy)),
+			"<p><img src=\"https://example.com/cat.png\" alt=\"cat\" loading=\"lazy\"></p>\n"
+		);
+	}
+
+	#[test]
+	fn proxy_keys() {
+		let proxy = ImageProxyUrl {
+			url: Url::parse("https://dbots.fun/images/proxy").unwrap(),
+			allowed_hosts: Some(vec!["i.imgur.com".into(), "cdn.discordapp.com".into()]),
+		};
+
+		assert_eq!(proxy_key(None), "");
+		assert_eq!(
+			proxy_key(Some(&proxy)),
+			"https://dbots.fun/images/proxy cdn.discordapp.com,i.imgur.com"
+		);
+
+		let any_host = ImageProxyUrl {
+			allowed_hosts: None,
+			..proxy
+		};
+		assert_eq!(
+			proxy_key(Some(&any_host)),
+			"https://dbots.fun/images/proxy *"
+		);
+	}
+}
@@ -0,0 +1,6 @@
+pub use self::instance::InstanceMetrics;
+
+#[macro_use]
+mod macros;
+
+mod instance;
@@ -0,0 +1,93 @@
+//! This module defines all the instance-level metrics of izumo.
+//!
+//! Instance-level metrics are collected separately for each instance of the
+//! application, and are then aggregated at the Prometheus level. They're not
+//! suited for service-level metrics (like "how many bots are there").
+//!
+//! There are two ways to update instance-level metrics:
+//!
+//! * Continuously as things happen in the instance: every time something
+//!   worth recording happens the application updates the value of the metrics,
+//!   accessing the metric through `app.instance_metrics.$metric_name`.
+//!
+//! * When metrics are scraped by Prometheus: every `N` seconds Prometheus sends
+//!   a request to the instance asking to return the metrics, and the values
+//!   are calculated in the [InstanceMetrics::gather] method.
+
+use crate::app::App;
+use diesel_async::pooled_connection::deadpool::Pool;
+use diesel_async::AsyncPgConnection;
+use prometheus::proto::MetricFamily;
+use prometheus::{HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec};
+
+metrics! {
+	pub struct InstanceMetrics {
+		/// Number of idle database connections in the pool
+		database_idle_conns: IntGaugeVec["pool"],
+		/// Number of used database connections in the pool
+		database_used_conns: IntGaugeVec["pool"],
+		/// Amount of time required to obtain a database connection
+		pub database_time_to_obtain_connection: HistogramVec["pool"],
+		/// Number of times the database pool was unavailable and the fallback was used
+		pub database_fallback_used: IntCounterVec["pool"],
+
+		/// Number of requests processed by this instance
+		pub requests_total: IntCounter,
+		/// Number of requests currently being processed
+		pub requests_in_flight: IntGauge,
+
+		/// Response times of our endpoints
+		pub response_times: HistogramVec["endpoint"],
+		/// Number of responses per status code
+		pub responses_by_status_code_total: IntCounterVec["status"],
+
+		/// Number of calls to the Discord API, by endpoint and outcome
+		pub discord_requests_total: IntCounterVec["endpoint", "outcome"],
+
+		/// Number of bots published
+		pub bots_published_total: IntCounter,
+		/// Number of votes cast
+		pub votes_total: IntCounter,
+	}
+
+	// All instance metrics will be prefixed with this namespace.
+	namespace: "izumo_instance",
+}
+
+impl InstanceMetrics {
+	pub fn gather(&self, app: &App) -> prometheus::Result<Vec<MetricFamily>> {
+		// Database pool stats
+		self.refresh_pool_stats("primary", &app.primary_database)?;
+		if let Some(replica) = &app.replica_database {
+			self.refresh_pool_stats("replica", replica)?;
+		}
+
+		Ok(self.registry.gather())
+	}
+
+	/// Records the outcome of a call to the Discord API.
+	pub fn discord_request<T, E>(&self, endpoint: &str, result: &Result<T, E>) {
+		let outcome = if result.is_ok() { "success" } else { "failure" };
+
+		self.discord_requests_total
+			.with_label_values(&[endpoint, outcome])
+			.inc();
+	}
+
+	fn refresh_pool_stats(
+		&self,
+		name: &str,
+		pool: &Pool<AsyncPgConnection>,
+	) -> prometheus::Result<()> {
+		let status = pool.status();
+
+		self.database_idle_conns
+			.get_metric_with_label_values(&[name])?
+			.set(status.available as i64);
+		self.database_used_conns
+			.get_metric_with_label_values(&[name])?
+			.set((status.size - status.available) as i64);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,54 @@
+//! Middleware that rejects the IPs, CIDR blocks and user agents of the
+//! blocklist, see [`crate::blocklist`].
+
+use crate::app::AppState;
+use crate::middleware::log_request::RequestLogExt;
+use crate::middleware::real_ip::RealIp;
+use crate::middleware::request_id::RequestId;
+use crate::util::errors::{api_error, ErrorCode};
+use axum::extract::{Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+pub async fn middleware(State(state): State<AppState>, req: Request, next: Next) -> Response {
+	let rules = state.blocklist.rules();
+
+	if let Some(real_ip) = req.extensions().get::<RealIp>() {
+		if rules.is_ip_blocked(**real_ip) {
+			req.request_log().add("cause", "blocked ip");
+			return rejection_response(&state, &req);
+		}
+	}
+
+	let user_agent = req
+		.headers()
+		.get(header::USER_AGENT)
+		.and_then(|value| value.to_str().ok())
+		.unwrap_or_default();
+
+	if rules.is_user_agent_blocked(user_agent) {
+		req.request_log().add("cause", "blocked user agent");
+		return rejection_response(&state, &req);
+	}
+
+	next.run(req).await
+}
+
+fn rejection_response(state: &AppState, req: &Request) -> Response {
+	let domain_name = &state.config.domain_name;
+
+	let request_id = req
+		.extensions()
+		.get::<RequestId>()
+		.map(RequestId::as_str)
+		.unwrap_or_default();
+
+	let detail = format!(
+		"We are unable to process your request at this time. \
+		 If you believe you've received this message in error, feel free to contact us in our Discord server https://dc.{domain_name}, \
+		 and include the request id {request_id}."
+	);
+
+	api_error(ErrorCode::RequestBlocked, detail).into_response()
+}
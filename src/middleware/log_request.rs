@@ -2,8 +2,8 @@
 //! information that we care about like User-Agent
 
 use super::real_ip::RealIp;
+use super::request_id::RequestId;
 use crate::controllers::util::RequestPartsExt;
-use crate::headers::XRequestId;
 use crate::middleware::normalize_path::OriginalPath;
 use axum::extract::{MatchedPath, Request};
 use axum::http::{Method, Uri};
@@ -35,7 +35,7 @@ pub struct RequestMetadata {
 	matched_path: Option<Extension<MatchedPath>>,
 	real_ip: Extension<RealIp>,
 	user_agent: Option<TypedHeader<UserAgent>>,
-	request_id: Option<TypedHeader<XRequestId>>,
+	request_id: Option<Extension<RequestId>>,
 }
 
 pub async fn log_requests(
@@ -81,7 +81,7 @@ pub async fn log_requests(
 		http.method = %method,
 		http.url = %url,
 		http.matched_path = %matched_path,
-		http.request_id = %request_metadata.request_id.as_ref().map(|h| h.as_str()).unwrap_or_default(),
+		http.request_id = %request_metadata.request_id.as_ref().map(|id| id.as_str()).unwrap_or_default(),
 		http.useragent = %request_metadata.user_agent.as_ref().map(|h| h.as_str()).unwrap_or_default(),
 		http.status_code = status.as_u16(),
 		cause = response.extensions().get::<CauseField>().map(|e| e.0.as_str()).unwrap_or_default(),
@@ -1,13 +1,18 @@
 pub mod app;
+pub mod block_traffic;
 pub mod debug;
 pub mod log_request;
 pub mod normalize_path;
 pub mod real_ip;
+pub mod request_id;
 mod require_user_agent;
 pub mod session;
+pub mod trace_context;
+pub mod update_metrics;
 
 use crate::app::AppState;
 use crate::util::env::Env;
+use crate::util::tracing::is_otel_enabled;
 use ::sentry::integrations::tower as sentry_tower;
 use axum::middleware::{from_fn, from_fn_with_state};
 use axum::Router;
@@ -27,9 +32,17 @@ pub fn apply_middleware(state: AppState, router: Router<()>) -> Router {
 	let middlewares_1 = tower::ServiceBuilder::new()
 		.layer(sentry_tower::NewSentryLayer::new_from_top())
 		.layer(sentry_tower::SentryHttpLayer::with_transaction())
+		.layer(from_fn(request_id::middleware))
 		.layer(from_fn(self::real_ip::middleware))
 		.layer(CatchPanicLayer::new())
 		.layer(from_fn(log_request::log_requests))
+		.layer(conditional_layer(is_otel_enabled(), || {
+			from_fn(trace_context::middleware)
+		}))
+		.layer(from_fn_with_state(
+			state.clone(),
+			update_metrics::update_metrics,
+		))
 		.layer(conditional_layer(env == Env::Development, || {
 			from_fn(debug::debug_requests)
 		}));
@@ -37,7 +50,7 @@ pub fn apply_middleware(state: AppState, router: Router<()>) -> Router {
 	let middlewares_2 = tower::ServiceBuilder::new()
 		.layer(from_fn_with_state(state.clone(), session::attach_session))
 		.layer(from_fn(require_user_agent::require_user_agent))
-		// .layer(from_fn_with_state(state.clone(), block_traffic::middleware))
+		.layer(from_fn_with_state(state.clone(), block_traffic::middleware))
 		.layer(AddExtensionLayer::new(state.clone()));
 
 	router
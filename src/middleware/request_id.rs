@@ -0,0 +1,138 @@
+//! Middleware that gives every request an id, taken from the `X-Request-Id`
+//! header when the load balancer set one, or generated otherwise.
+//!
+//! The id is echoed in the response headers, attached to the logs, the Sentry
+//! scope and the JSON error bodies, and forwarded to the outgoing HTTP calls
+//! made on behalf of the request.
+
+use crate::headers::XRequestId;
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use axum_extra::headers::{Header, HeaderMapExt};
+use rand::Rng;
+use std::fmt;
+
+/// The maximum length of an incoming request id, longer ids are replaced.
+const MAX_LENGTH: usize = 200;
+
+tokio::task_local! {
+	static CURRENT_REQUEST_ID: RequestId;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct RequestId(String);
+
+impl RequestId {
+	fn generate() -> Self {
+		let bytes: [u8; 16] = rand::thread_rng().gen();
+		Self(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+	}
+
+	fn parse(value: &str) -> Option<Self> {
+		let is_valid = !value.is_empty()
+			&& value.len() <= MAX_LENGTH
+			&& value
+				.chars()
+				.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+
+		is_valid.then(|| Self(value.to_string()))
+	}
+
+	/// The id of the request being handled, if any.
+	///
+	/// Also available inside [`crate::task::spawn_blocking`].
+	pub fn current() -> Option<RequestId> {
+		CURRENT_REQUEST_ID.try_with(Clone::clone).ok()
+	}
+
+	/// Runs the closure with `request_id` as the current request id.
+	pub fn sync_scope<F: FnOnce() -> R, R>(request_id: Option<RequestId>, f: F) -> R {
+		match request_id {
+			Some(request_id) => CURRENT_REQUEST_ID.sync_scope(request_id, f),
+			None => f(),
+		}
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	fn header_value(&self) -> HeaderValue {
+		HeaderValue::from_str(&self.0).expect("request ids are valid header values")
+	}
+}
+
+impl fmt::Display for RequestId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+pub async fn middleware(mut req: Request, next: Next) -> Response {
+	let request_id = req
+		.headers()
+		.typed_get::<XRequestId>()
+		.and_then(|header| RequestId::parse(header.as_str()))
+		.unwrap_or_else(RequestId::generate);
+
+	let header_value = request_id.header_value();
+	req.headers_mut()
+		.insert(XRequestId::name(), header_value.clone());
+	req.extensions_mut().insert(request_id.clone());
+
+	sentry::configure_scope(|scope| scope.set_tag("request_id", &request_id));
+
+	let mut response = CURRENT_REQUEST_ID.scope(request_id, next.run(req)).await;
+	response
+		.headers_mut()
+		.insert(XRequestId::name(), header_value);
+
+	response
+}
+
+/// Forwards the id of the current request to an outgoing HTTP call.
+pub trait ForwardRequestId {
+	fn forward_request_id(self) -> Self;
+}
+
+impl ForwardRequestId for reqwest::blocking::RequestBuilder {
+	fn forward_request_id(self) -> Self {
+		match RequestId::current() {
+			Some(request_id) => self.header(XRequestId::name().as_str(), request_id.as_str()),
+			None => self,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn generated_ids_are_valid() {
+		let request_id = RequestId::generate();
+
+		assert_eq!(request_id.as_str().len(), 32);
+		assert_eq!(RequestId::parse(request_id.as_str()), Some(request_id));
+	}
+
+	#[test]
+	fn invalid_ids_are_rejected() {
+		assert!(RequestId::parse("").is_none());
+		assert!(RequestId::parse("has spaces").is_none());
+		assert!(RequestId::parse(&"a".repeat(MAX_LENGTH + 1)).is_none());
+		assert!(RequestId::parse("f3c1b2a0-5d6e-4f7a-8b9c-0d1e2f3a4b5c").is_some());
+	}
+
+	#[test]
+	fn current_in_scope() {
+		assert_eq!(RequestId::current(), None);
+
+		let request_id = RequestId::generate();
+		let current = RequestId::sync_scope(Some(request_id.clone()), RequestId::current);
+		assert_eq!(current, Some(request_id));
+	}
+}
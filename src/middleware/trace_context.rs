@@ -0,0 +1,55 @@
+//! Middleware that records a span for every request, exported to the
+//! OpenTelemetry collector, see [`crate::util::tracing`].
+//!
+//! The span continues the trace of the W3C `traceparent` header when the
+//! caller sent one.
+
+use crate::middleware::request_id::RequestId;
+use crate::util::tracing::set_parent_from_headers;
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::{field, info_span, Instrument};
+
+pub async fn middleware(req: Request, next: Next) -> Response {
+	let method = req.method().clone();
+	let route = req
+		.extensions()
+		.get::<MatchedPath>()
+		.map(|path| path.as_str().to_string());
+
+	let name = match &route {
+		Some(route) => format!("{method} {route}"),
+		None => method.to_string(),
+	};
+
+	let request_id = req
+		.extensions()
+		.get::<RequestId>()
+		.map(RequestId::as_str)
+		.unwrap_or_default();
+
+	let span = info_span!(
+		"http.request",
+		otel.name = %name,
+		otel.kind = "server",
+		otel.status_code = field::Empty,
+		http.request.method = %method,
+		http.route = route,
+		url.path = req.uri().path(),
+		http.response.status_code = field::Empty,
+		request_id,
+	);
+
+	set_parent_from_headers(&span, req.headers());
+
+	let response = next.run(req).instrument(span.clone()).await;
+
+	let status = response.status();
+	span.record("http.response.status_code", status.as_u16());
+	if status.is_server_error() {
+		span.record("otel.status_code", "error");
+	}
+
+	response
+}
@@ -1,17 +1,31 @@
 pub use self::bot::Bot;
 pub use self::category::{BotCategory, Category};
+pub use self::denial::{BotDenial, BotResubmission};
+pub use self::image::Image;
 pub use self::owners::BotOwner;
+pub use self::rating::{BotRating, BotRatingAction, BotRatingFlag, BotRatingReply};
+pub use self::report::BotReport;
 pub use self::review::BotReview;
 pub use self::token::{ApiToken, CreatedApiToken};
+pub use self::translation::BotTranslation;
 pub use self::user::User;
 pub use self::vote::BotVote;
 
+pub mod audit;
+pub mod blocked_traffic;
 pub mod bot;
 pub mod category;
+pub mod certification;
+pub mod denial;
 pub mod helpers;
+pub mod image;
+pub mod language;
 pub mod owners;
+pub mod rating;
+pub mod report;
 pub mod review;
 pub mod token;
+pub mod translation;
 pub mod user;
 pub mod util;
 pub mod vanity;
@@ -0,0 +1,209 @@
+use crate::auth::Authentication;
+use crate::controllers::util::RequestPartsExt;
+use crate::middleware::real_ip::RealIp;
+use crate::models::util::diesel::Conn;
+use crate::schema::audit_log;
+use crate::sql::pg_enum;
+use crate::util::errors::{api_error, BoxedAppError, ErrorCode};
+use diesel::prelude::*;
+use diesel::{deserialize::FromSqlRow, expression::AsExpression};
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+
+pg_enum! {
+	pub enum AuditAction {
+		BotPublish = 0,
+		BotResubmit = 1,
+		BotStatusChange = 2,
+		BotCertificationReview = 3,
+		TokenCreate = 4,
+		TokenRevoke = 5,
+		RatingModerate = 6,
+		ReportUpdate = 7,
+		CategoryCreate = 8,
+		CategoryUpdate = 9,
+		CategoryDelete = 10,
+		UserRoleChange = 11,
+		UserBan = 12,
+		UserUnban = 13,
+		BotResync = 14,
+		BotImport = 15,
+		RateLimitOverride = 16,
+		BlocklistAdd = 17,
+		BlocklistRemove = 18,
+		BotTranslationUpdate = 19,
+		BotTranslationDelete = 20,
+		BotBannerUpdate = 21,
+		BotBannerDelete = 22,
+		BotScreenshotAdd = 23,
+		BotScreenshotDelete = 24,
+	}
+}
+
+impl From<AuditAction> for &'static str {
+	fn from(action: AuditAction) -> Self {
+		match action {
+			AuditAction::BotPublish => "BOT_PUBLISH",
+			AuditAction::BotResubmit => "BOT_RESUBMIT",
+			AuditAction::BotStatusChange => "BOT_STATUS_CHANGE",
+			AuditAction::BotCertificationReview => "BOT_CERTIFICATION_REVIEW",
+			AuditAction::TokenCreate => "TOKEN_CREATE",
+			AuditAction::TokenRevoke => "TOKEN_REVOKE",
+			AuditAction::RatingModerate => "RATING_MODERATE",
+			AuditAction::ReportUpdate => "REPORT_UPDATE",
+			AuditAction::CategoryCreate => "CATEGORY_CREATE",
+			AuditAction::CategoryUpdate => "CATEGORY_UPDATE",
+			AuditAction::CategoryDelete => "CATEGORY_DELETE",
+			AuditAction::UserRoleChange => "USER_ROLE_CHANGE",
+			AuditAction::UserBan => "USER_BAN",
+			AuditAction::UserUnban => "USER_UNBAN",
+			AuditAction::BotResync => "BOT_RESYNC",
+			AuditAction::BotImport => "BOT_IMPORT",
+			AuditAction::RateLimitOverride => "RATE_LIMIT_OVERRIDE",
+			AuditAction::BlocklistAdd => "BLOCKLIST_ADD",
+			AuditAction::BlocklistRemove => "BLOCKLIST_REMOVE",
+			AuditAction::BotTranslationUpdate => "BOT_TRANSLATION_UPDATE",
+			AuditAction::BotTranslationDelete => "BOT_TRANSLATION_DELETE",
+			AuditAction::BotBannerUpdate => "BOT_BANNER_UPDATE",
+			AuditAction::BotBannerDelete => "BOT_BANNER_DELETE",
+			AuditAction::BotScreenshotAdd => "BOT_SCREENSHOT_ADD",
+			AuditAction::BotScreenshotDelete => "BOT_SCREENSHOT_DELETE",
+		}
+	}
+}
+
+impl From<AuditAction> for String {
+	fn from(action: AuditAction) -> Self {
+		let string: &'static str = action.into();
+
+		string.into()
+	}
+}
+
+impl FromStr for AuditAction {
+	type Err = BoxedAppError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		AuditAction::VARIANTS
+			.iter()
+			.copied()
+			.find(|action| <&str>::from(*action).eq_ignore_ascii_case(s))
+			.ok_or_else(|| {
+				api_error(
+					ErrorCode::InvalidValue,
+					format!("invalid audit action `{s}`"),
+				)
+			})
+	}
+}
+
+/// The actor of the entries recorded by the server itself, e.g. when syncing
+/// the categories on startup.
+pub const SYSTEM_ACTOR: &str = "system";
+
+/// Represents an entry of the append-only audit log.
+#[derive(Identifiable, Selectable, Queryable, Debug, Clone)]
+#[diesel(table_name = audit_log, check_for_backend(diesel::pg::Pg))]
+pub struct AuditEntry {
+	/// Unique identifier of the entry
+	pub id: i64,
+	/// ID of the user who performed the action
+	pub actor_id: String,
+	/// ID of the API token used for the action, `None` for cookie sessions
+	pub token_id: Option<i32>,
+	/// What was done
+	pub action: AuditAction,
+	/// ID of the affected bot, if any
+	pub bot_id: Option<String>,
+	/// What the action was performed on, e.g. `bot:1234` or `token:42`
+	pub target: String,
+	/// State of the target before the action
+	pub before: Option<Value>,
+	/// State of the target after the action
+	pub after: Option<Value>,
+	/// IP address of the actor
+	pub ip: Option<IpNetwork>,
+	/// When the action was performed
+	pub created_at: chrono::NaiveDateTime,
+}
+
+/// Represents the data needed to append an entry to the audit log.
+///
+/// Mutating controllers should insert the entry inside the same transaction
+/// as the change it describes.
+#[derive(Insertable, Debug)]
+#[diesel(table_name = audit_log, check_for_backend(diesel::pg::Pg))]
+pub struct NewAuditEntry {
+	actor_id: String,
+	token_id: Option<i32>,
+	action: AuditAction,
+	bot_id: Option<String>,
+	target: String,
+	before: Option<Value>,
+	after: Option<Value>,
+	ip: Option<IpNetwork>,
+}
+
+impl NewAuditEntry {
+	pub fn new<T: RequestPartsExt>(
+		req: &T,
+		auth: &Authentication,
+		action: AuditAction,
+		target: impl Into<String>,
+	) -> Self {
+		let ip = req
+			.extensions()
+			.get::<RealIp>()
+			.map(|ip| IpNetwork::from(**ip));
+
+		Self {
+			actor_id: auth.user_id(),
+			token_id: auth.api_token_id(),
+			action,
+			bot_id: None,
+			target: target.into(),
+			before: None,
+			after: None,
+			ip,
+		}
+	}
+
+	/// Creates an entry for an action performed outside of a request, e.g.
+	/// by the `izumo-admin` command-line tool.
+	pub fn for_actor(actor_id: &str, action: AuditAction, target: impl Into<String>) -> Self {
+		Self {
+			actor_id: actor_id.to_string(),
+			token_id: None,
+			action,
+			bot_id: None,
+			target: target.into(),
+			before: None,
+			after: None,
+			ip: None,
+		}
+	}
+
+	pub fn bot(mut self, bot_id: &str) -> Self {
+		self.bot_id = Some(bot_id.to_string());
+		self
+	}
+
+	pub fn before(mut self, before: Value) -> Self {
+		self.before = Some(before);
+		self
+	}
+
+	pub fn after(mut self, after: Value) -> Self {
+		self.after = Some(after);
+		self
+	}
+
+	pub fn insert(&self, conn: &mut impl Conn) -> QueryResult<AuditEntry> {
+		diesel::insert_into(audit_log::table)
+			.values(self)
+			.returning(AuditEntry::as_returning())
+			.get_result(conn)
+	}
+}
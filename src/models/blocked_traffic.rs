@@ -0,0 +1,73 @@
+use crate::models::util::diesel::Conn;
+use crate::schema::blocked_traffic;
+use diesel::dsl::now;
+use diesel::prelude::*;
+use ipnetwork::IpNetwork;
+
+/// Represents an IP range or a user agent blocked by staff at runtime.
+#[derive(Identifiable, Selectable, Queryable, Debug, Clone)]
+#[diesel(table_name = blocked_traffic, check_for_backend(diesel::pg::Pg))]
+pub struct BlockedTraffic {
+	/// Unique identifier of the entry
+	pub id: i32,
+	/// Blocked address or CIDR block
+	pub ip_range: Option<IpNetwork>,
+	/// Requests whose user agent contains this string are blocked
+	pub user_agent: Option<String>,
+	/// Why the traffic is blocked, only visible to staff
+	pub reason: String,
+	/// ID of the staff member who added the entry
+	pub created_by: Option<String>,
+	/// When the entry was added
+	pub created_at: chrono::NaiveDateTime,
+	/// When the entry stops being enforced, `None` if never
+	pub expires_at: Option<chrono::NaiveDateTime>,
+}
+
+impl BlockedTraffic {
+	/// The maximum length of the reason of an entry.
+	pub const MAX_REASON_LENGTH: usize = 500;
+
+	/// The shortest prefix of the blocked IPv4 blocks, wider blocks would
+	/// reject a large part of the legitimate traffic.
+	pub const MIN_IPV4_PREFIX: u8 = 16;
+
+	/// The shortest prefix of the blocked IPv6 blocks.
+	pub const MIN_IPV6_PREFIX: u8 = 48;
+
+	/// The minimum length of a blocked user agent, since shorter strings are
+	/// contained in the user agents of most browsers.
+	pub const MIN_USER_AGENT_LENGTH: usize = 4;
+
+	/// Loads the entries that have not expired yet.
+	pub fn active(conn: &mut impl Conn) -> QueryResult<Vec<BlockedTraffic>> {
+		blocked_traffic::table
+			.filter(
+				blocked_traffic::expires_at
+					.is_null()
+					.or(blocked_traffic::expires_at.gt(now)),
+			)
+			.select(BlockedTraffic::as_select())
+			.load(conn)
+	}
+}
+
+/// Represents the data needed to block an IP range or a user agent.
+#[derive(Insertable, Debug)]
+#[diesel(table_name = blocked_traffic, check_for_backend(diesel::pg::Pg))]
+pub struct NewBlockedTraffic<'a> {
+	pub ip_range: Option<IpNetwork>,
+	pub user_agent: Option<&'a str>,
+	pub reason: &'a str,
+	pub created_by: &'a str,
+	pub expires_at: Option<chrono::NaiveDateTime>,
+}
+
+impl NewBlockedTraffic<'_> {
+	pub fn insert(&self, conn: &mut impl Conn) -> QueryResult<BlockedTraffic> {
+		diesel::insert_into(blocked_traffic::table)
+			.values(self)
+			.returning(BlockedTraffic::as_returning())
+			.get_result(conn)
+	}
+}
@@ -2,12 +2,13 @@ use crate::models::util::diesel::Conn;
 use crate::models::{BotOwner, User};
 use crate::schema::{bot_owners, bots, users};
 use crate::sql::pg_enum;
-use crate::util::errors::{bot_not_found, AppResult};
+use crate::util::errors::{api_error, bot_not_found, AppResult, BoxedAppError, ErrorCode};
 use derivative::Derivative;
 use diesel::{deserialize::FromSqlRow, expression::AsExpression};
 use diesel::{dsl, ExpressionMethods, QueryDsl, QueryResult, SelectableHelper};
 use diesel_async::AsyncPgConnection;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 pg_enum! {
 	pub enum BotStatus {
@@ -35,33 +36,15 @@ impl From<BotStatus> for String {
 	}
 }
 
-pg_enum! {
-	pub enum BotLanguages {
-		ENGLISH = 0,
-		SPANISH = 1,
-		GERMAN = 2,
-		JAPANESE = 3,
-		CHINESE = 4,
-	}
-}
-
-impl From<BotLanguages> for &'static str {
-	fn from(lang: BotLanguages) -> Self {
-		match lang {
-			BotLanguages::ENGLISH => "English",
-			BotLanguages::SPANISH => "Spanish",
-			BotLanguages::GERMAN => "German",
-			BotLanguages::JAPANESE => "Japanese",
-			BotLanguages::CHINESE => "Chinese",
-		}
-	}
-}
+impl FromStr for BotStatus {
+	type Err = BoxedAppError;
 
-impl From<BotLanguages> for String {
-	fn from(status: BotLanguages) -> Self {
-		let string: &'static str = status.into();
-
-		string.into()
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		BotStatus::VARIANTS
+			.iter()
+			.copied()
+			.find(|status| <&str>::from(*status).eq_ignore_ascii_case(s))
+			.ok_or_else(|| api_error(ErrorCode::InvalidValue, format!("invalid bot status `{s}`")))
 	}
 }
 
@@ -80,7 +63,7 @@ pub struct Bot {
 	pub avatar: Option<String>,
 	/// Whether the bot is certified
 	pub certified: bool,
-	/// Banner URL
+	/// Key of the stored banner
 	pub banner: Option<String>,
 	/// Status of the bot
 	pub status: BotStatus,
@@ -108,13 +91,34 @@ pub struct Bot {
 	pub created_at: chrono::NaiveDateTime,
 	/// Last time the bot was updated
 	pub updated_at: chrono::NaiveDateTime,
-	/// Yeah
-	pub supported_languages: Vec<Option<BotLanguages>>,
 	/// Approximate Guild Count
 	pub guild_count: i32,
+	/// Number of user ratings
+	pub rating_count: i32,
+	/// Average of the user ratings, `0` if the bot has none
+	pub rating_average: f32,
+	/// Description rendered to sanitized HTML, see [`crate::markdown`]
+	pub description_html: String,
+	/// Sanitizer policy the description was rendered with
+	pub description_html_version: i32,
+	/// ISO 639-1 codes of the languages the bot speaks, see [`crate::models::language`]
+	pub supported_languages: Vec<String>,
+	/// Image proxy the description was rendered with, see [`crate::markdown::proxy_key`]
+	pub description_html_proxy: String,
 }
 
 impl Bot {
+	/// The maximum length of the description of a bot.
+	pub const MAX_DESCRIPTION_LENGTH: usize = 4000;
+	/// The maximum length of the short description of a bot.
+	pub const MAX_SHORT_DESCRIPTION_LENGTH: usize = 200;
+	/// The maximum length of the prefix of a bot.
+	pub const MAX_PREFIX_LENGTH: usize = 16;
+	/// The maximum length of the links of a bot.
+	pub const MAX_URL_LENGTH: usize = 512;
+	/// The maximum number of languages a bot can speak.
+	pub const MAX_SUPPORTED_LANGUAGES: usize = 20;
+
 	pub async fn find(conn: &mut AsyncPgConnection, id: &str) -> AppResult<Bot> {
 		use diesel::OptionalExtension;
 		use diesel_async::RunQueryDsl;
@@ -144,6 +148,39 @@ impl Bot {
 	}
 }
 
+/// Changes made by the owners of a bot to its listing.
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = bots, check_for_backend(diesel::pg::Pg))]
+pub struct BotChanges<'a> {
+	pub description: Option<&'a str>,
+	pub short_description: Option<&'a str>,
+	pub prefix: Option<&'a str>,
+	pub is_slash: Option<bool>,
+	pub invite_link: Option<&'a str>,
+	pub github: Option<&'a str>,
+	pub website: Option<&'a str>,
+	pub support_server: Option<&'a str>,
+	pub supported_languages: Option<&'a [String]>,
+	pub description_html: Option<&'a str>,
+	pub description_html_version: Option<i32>,
+	pub description_html_proxy: Option<&'a str>,
+}
+
+impl BotChanges<'_> {
+	/// Whether there is anything to change.
+	pub fn is_empty(&self) -> bool {
+		self.description.is_none()
+			&& self.short_description.is_none()
+			&& self.prefix.is_none()
+			&& self.is_slash.is_none()
+			&& self.invite_link.is_none()
+			&& self.github.is_none()
+			&& self.website.is_none()
+			&& self.support_server.is_none()
+			&& self.supported_languages.is_none()
+	}
+}
+
 impl From<Bot> for crate::views::OwnedBot {
 	fn from(btu: Bot) -> Self {
 		crate::views::OwnedBot {
@@ -174,8 +211,11 @@ pub struct NewBot<'a> {
 	pub support_server: Option<&'a str>,
 	pub api_key: Option<&'a str>,
 	pub imported_from: Option<&'a str>,
-	pub supported_languages: Vec<Option<BotLanguages>>,
+	pub supported_languages: Vec<String>,
 	pub guild_count: i32,
+	pub description_html: &'a str,
+	pub description_html_version: i32,
+	pub description_html_proxy: &'a str,
 }
 
 #[derive(Debug, Default, Derivative)]
@@ -185,6 +225,10 @@ pub struct NewBotBuilder<'a> {
 	pub avatar: Option<&'a str>,
 	pub banner: Option<&'a str>,
 	pub description: &'a str,
+	/// The description rendered with [`crate::markdown::render`]
+	pub description_html: &'a str,
+	/// The [`crate::markdown::proxy_key`] of the image proxy it was rendered with
+	pub description_html_proxy: &'a str,
 	pub short_description: &'a str,
 	pub prefix: &'a str,
 	#[derivative(Default(value = "false"))]
@@ -194,7 +238,7 @@ pub struct NewBotBuilder<'a> {
 	pub website: Option<&'a str>,
 	pub invite_link: Option<&'a str>,
 	pub support_server: Option<&'a str>,
-	pub supported_languages: Vec<Option<BotLanguages>>,
+	pub supported_languages: Vec<String>,
 	pub guild_count: i32,
 }
 
@@ -206,6 +250,8 @@ impl<'a> NewBot<'a> {
 			avatar,
 			banner,
 			description,
+			description_html,
+			description_html_proxy,
 			short_description,
 			prefix,
 			is_slash,
@@ -236,6 +282,9 @@ impl<'a> NewBot<'a> {
 			imported_from,
 			supported_languages,
 			guild_count,
+			description_html,
+			description_html_version: crate::markdown::POLICY_VERSION,
+			description_html_proxy,
 		}
 	}
 
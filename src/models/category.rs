@@ -2,8 +2,8 @@ use super::util::diesel::Conn;
 use crate::models::Bot;
 use crate::schema::*;
 use diesel::{
-	delete, dsl, insert_into, pg, sql_query, ExpressionMethods, QueryDsl, QueryResult,
-	TextExpressionMethods,
+	delete, dsl, insert_into, pg, sql_query, BoolExpressionMethods, ExpressionMethods, QueryDsl,
+	QueryResult, TextExpressionMethods,
 };
 
 use diesel_async::AsyncPgConnection;
@@ -48,6 +48,114 @@ impl Category {
 		categories::table.filter(filter)
 	}
 
+	/// The maximum length of a slug or name, including the parent categories.
+	pub const MAX_LENGTH: usize = 100;
+
+	/// Whether the slug is made of `::`-separated segments of lowercase
+	/// letters, digits and dashes, e.g. `moderation::auto-moderation`.
+	pub fn is_valid_slug(slug: &str) -> bool {
+		slug.len() <= Self::MAX_LENGTH
+			&& slug.split("::").all(|segment| {
+				!segment.is_empty()
+					&& segment
+						.chars()
+						.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+			})
+	}
+
+	/// The slug of the direct parent of a subcategory, `None` for top-level categories.
+	pub fn parent_slug(slug: &str) -> Option<&str> {
+		slug.rsplit_once("::").map(|(parent, _)| parent)
+	}
+
+	/// Returns the direct subcategories of this category. Their `bots_cnt`
+	/// include the bots of their own subcategories, each bot counted once.
+	pub fn subcategories(&self, conn: &mut impl Conn) -> QueryResult<Vec<Category>> {
+		use diesel::sql_types::Text;
+		use diesel::RunQueryDsl;
+
+		sql_query(include_str!("subcategories.sql"))
+			.bind::<Text, _>(&self.slug)
+			.load(conn)
+	}
+
+	/// Returns the parents of this category, from the top-level category down
+	/// to its direct parent. Their `bots_cnt` include the bots of their
+	/// subcategories.
+	pub fn parent_categories(&self, conn: &mut impl Conn) -> QueryResult<Vec<Category>> {
+		use diesel::sql_types::Text;
+		use diesel::RunQueryDsl;
+
+		sql_query(include_str!("parent_categories.sql"))
+			.bind::<Text, _>(&self.slug)
+			.load(conn)
+	}
+
+	/// The `LIKE` pattern of the slugs of the subcategories, at any depth.
+	///
+	/// The wildcards of `LIKE` are escaped, so that they only match themselves
+	/// in the slug of the category.
+	pub fn subcategories_pattern(slug: &str) -> String {
+		let mut pattern = String::with_capacity(slug.len() + 3);
+		for c in slug.chars() {
+			if matches!(c, '\\' | '%' | '_') {
+				pattern.push('\\');
+			}
+			pattern.push(c);
+		}
+		pattern.push_str("::%");
+		pattern
+	}
+
+	/// Filters the category with the slug and its subcategories, at any depth.
+	#[dsl::auto_type(no_type_alias)]
+	pub fn in_subtree<'a>(slug: &'a str) -> _ {
+		let pattern: String = Self::subcategories_pattern(slug);
+		categories::slug.eq(slug).or(categories::slug.like(pattern))
+	}
+
+	/// Counts the approved bots of this category and of its subcategories, each
+	/// bot counted once, like the bot listing of the category.
+	pub fn subtree_bots_cnt(&self, conn: &mut impl Conn) -> QueryResult<i32> {
+		use diesel::RunQueryDsl;
+
+		dsl::select(crate::sql::subtree_bots_cnt(&self.slug)).get_result(conn)
+	}
+
+	/// Returns the given slugs that belong to categories with subcategories.
+	pub fn with_subcategories(conn: &mut impl Conn, slugs: &[&str]) -> QueryResult<Vec<String>> {
+		use diesel::RunQueryDsl;
+
+		if slugs.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let mut query = categories::table.select(categories::slug).into_boxed();
+		for slug in slugs {
+			query = query.or_filter(categories::slug.like(Self::subcategories_pattern(slug)));
+		}
+		let subcategories: Vec<String> = query.load(conn)?;
+
+		Ok(slugs
+			.iter()
+			.filter(|slug| {
+				let prefix = format!("{slug}::");
+				subcategories
+					.iter()
+					.any(|subcategory| subcategory.starts_with(&prefix))
+			})
+			.map(ToString::to_string)
+			.collect())
+	}
+
+	/// Recounts the approved bots of every category. The counters are kept up
+	/// to date by triggers, this is only needed to repair them.
+	pub fn recalculate_bots_cnt(conn: &mut impl Conn) -> QueryResult<usize> {
+		use diesel::RunQueryDsl;
+
+		sql_query("SELECT recalculate_bots_cnt(id) FROM categories").execute(conn)
+	}
+
 	pub fn update_bot(
 		conn: &mut impl Conn,
 		bot_id: &str,
@@ -107,8 +215,8 @@ impl Category {
 			_ => "ORDER BY category ASC",
 		};
 
-		// Collect all the top-level categories and sum up the bots_cnt of
-		// the bots in all subcategories
+		// Collect all the top-level categories and count the bots of all
+		// their subcategories
 		sql_query(format!(include_str!("toplevel.sql"), sort_sql))
 			.bind::<Int8, _>(limit)
 			.bind::<Int8, _>(offset)
@@ -137,3 +245,53 @@ impl<'a> NewCategory<'a> {
 			.get_result(conn)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn valid_slugs() {
+		assert!(Category::is_valid_slug("moderation"));
+		assert!(Category::is_valid_slug("moderation::auto-moderation"));
+		assert!(Category::is_valid_slug("games::rpg::turn-based-2"));
+	}
+
+	#[test]
+	fn invalid_slugs() {
+		assert!(!Category::is_valid_slug(""));
+		assert!(!Category::is_valid_slug("Moderation"));
+		assert!(!Category::is_valid_slug("moderation::"));
+		assert!(!Category::is_valid_slug("::moderation"));
+		assert!(!Category::is_valid_slug("auto_moderation"));
+		assert!(!Category::is_valid_slug("auto%"));
+		assert!(!Category::is_valid_slug(
+			&"a".repeat(Category::MAX_LENGTH + 1)
+		));
+	}
+
+	#[test]
+	fn parent_slugs() {
+		assert_eq!(Category::parent_slug("moderation"), None);
+		assert_eq!(
+			Category::parent_slug("moderation::auto-moderation"),
+			Some("moderation")
+		);
+		assert_eq!(
+			Category::parent_slug("games::rpg::idle"),
+			Some("games::rpg")
+		);
+	}
+
+	#[test]
+	fn subcategories_patterns() {
+		assert_eq!(
+			Category::subcategories_pattern("moderation"),
+			"moderation::%"
+		);
+		assert_eq!(
+			Category::subcategories_pattern("auto_mod%\\"),
+			"auto\\_mod\\%\\\\::%"
+		);
+	}
+}
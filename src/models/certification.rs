@@ -0,0 +1,311 @@
+use crate::models::bot::BotStatus;
+use crate::models::util::diesel::Conn;
+use crate::models::Bot;
+use crate::schema::{bot_votes, bots, certification_applications};
+use crate::sql::pg_enum;
+use crate::util::errors::{api_error, BoxedAppError, ErrorCode};
+use chrono::{NaiveDateTime, TimeDelta};
+use diesel::prelude::*;
+use diesel::{deserialize::FromSqlRow, expression::AsExpression};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pg_enum! {
+	pub enum ApplicationStatus {
+		PENDING = 0,
+		APPROVED = 1,
+		DENIED = 2,
+	}
+}
+
+impl From<ApplicationStatus> for &'static str {
+	fn from(status: ApplicationStatus) -> Self {
+		match status {
+			ApplicationStatus::PENDING => "PENDING",
+			ApplicationStatus::APPROVED => "APPROVED",
+			ApplicationStatus::DENIED => "DENIED",
+		}
+	}
+}
+
+impl From<ApplicationStatus> for String {
+	fn from(status: ApplicationStatus) -> Self {
+		let string: &'static str = status.into();
+
+		string.into()
+	}
+}
+
+impl FromStr for ApplicationStatus {
+	type Err = BoxedAppError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		ApplicationStatus::VARIANTS
+			.iter()
+			.copied()
+			.find(|status| <&str>::from(*status).eq_ignore_ascii_case(s))
+			.ok_or_else(|| {
+				api_error(
+					ErrorCode::InvalidValue,
+					format!("invalid application status `{s}`"),
+				)
+			})
+	}
+}
+
+/// Represents the application of a bot for certification.
+#[derive(Identifiable, Selectable, Queryable, Associations, Debug, Clone)]
+#[diesel(
+    table_name = certification_applications,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(Bot, foreign_key = bot_id),
+)]
+pub struct CertificationApplication {
+	/// Unique identifier of the application
+	pub id: i32,
+	/// ID of the bot applying for certification
+	pub bot_id: String,
+	/// ID of the owner who applied
+	pub user_id: String,
+	/// Message of the owner to the reviewers
+	pub message: Option<String>,
+	/// Review state of the application
+	pub status: ApplicationStatus,
+	/// ID of the staff member who reviewed the application
+	pub reviewer_id: Option<String>,
+	/// Notes of the reviewer, shown to the owners
+	pub reason: Option<String>,
+	/// When the application was created
+	pub created_at: NaiveDateTime,
+	/// Last time the application was updated
+	pub updated_at: NaiveDateTime,
+}
+
+impl CertificationApplication {
+	/// The maximum length of the message of an application.
+	pub const MAX_MESSAGE_LENGTH: usize = 2000;
+
+	/// The notes of the applications closed because their bot was denied.
+	pub const BOT_DENIED_REASON: &'static str = "The bot was denied.";
+
+	/// Finds the most recent application of the bot, if any.
+	pub fn latest(conn: &mut impl Conn, bot_id: &str) -> QueryResult<Option<Self>> {
+		certification_applications::table
+			.filter(certification_applications::bot_id.eq(bot_id))
+			.order(certification_applications::id.desc())
+			.select(Self::as_select())
+			.first(conn)
+			.optional()
+	}
+
+	pub fn create(
+		conn: &mut impl Conn,
+		bot_id: &str,
+		user_id: &str,
+		message: Option<&str>,
+	) -> QueryResult<Self> {
+		diesel::insert_into(certification_applications::table)
+			.values((
+				certification_applications::bot_id.eq(bot_id),
+				certification_applications::user_id.eq(user_id),
+				certification_applications::message.eq(message),
+			))
+			.returning(Self::as_returning())
+			.get_result(conn)
+	}
+
+	/// Denies the pending application of the bot, if any, when the bot itself
+	/// is denied.
+	pub fn close_pending(
+		conn: &mut impl Conn,
+		bot_id: &str,
+		reviewer_id: &str,
+	) -> QueryResult<usize> {
+		diesel::update(certification_applications::table)
+			.filter(certification_applications::bot_id.eq(bot_id))
+			.filter(certification_applications::status.eq(ApplicationStatus::PENDING))
+			.set((
+				certification_applications::status.eq(ApplicationStatus::DENIED),
+				certification_applications::reviewer_id.eq(reviewer_id),
+				certification_applications::reason.eq(Self::BOT_DENIED_REASON),
+			))
+			.execute(conn)
+	}
+
+	/// Records the decision of a reviewer, certifying the bot if the application
+	/// was approved.
+	///
+	/// The caller checks that the application is pending and that the bot is
+	/// eligible, with both rows locked.
+	pub fn review(
+		&self,
+		conn: &mut impl Conn,
+		reviewer_id: &str,
+		status: ApplicationStatus,
+		reason: Option<&str>,
+	) -> QueryResult<Self> {
+		conn.transaction(|conn| {
+			if status == ApplicationStatus::APPROVED {
+				diesel::update(bots::table.find(&self.bot_id))
+					.set(bots::certified.eq(true))
+					.execute(conn)?;
+			}
+
+			diesel::update(self)
+				.set((
+					certification_applications::status.eq(status),
+					certification_applications::reviewer_id.eq(reviewer_id),
+					certification_applications::reason.eq(reason),
+				))
+				.returning(Self::as_returning())
+				.get_result(conn)
+		})
+	}
+}
+
+/// A single rule a bot has to pass before applying for certification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificationRequirement {
+	pub name: &'static str,
+	pub required: i64,
+	pub current: i64,
+}
+
+impl CertificationRequirement {
+	pub fn is_met(&self) -> bool {
+		self.current >= self.required
+	}
+}
+
+/// The rules a bot has to pass before applying for certification.
+#[derive(Debug, Clone)]
+pub struct CertificationEligibility {
+	pub requirements: Vec<CertificationRequirement>,
+}
+
+impl CertificationEligibility {
+	/// Minimum amount of servers the bot has to be in.
+	pub const MIN_GUILD_COUNT: i64 = 100;
+	/// Minimum amount of days since the bot was published.
+	pub const MIN_AGE_DAYS: i64 = 30;
+	/// Minimum amount of votes the bot has to receive in the last
+	/// [`CertificationEligibility::VOTES_WINDOW_DAYS`] days.
+	pub const MIN_VOTES: i64 = 50;
+	pub const VOTES_WINDOW_DAYS: i64 = 30;
+
+	/// Computes the eligibility of the bot, loading its recent votes.
+	pub fn for_bot(conn: &mut impl Conn, bot: &Bot) -> QueryResult<Self> {
+		let now = chrono::Utc::now().naive_utc();
+		let since = (now - TimeDelta::days(Self::VOTES_WINDOW_DAYS)).date();
+
+		let votes: Option<i64> = bot_votes::table
+			.filter(bot_votes::bot_id.eq(&bot.id))
+			.filter(bot_votes::date.ge(since))
+			.select(diesel::dsl::sum(bot_votes::votes))
+			.get_result(conn)?;
+
+		Ok(Self::evaluate(bot, votes.unwrap_or_default(), now))
+	}
+
+	/// Evaluates the rules for a bot with the given amount of recent votes.
+	pub fn evaluate(bot: &Bot, recent_votes: i64, now: NaiveDateTime) -> Self {
+		let requirements = vec![
+			CertificationRequirement {
+				name: "approved",
+				required: 1,
+				current: (bot.status == BotStatus::APPROVED).into(),
+			},
+			CertificationRequirement {
+				name: "guild_count",
+				required: Self::MIN_GUILD_COUNT,
+				current: bot.guild_count.into(),
+			},
+			CertificationRequirement {
+				name: "age_days",
+				required: Self::MIN_AGE_DAYS,
+				current: (now - bot.created_at).num_days(),
+			},
+			CertificationRequirement {
+				name: "recent_votes",
+				required: Self::MIN_VOTES,
+				current: recent_votes,
+			},
+		];
+
+		Self { requirements }
+	}
+
+	pub fn is_eligible(&self) -> bool {
+		self.requirements
+			.iter()
+			.all(CertificationRequirement::is_met)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::NaiveDate;
+
+	fn bot(status: BotStatus, guild_count: i32, created_at: NaiveDateTime) -> Bot {
+		Bot {
+			id: "1".into(),
+			name: "bot".into(),
+			avatar: None,
+			certified: false,
+			banner: None,
+			status,
+			description: String::new(),
+			short_description: String::new(),
+			prefix: "!".into(),
+			is_slash: true,
+			github: None,
+			website: None,
+			invite_link: None,
+			support_server: None,
+			api_key: None,
+			imported_from: None,
+			created_at,
+			updated_at: created_at,
+			supported_languages: vec![],
+			guild_count,
+			rating_count: 0,
+			rating_average: 0.0,
+			description_html: String::new(),
+			description_html_version: 0,
+			description_html_proxy: String::new(),
+		}
+	}
+
+	fn date(day: u32, month: u32) -> NaiveDateTime {
+		NaiveDate::from_ymd_opt(2024, month, day)
+			.unwrap()
+			.and_hms_opt(0, 0, 0)
+			.unwrap()
+	}
+
+	#[test]
+	fn eligible_bot_passes_every_requirement() {
+		let bot = bot(BotStatus::APPROVED, 150, date(1, 1));
+		let eligibility = CertificationEligibility::evaluate(&bot, 60, date(1, 3));
+
+		assert!(eligibility.is_eligible());
+	}
+
+	#[test]
+	fn each_requirement_is_checked() {
+		let now = date(1, 3);
+
+		let pending = bot(BotStatus::PENDING, 150, date(1, 1));
+		assert!(!CertificationEligibility::evaluate(&pending, 60, now).is_eligible());
+
+		let small = bot(BotStatus::APPROVED, 99, date(1, 1));
+		assert!(!CertificationEligibility::evaluate(&small, 60, now).is_eligible());
+
+		let young = bot(BotStatus::APPROVED, 150, date(15, 2));
+		assert!(!CertificationEligibility::evaluate(&young, 60, now).is_eligible());
+
+		let unpopular = bot(BotStatus::APPROVED, 150, date(1, 1));
+		assert!(!CertificationEligibility::evaluate(&unpopular, 49, now).is_eligible());
+	}
+}
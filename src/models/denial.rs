@@ -0,0 +1,125 @@
+use crate::models::bot::BotStatus;
+use crate::models::certification::CertificationApplication;
+use crate::models::util::diesel::Conn;
+use crate::models::Bot;
+use crate::schema::{bot_denials, bot_resubmissions, bots};
+use diesel::prelude::*;
+
+/// Represents the denial of a bot by a reviewer, together with the reason
+/// given to the owners. Denials are kept after the bot is resubmitted, so
+/// reviewers can see the full history of the bot.
+#[derive(Identifiable, Selectable, Queryable, Associations, Debug, Clone)]
+#[diesel(
+    table_name = bot_denials,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(Bot, foreign_key = bot_id),
+)]
+pub struct BotDenial {
+	/// Unique identifier of the denial
+	pub id: i32,
+	/// ID of the denied bot
+	pub bot_id: String,
+	/// ID of the reviewer who denied the bot
+	pub reviewer_id: Option<String>,
+	/// Why the bot was denied
+	pub reason: String,
+	/// When the bot was denied
+	pub created_at: chrono::NaiveDateTime,
+}
+
+impl BotDenial {
+	/// The maximum length of the reason of a denial.
+	pub const MAX_REASON_LENGTH: usize = 2000;
+	/// How long the owners have to wait after a denial before resubmitting the bot.
+	pub const RESUBMIT_COOLDOWN: chrono::TimeDelta = chrono::TimeDelta::hours(24);
+
+	/// Denies the bot, recording who did it and why, and closes its pending
+	/// certification application.
+	pub fn deny(
+		conn: &mut impl Conn,
+		bot_id: &str,
+		reviewer_id: &str,
+		reason: &str,
+	) -> QueryResult<BotDenial> {
+		conn.transaction(|conn| {
+			diesel::update(bots::table.find(bot_id))
+				.set(bots::status.eq(BotStatus::DENIED))
+				.execute(conn)?;
+
+			CertificationApplication::close_pending(conn, bot_id, reviewer_id)?;
+
+			diesel::insert_into(bot_denials::table)
+				.values((
+					bot_denials::bot_id.eq(bot_id),
+					bot_denials::reviewer_id.eq(reviewer_id),
+					bot_denials::reason.eq(reason),
+				))
+				.returning(BotDenial::as_returning())
+				.get_result(conn)
+		})
+	}
+
+	/// Finds the most recent denial of the bot, if any.
+	pub fn latest(conn: &mut impl Conn, bot_id: &str) -> QueryResult<Option<BotDenial>> {
+		bot_denials::table
+			.filter(bot_denials::bot_id.eq(bot_id))
+			.order(bot_denials::id.desc())
+			.select(BotDenial::as_select())
+			.first(conn)
+			.optional()
+	}
+
+	/// When the owners are allowed to resubmit the bot after this denial.
+	pub fn resubmittable_at(&self) -> chrono::NaiveDateTime {
+		self.created_at + Self::RESUBMIT_COOLDOWN
+	}
+}
+
+/// Represents the resubmission of a denied bot by one of its owners, with an
+/// optional appeal message for the reviewers.
+#[derive(Identifiable, Selectable, Queryable, Associations, Debug, Clone)]
+#[diesel(
+    table_name = bot_resubmissions,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(Bot, foreign_key = bot_id),
+)]
+pub struct BotResubmission {
+	/// Unique identifier of the resubmission
+	pub id: i32,
+	/// ID of the resubmitted bot
+	pub bot_id: String,
+	/// ID of the owner who resubmitted the bot
+	pub user_id: String,
+	/// Message of the owner to the reviewers
+	pub appeal: Option<String>,
+	/// When the bot was resubmitted
+	pub created_at: chrono::NaiveDateTime,
+}
+
+impl BotResubmission {
+	/// The maximum length of an appeal message.
+	pub const MAX_APPEAL_LENGTH: usize = 2000;
+
+	/// Moves the bot back to the review queue, recording the appeal of the owner.
+	pub fn create(
+		conn: &mut impl Conn,
+		bot_id: &str,
+		user_id: &str,
+		appeal: Option<&str>,
+	) -> QueryResult<BotResubmission> {
+		conn.transaction(|conn| {
+			diesel::update(bots::table.find(bot_id))
+				.set(bots::status.eq(BotStatus::PENDING))
+				.execute(conn)?;
+
+			diesel::insert_into(bot_resubmissions::table)
+				.values((
+					bot_resubmissions::bot_id.eq(bot_id),
+					bot_resubmissions::user_id.eq(user_id),
+					bot_resubmissions::appeal.eq(appeal),
+				))
+				.returning(BotResubmission::as_returning())
+				.get_result(conn)
+		})
+	}
+}
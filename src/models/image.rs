@@ -0,0 +1,260 @@
+use crate::images::{ImageSpec, ProcessedImage};
+use crate::models::util::diesel::Conn;
+use crate::schema::{deleted_images, images};
+use crate::sql::pg_enum;
+use diesel::prelude::*;
+use diesel::{deserialize::FromSqlRow, dsl, expression::AsExpression};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+pg_enum! {
+	pub enum ImageKind {
+		BotBanner = 0,
+		BotScreenshot = 1,
+		UserBanner = 2,
+	}
+}
+
+impl ImageKind {
+	pub fn spec(self) -> &'static ImageSpec {
+		match self {
+			ImageKind::BotBanner | ImageKind::UserBanner => &ImageSpec::BANNER,
+			ImageKind::BotScreenshot => &ImageSpec::SCREENSHOT,
+		}
+	}
+
+	/// The directory of the images of this kind, in the one of their owner.
+	fn directory(self) -> &'static str {
+		match self {
+			ImageKind::BotBanner | ImageKind::UserBanner => "banners",
+			ImageKind::BotScreenshot => "screenshots",
+		}
+	}
+}
+
+/// The stored files of an image, one per variant, as
+/// `{key}/{width}.{extension}`.
+pub trait StoredImage {
+	/// Prefix of the stored files
+	fn key(&self) -> &str;
+	/// Extension of the stored files
+	fn extension(&self) -> &str;
+	/// Widths of the variants
+	fn variants(&self) -> &[i32];
+
+	/// The key of the stored file of the variant.
+	fn variant_key(&self, width: i32) -> String {
+		format!("{}/{width}.{}", self.key(), self.extension())
+	}
+
+	/// The keys of the stored files of all the variants.
+	fn object_keys(&self) -> Vec<String> {
+		self.variants()
+			.iter()
+			.map(|width| self.variant_key(*width))
+			.collect()
+	}
+}
+
+/// An image uploaded to the storage, owned by either a bot or a user.
+#[derive(Identifiable, Selectable, Queryable, Debug, Clone)]
+#[diesel(table_name = images, check_for_backend(diesel::pg::Pg))]
+pub struct Image {
+	pub id: i32,
+	pub kind: ImageKind,
+	/// ID of the bot the image belongs to
+	pub bot_id: Option<String>,
+	/// ID of the user the image belongs to
+	pub user_id: Option<String>,
+	/// Prefix of the stored files of the image
+	pub key: String,
+	/// Extension of the stored files, `png` or `jpg`
+	pub extension: String,
+	/// Width of the largest variant
+	pub width: i32,
+	/// Height of the largest variant
+	pub height: i32,
+	/// Widths of the variants, from the largest
+	pub variants: Vec<i32>,
+	/// Position of the screenshot in the gallery of the bot
+	pub position: i32,
+	pub created_at: chrono::NaiveDateTime,
+}
+
+impl Image {
+	/// The maximum number of screenshots of a bot.
+	pub const MAX_SCREENSHOTS: i64 = 8;
+
+	#[dsl::auto_type(no_type_alias)]
+	pub fn of_bot(bot_id: &str, kind: ImageKind) -> _ {
+		images::table
+			.filter(images::bot_id.eq(bot_id))
+			.filter(images::kind.eq(kind))
+	}
+
+	/// The screenshots of the bot, in the order of the gallery.
+	pub fn screenshots(conn: &mut impl Conn, bot_id: &str) -> QueryResult<Vec<Image>> {
+		Image::of_bot(bot_id, ImageKind::BotScreenshot)
+			.order((images::position.asc(), images::id.asc()))
+			.select(Image::as_select())
+			.load(conn)
+	}
+
+	pub fn user_banner(conn: &mut impl Conn, user_id: &str) -> QueryResult<Option<Image>> {
+		images::table
+			.filter(images::user_id.eq(user_id))
+			.filter(images::kind.eq(ImageKind::UserBanner))
+			.select(Image::as_select())
+			.first(conn)
+			.optional()
+	}
+
+	pub fn delete(&self, conn: &mut impl Conn) -> QueryResult<()> {
+		diesel::delete(self).execute(conn)?;
+		Ok(())
+	}
+}
+
+impl StoredImage for Image {
+	fn key(&self) -> &str {
+		&self.key
+	}
+
+	fn extension(&self) -> &str {
+		&self.extension
+	}
+
+	fn variants(&self) -> &[i32] {
+		&self.variants
+	}
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = images, check_for_backend(diesel::pg::Pg))]
+pub struct NewImage {
+	pub kind: ImageKind,
+	pub bot_id: Option<String>,
+	pub user_id: Option<String>,
+	pub key: String,
+	pub extension: &'static str,
+	pub width: i32,
+	pub height: i32,
+	pub variants: Vec<i32>,
+	pub position: i32,
+}
+
+impl NewImage {
+	/// Describes a processed image of the bot, under a new random key.
+	pub fn for_bot(kind: ImageKind, bot_id: &str, processed: &ProcessedImage) -> Self {
+		let key = format!("bots/{bot_id}/{}/{}", kind.directory(), random_name());
+
+		Self {
+			bot_id: Some(bot_id.into()),
+			..Self::new(kind, key, processed)
+		}
+	}
+
+	/// Describes a processed image of the user, under a new random key.
+	pub fn for_user(kind: ImageKind, user_id: &str, processed: &ProcessedImage) -> Self {
+		let key = format!("users/{user_id}/{}/{}", kind.directory(), random_name());
+
+		Self {
+			user_id: Some(user_id.into()),
+			..Self::new(kind, key, processed)
+		}
+	}
+
+	fn new(kind: ImageKind, key: String, processed: &ProcessedImage) -> Self {
+		let full = processed.full();
+
+		Self {
+			kind,
+			bot_id: None,
+			user_id: None,
+			key,
+			extension: processed.format.extension(),
+			width: full.width as i32,
+			height: full.height as i32,
+			variants: processed
+				.variants
+				.iter()
+				.map(|variant| variant.width as i32)
+				.collect(),
+			position: 0,
+		}
+	}
+
+	pub fn insert(&self, conn: &mut impl Conn) -> QueryResult<Image> {
+		diesel::insert_into(images::table)
+			.values(self)
+			.returning(Image::as_returning())
+			.get_result(conn)
+	}
+}
+
+impl StoredImage for NewImage {
+	fn key(&self) -> &str {
+		&self.key
+	}
+
+	fn extension(&self) -> &str {
+		self.extension
+	}
+
+	fn variants(&self) -> &[i32] {
+		&self.variants
+	}
+}
+
+/// An image of a deleted bot or user, queued by the database so that its
+/// stored files get deleted too.
+#[derive(Identifiable, Selectable, Queryable, Debug, Clone)]
+#[diesel(table_name = deleted_images, check_for_backend(diesel::pg::Pg))]
+pub struct DeletedImage {
+	pub id: i32,
+	pub key: String,
+	pub extension: String,
+	pub variants: Vec<i32>,
+	/// When the bot or the user was deleted
+	pub deleted_at: chrono::NaiveDateTime,
+}
+
+impl DeletedImage {
+	/// The oldest queued images, up to `limit`.
+	pub fn queued(conn: &mut impl Conn, limit: i64) -> QueryResult<Vec<DeletedImage>> {
+		deleted_images::table
+			.select(DeletedImage::as_select())
+			.order(deleted_images::id.asc())
+			.limit(limit)
+			.load(conn)
+	}
+
+	/// Removes the images from the queue once their files are deleted.
+	pub fn dequeue(conn: &mut impl Conn, ids: &[i32]) -> QueryResult<usize> {
+		diesel::delete(deleted_images::table.filter(deleted_images::id.eq_any(ids))).execute(conn)
+	}
+}
+
+impl StoredImage for DeletedImage {
+	fn key(&self) -> &str {
+		&self.key
+	}
+
+	fn extension(&self) -> &str {
+		&self.extension
+	}
+
+	fn variants(&self) -> &[i32] {
+		&self.variants
+	}
+}
+
+/// A random name, so that replaced images never share their URLs, which are
+/// cached forever.
+fn random_name() -> String {
+	rand::thread_rng()
+		.sample_iter(Alphanumeric)
+		.take(16)
+		.map(|c| char::from(c).to_ascii_lowercase())
+		.collect()
+}
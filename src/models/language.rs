@@ -0,0 +1,266 @@
+//! The languages the bots can speak and be described in.
+//!
+//! Languages are identified by their ISO 639-1 code, e.g. `es`, which is also
+//! a valid BCP 47 language tag.
+
+use crate::models::bot::BotStatus;
+use crate::models::util::diesel::Conn;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Text};
+use std::collections::HashMap;
+
+/// The ISO 639-1 codes and English names of the languages, sorted by code.
+const LANGUAGES: &[(&str, &str)] = &[
+	("aa", "Afar"),
+	("ab", "Abkhazian"),
+	("ae", "Avestan"),
+	("af", "Afrikaans"),
+	("ak", "Akan"),
+	("am", "Amharic"),
+	("an", "Aragonese"),
+	("ar", "Arabic"),
+	("as", "Assamese"),
+	("av", "Avaric"),
+	("ay", "Aymara"),
+	("az", "Azerbaijani"),
+	("ba", "Bashkir"),
+	("be", "Belarusian"),
+	("bg", "Bulgarian"),
+	("bi", "Bislama"),
+	("bm", "Bambara"),
+	("bn", "Bengali"),
+	("bo", "Tibetan"),
+	("br", "Breton"),
+	("bs", "Bosnian"),
+	("ca", "Catalan"),
+	("ce", "Chechen"),
+	("ch", "Chamorro"),
+	("co", "Corsican"),
+	("cr", "Cree"),
+	("cs", "Czech"),
+	("cu", "Church Slavic"),
+	("cv", "Chuvash"),
+	("cy", "Welsh"),
+	("da", "Danish"),
+	("de", "German"),
+	("dv", "Divehi"),
+	("dz", "Dzongkha"),
+	("ee", "Ewe"),
+	("el", "Greek"),
+	("en", "English"),
+	("eo", "Esperanto"),
+	("es", "Spanish"),
+	("et", "Estonian"),
+	("eu", "Basque"),
+	("fa", "Persian"),
+	("ff", "Fulah"),
+	("fi", "Finnish"),
+	("fj", "Fijian"),
+	("fo", "Faroese"),
+	("fr", "French"),
+	("fy", "Western Frisian"),
+	("ga", "Irish"),
+	("gd", "Scottish Gaelic"),
+	("gl", "Galician"),
+	("gn", "Guarani"),
+	("gu", "Gujarati"),
+	("gv", "Manx"),
+	("ha", "Hausa"),
+	("he", "Hebrew"),
+	("hi", "Hindi"),
+	("ho", "Hiri Motu"),
+	("hr", "Croatian"),
+	("ht", "Haitian Creole"),
+	("hu", "Hungarian"),
+	("hy", "Armenian"),
+	("hz", "Herero"),
+	("ia", "Interlingua"),
+	("id", "Indonesian"),
+	("ie", "Interlingue"),
+	("ig", "Igbo"),
+	("ii", "Sichuan Yi"),
+	("ik", "Inupiaq"),
+	("io", "Ido"),
+	("is", "Icelandic"),
+	("it", "Italian"),
+	("iu", "Inuktitut"),
+	("ja", "Japanese"),
+	("jv", "Javanese"),
+	("ka", "Georgian"),
+	("kg", "Kongo"),
+	("ki", "Kikuyu"),
+	("kj", "Kuanyama"),
+	("kk", "Kazakh"),
+	("kl", "Kalaallisut"),
+	("km", "Khmer"),
+	("kn", "Kannada"),
+	("ko", "Korean"),
+	("kr", "Kanuri"),
+	("ks", "Kashmiri"),
+	("ku", "Kurdish"),
+	("kv", "Komi"),
+	("kw", "Cornish"),
+	("ky", "Kyrgyz"),
+	("la", "Latin"),
+	("lb", "Luxembourgish"),
+	("lg", "Ganda"),
+	("li", "Limburgish"),
+	("ln", "Lingala"),
+	("lo", "Lao"),
+	("lt", "Lithuanian"),
+	("lu", "Luba-Katanga"),
+	("lv", "Latvian"),
+	("mg", "Malagasy"),
+	("mh", "Marshallese"),
+	("mi", "Maori"),
+	("mk", "Macedonian"),
+	("ml", "Malayalam"),
+	("mn", "Mongolian"),
+	("mr", "Marathi"),
+	("ms", "Malay"),
+	("mt", "Maltese"),
+	("my", "Burmese"),
+	("na", "Nauru"),
+	("nb", "Norwegian Bokmål"),
+	("nd", "North Ndebele"),
+	("ne", "Nepali"),
+	("ng", "Ndonga"),
+	("nl", "Dutch"),
+	("nn", "Norwegian Nynorsk"),
+	("no", "Norwegian"),
+	("nr", "South Ndebele"),
+	("nv", "Navajo"),
+	("ny", "Chichewa"),
+	("oc", "Occitan"),
+	("oj", "Ojibwa"),
+	("om", "Oromo"),
+	("or", "Odia"),
+	("os", "Ossetian"),
+	("pa", "Punjabi"),
+	("pi", "Pali"),
+	("pl", "Polish"),
+	("ps", "Pashto"),
+	("pt", "Portuguese"),
+	("qu", "Quechua"),
+	("rm", "Romansh"),
+	("rn", "Rundi"),
+	("ro", "Romanian"),
+	("ru", "Russian"),
+	("rw", "Kinyarwanda"),
+	("sa", "Sanskrit"),
+	("sc", "Sardinian"),
+	("sd", "Sindhi"),
+	("se", "Northern Sami"),
+	("sg", "Sango"),
+	("si", "Sinhala"),
+	("sk", "Slovak"),
+	("sl", "Slovenian"),
+	("sm", "Samoan"),
+	("sn", "Shona"),
+	("so", "Somali"),
+	("sq", "Albanian"),
+	("sr", "Serbian"),
+	("ss", "Swati"),
+	("st", "Southern Sotho"),
+	("su", "Sundanese"),
+	("sv", "Swedish"),
+	("sw", "Swahili"),
+	("ta", "Tamil"),
+	("te", "Telugu"),
+	("tg", "Tajik"),
+	("th", "Thai"),
+	("ti", "Tigrinya"),
+	("tk", "Turkmen"),
+	("tl", "Tagalog"),
+	("tn", "Tswana"),
+	("to", "Tongan"),
+	("tr", "Turkish"),
+	("ts", "Tsonga"),
+	("tt", "Tatar"),
+	("tw", "Twi"),
+	("ty", "Tahitian"),
+	("ug", "Uyghur"),
+	("uk", "Ukrainian"),
+	("ur", "Urdu"),
+	("uz", "Uzbek"),
+	("ve", "Venda"),
+	("vi", "Vietnamese"),
+	("vo", "Volapük"),
+	("wa", "Walloon"),
+	("wo", "Wolof"),
+	("xh", "Xhosa"),
+	("yi", "Yiddish"),
+	("yo", "Yoruba"),
+	("za", "Zhuang"),
+	("zh", "Chinese"),
+	("zu", "Zulu"),
+];
+
+/// A language of the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+	/// Lowercase ISO 639-1 code
+	pub code: &'static str,
+	/// English name
+	pub name: &'static str,
+}
+
+impl Language {
+	/// Every language of the registry, sorted by code.
+	pub fn all() -> impl Iterator<Item = Language> {
+		LANGUAGES
+			.iter()
+			.map(|&(code, name)| Language { code, name })
+	}
+
+	/// Finds the language with the given code, ignoring case.
+	pub fn find(code: &str) -> Option<Language> {
+		let code = code.to_ascii_lowercase();
+		LANGUAGES
+			.binary_search_by_key(&code.as_str(), |(code, _)| code)
+			.ok()
+			.map(|index| {
+				let (code, name) = LANGUAGES[index];
+				Language { code, name }
+			})
+	}
+
+	/// Counts the approved bots that speak each language, omitting the
+	/// languages no bot speaks.
+	pub fn bot_counts(conn: &mut impl Conn) -> QueryResult<HashMap<String, i64>> {
+		#[derive(QueryableByName)]
+		struct LanguageCount {
+			#[diesel(sql_type = Text)]
+			language: String,
+			#[diesel(sql_type = BigInt)]
+			count: i64,
+		}
+
+		let counts: Vec<LanguageCount> = diesel::sql_query(include_str!("language_counts.sql"))
+			.bind::<Integer, _>(BotStatus::APPROVED)
+			.load(conn)?;
+
+		Ok(counts
+			.into_iter()
+			.map(|count| (count.language, count.count))
+			.collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn registry_is_sorted() {
+		assert!(LANGUAGES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+	}
+
+	#[test]
+	fn finds_languages() {
+		assert_eq!(Language::find("ES").map(|l| l.name), Some("Spanish"));
+		assert_eq!(Language::find("zh").map(|l| l.name), Some("Chinese"));
+		assert_eq!(Language::find("xx"), None);
+		assert_eq!(Language::find("eng"), None);
+	}
+}
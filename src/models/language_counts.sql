@@ -0,0 +1,4 @@
+SELECT language, COUNT(*) AS count
+FROM bots, unnest(bots.supported_languages) AS language
+WHERE bots.status = $1
+GROUP BY language
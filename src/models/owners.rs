@@ -38,6 +38,18 @@ impl BotOwner {
 		Ok(bots)
 	}
 
+	/// Whether the user is one of the owners of the bot.
+	pub fn is_owner(conn: &mut impl Conn, bot_id: &str, user_id: &str) -> QueryResult<bool> {
+		use diesel::dsl::{exists, select};
+
+		select(exists(
+			bot_owners::table
+				.filter(bot_owners::bot_id.eq(bot_id))
+				.filter(bot_owners::user_id.eq(user_id)),
+		))
+		.get_result(conn)
+	}
+
 	pub fn boxed() -> BoxedQuery<'static> {
 		bot_owners::table.into_boxed()
 	}
@@ -0,0 +1,25 @@
+WITH RECURSIVE parents AS (SELECT id,
+                                  category,
+                                  slug,
+                                  description,
+                                  bots_cnt,
+                                  created_at,
+                                  1 AS depth
+                           FROM categories
+                           WHERE slug = regexp_replace($1, '::[^:]+$', '')
+                             AND $1 LIKE '%::%'
+                           UNION ALL
+                           SELECT c.id,
+                                  c.category,
+                                  c.slug,
+                                  c.description,
+                                  c.bots_cnt,
+                                  c.created_at,
+                                  p.depth + 1
+                           FROM categories AS c
+                                    INNER JOIN parents AS p
+                                               ON c.slug = regexp_replace(p.slug, '::[^:]+$', '')
+                           WHERE p.slug LIKE '%::%')
+SELECT id, category, slug, description, subtree_bots_cnt(slug) AS bots_cnt, created_at
+FROM parents
+ORDER BY depth DESC
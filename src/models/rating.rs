@@ -0,0 +1,317 @@
+use crate::models::util::diesel::Conn;
+use crate::models::{Bot, User};
+use crate::schema::{bot_rating_actions, bot_rating_flags, bot_rating_replies, bot_ratings};
+use crate::sql::pg_enum;
+use diesel::prelude::*;
+use diesel::{deserialize::FromSqlRow, expression::AsExpression};
+use serde::{Deserialize, Serialize};
+
+/// Represents a public rating of a bot, made by a user. Each user can rate
+/// a bot once, and edit the rating afterwards.
+///
+/// This is not related to the staff reviews in [`crate::models::BotReview`].
+#[derive(Identifiable, Selectable, Queryable, Associations, Debug, Clone)]
+#[diesel(
+    table_name = bot_ratings,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(User, foreign_key = user_id),
+    belongs_to(Bot, foreign_key = bot_id),
+)]
+pub struct BotRating {
+	/// Unique identifier of the rating
+	pub id: i32,
+	/// ID of the rated bot
+	pub bot_id: String,
+	/// ID of the user who rated the bot
+	pub user_id: String,
+	/// Amount of stars, from 1 to 5
+	pub rating: i16,
+	/// Text of the rating
+	pub body: String,
+	/// When the rating was created
+	pub created_at: chrono::NaiveDateTime,
+	/// Last time the rating was edited
+	pub updated_at: chrono::NaiveDateTime,
+	/// Whether the rating was hidden by a staff member
+	pub hidden: bool,
+}
+
+impl BotRating {
+	/// The lowest amount of stars a bot can be rated with.
+	pub const MIN_RATING: i16 = 1;
+	/// The highest amount of stars a bot can be rated with.
+	pub const MAX_RATING: i16 = 5;
+	/// The maximum length of the text of a rating.
+	pub const MAX_BODY_LENGTH: usize = 2000;
+
+	/// Find the rating of a user for a bot, if any.
+	pub fn find_by_user(
+		conn: &mut impl Conn,
+		bot_id: &str,
+		user_id: &str,
+	) -> QueryResult<Option<BotRating>> {
+		bot_ratings::table
+			.filter(bot_ratings::bot_id.eq(bot_id))
+			.filter(bot_ratings::user_id.eq(user_id))
+			.select(BotRating::as_select())
+			.first(conn)
+			.optional()
+	}
+
+	/// Applies a moderation action to the rating, recording who did it and why.
+	///
+	/// Every pending flag of the rating is resolved by the action.
+	pub fn moderate(
+		&self,
+		conn: &mut impl Conn,
+		actor_id: &str,
+		action: ModerationAction,
+		reason: &str,
+	) -> QueryResult<BotRatingAction> {
+		conn.transaction(|conn| {
+			let hidden = match action {
+				ModerationAction::HIDE => Some(true),
+				ModerationAction::RESTORE => Some(false),
+				ModerationAction::DISMISS => None,
+			};
+
+			if let Some(hidden) = hidden {
+				diesel::update(self)
+					.set(bot_ratings::hidden.eq(hidden))
+					.execute(conn)?;
+			}
+
+			diesel::update(BotRatingFlag::belonging_to(self))
+				.filter(bot_rating_flags::resolved_at.is_null())
+				.set(bot_rating_flags::resolved_at.eq(diesel::dsl::now))
+				.execute(conn)?;
+
+			diesel::insert_into(bot_rating_actions::table)
+				.values((
+					bot_rating_actions::rating_id.eq(self.id),
+					bot_rating_actions::bot_id.eq(&self.bot_id),
+					bot_rating_actions::user_id.eq(&self.user_id),
+					bot_rating_actions::actor_id.eq(actor_id),
+					bot_rating_actions::action.eq(action),
+					bot_rating_actions::reason.eq(reason),
+				))
+				.returning(BotRatingAction::as_returning())
+				.get_result(conn)
+		})
+	}
+}
+
+/// Represents the data needed to insert or update a bot rating in the database.
+#[derive(Insertable, Debug)]
+#[diesel(table_name = bot_ratings, check_for_backend(diesel::pg::Pg))]
+pub struct NewBotRating<'a> {
+	pub bot_id: &'a str,
+	pub user_id: &'a str,
+	pub rating: i16,
+	pub body: &'a str,
+}
+
+impl<'a> NewBotRating<'a> {
+	pub fn new(bot_id: &'a str, user_id: &'a str, rating: i16, body: &'a str) -> Self {
+		Self {
+			bot_id,
+			user_id,
+			rating,
+			body,
+		}
+	}
+
+	/// Inserts the rating, or updates the existing rating of the user for the bot.
+	///
+	/// A new rating is hidden if the previous rating of the user for the bot was
+	/// hidden by a staff member, so that deleting it doesn't undo the action.
+	pub fn upsert(&self, conn: &mut impl Conn) -> QueryResult<BotRating> {
+		use diesel::pg::upsert::excluded;
+
+		let hidden = BotRatingAction::was_hidden(conn, self.bot_id, self.user_id)?;
+
+		diesel::insert_into(bot_ratings::table)
+			.values((self, bot_ratings::hidden.eq(hidden)))
+			.on_conflict((bot_ratings::bot_id, bot_ratings::user_id))
+			.do_update()
+			.set((
+				bot_ratings::rating.eq(excluded(bot_ratings::rating)),
+				bot_ratings::body.eq(excluded(bot_ratings::body)),
+			))
+			.returning(BotRating::as_returning())
+			.get_result(conn)
+	}
+}
+
+/// The reply of a bot owner to a rating. Each rating can only have one reply.
+#[derive(Identifiable, Selectable, Queryable, Associations, Debug, Clone)]
+#[diesel(
+    table_name = bot_rating_replies,
+    check_for_backend(diesel::pg::Pg),
+    primary_key(rating_id),
+    belongs_to(BotRating, foreign_key = rating_id),
+)]
+pub struct BotRatingReply {
+	/// ID of the rating this reply belongs to
+	pub rating_id: i32,
+	/// ID of the owner who replied
+	pub user_id: String,
+	/// Text of the reply
+	pub body: String,
+	/// When the reply was created
+	pub created_at: chrono::NaiveDateTime,
+	/// Last time the reply was edited
+	pub updated_at: chrono::NaiveDateTime,
+}
+
+impl BotRatingReply {
+	/// The maximum length of the text of a reply.
+	pub const MAX_BODY_LENGTH: usize = 2000;
+
+	/// Inserts the reply, or updates the existing reply of the rating.
+	pub fn upsert(
+		conn: &mut impl Conn,
+		rating_id: i32,
+		user_id: &str,
+		body: &str,
+	) -> QueryResult<BotRatingReply> {
+		use diesel::pg::upsert::excluded;
+
+		diesel::insert_into(bot_rating_replies::table)
+			.values((
+				bot_rating_replies::rating_id.eq(rating_id),
+				bot_rating_replies::user_id.eq(user_id),
+				bot_rating_replies::body.eq(body),
+			))
+			.on_conflict(bot_rating_replies::rating_id)
+			.do_update()
+			.set((
+				bot_rating_replies::user_id.eq(excluded(bot_rating_replies::user_id)),
+				bot_rating_replies::body.eq(excluded(bot_rating_replies::body)),
+			))
+			.returning(BotRatingReply::as_returning())
+			.get_result(conn)
+	}
+}
+
+/// A report of an abusive rating, made by a user.
+#[derive(Identifiable, Selectable, Queryable, Associations, Debug, Clone)]
+#[diesel(
+    table_name = bot_rating_flags,
+    check_for_backend(diesel::pg::Pg),
+    primary_key(rating_id, user_id),
+    belongs_to(BotRating, foreign_key = rating_id),
+)]
+pub struct BotRatingFlag {
+	/// ID of the flagged rating
+	pub rating_id: i32,
+	/// ID of the user who flagged the rating
+	pub user_id: String,
+	/// Why the user flagged the rating
+	pub reason: String,
+	/// When the rating was flagged
+	pub created_at: chrono::NaiveDateTime,
+	/// When a staff member acted on the flag, if they did
+	pub resolved_at: Option<chrono::NaiveDateTime>,
+}
+
+impl BotRatingFlag {
+	/// The maximum length of the reason of a flag.
+	pub const MAX_REASON_LENGTH: usize = 500;
+
+	/// Flags the rating, or re-opens the previous flag of the user with the new reason.
+	pub fn upsert(
+		conn: &mut impl Conn,
+		rating_id: i32,
+		user_id: &str,
+		reason: &str,
+	) -> QueryResult<BotRatingFlag> {
+		use diesel::pg::upsert::excluded;
+
+		diesel::insert_into(bot_rating_flags::table)
+			.values((
+				bot_rating_flags::rating_id.eq(rating_id),
+				bot_rating_flags::user_id.eq(user_id),
+				bot_rating_flags::reason.eq(reason),
+			))
+			.on_conflict((bot_rating_flags::rating_id, bot_rating_flags::user_id))
+			.do_update()
+			.set((
+				bot_rating_flags::reason.eq(excluded(bot_rating_flags::reason)),
+				bot_rating_flags::created_at.eq(diesel::dsl::now),
+				bot_rating_flags::resolved_at.eq(None::<chrono::NaiveDateTime>),
+			))
+			.returning(BotRatingFlag::as_returning())
+			.get_result(conn)
+	}
+}
+
+pg_enum! {
+	pub enum ModerationAction {
+		HIDE = 0,
+		RESTORE = 1,
+		DISMISS = 2,
+	}
+}
+
+impl From<ModerationAction> for &'static str {
+	fn from(action: ModerationAction) -> Self {
+		match action {
+			ModerationAction::HIDE => "HIDE",
+			ModerationAction::RESTORE => "RESTORE",
+			ModerationAction::DISMISS => "DISMISS",
+		}
+	}
+}
+
+impl From<ModerationAction> for String {
+	fn from(action: ModerationAction) -> Self {
+		let string: &'static str = action.into();
+
+		string.into()
+	}
+}
+
+/// A moderation action taken by a staff member on a rating.
+#[derive(Identifiable, Selectable, Queryable, Associations, Debug, Clone)]
+#[diesel(
+    table_name = bot_rating_actions,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(BotRating, foreign_key = rating_id),
+)]
+pub struct BotRatingAction {
+	/// Unique identifier of the action
+	pub id: i32,
+	/// ID of the moderated rating, `None` once its author deleted it
+	pub rating_id: Option<i32>,
+	/// ID of the staff member who took the action, `None` once their account
+	/// was deleted
+	pub actor_id: Option<String>,
+	/// What was done to the rating
+	pub action: ModerationAction,
+	/// Why the action was taken
+	pub reason: String,
+	/// When the action was taken
+	pub created_at: chrono::NaiveDateTime,
+	/// ID of the rated bot
+	pub bot_id: String,
+	/// ID of the author of the rating
+	pub user_id: String,
+}
+
+impl BotRatingAction {
+	/// Whether the last staff action on the ratings of the user for the bot,
+	/// including the deleted ones, hid the rating.
+	pub fn was_hidden(conn: &mut impl Conn, bot_id: &str, user_id: &str) -> QueryResult<bool> {
+		let last_action = bot_rating_actions::table
+			.filter(bot_rating_actions::bot_id.eq(bot_id))
+			.filter(bot_rating_actions::user_id.eq(user_id))
+			.filter(bot_rating_actions::action.ne(ModerationAction::DISMISS))
+			.order(bot_rating_actions::id.desc())
+			.select(bot_rating_actions::action)
+			.first::<ModerationAction>(conn)
+			.optional()?;
+
+		Ok(last_action == Some(ModerationAction::HIDE))
+	}
+}
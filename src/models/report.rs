@@ -0,0 +1,193 @@
+use crate::models::bot::BotStatus;
+use crate::models::util::diesel::Conn;
+use crate::models::{Bot, User};
+use crate::schema::bot_reports;
+use crate::sql::pg_enum;
+use crate::util::errors::{api_error, BoxedAppError, ErrorCode};
+use diesel::prelude::*;
+use diesel::{deserialize::FromSqlRow, expression::AsExpression};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pg_enum! {
+	pub enum ReportReason {
+		SCAM = 0,
+		NSFW = 1,
+		BROKEN = 2,
+		SPAM = 3,
+		IMPERSONATION = 4,
+		OTHER = 5,
+	}
+}
+
+impl From<ReportReason> for &'static str {
+	fn from(reason: ReportReason) -> Self {
+		match reason {
+			ReportReason::SCAM => "SCAM",
+			ReportReason::NSFW => "NSFW",
+			ReportReason::BROKEN => "BROKEN",
+			ReportReason::SPAM => "SPAM",
+			ReportReason::IMPERSONATION => "IMPERSONATION",
+			ReportReason::OTHER => "OTHER",
+		}
+	}
+}
+
+impl From<ReportReason> for String {
+	fn from(reason: ReportReason) -> Self {
+		let string: &'static str = reason.into();
+
+		string.into()
+	}
+}
+
+impl FromStr for ReportReason {
+	type Err = BoxedAppError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		ReportReason::VARIANTS
+			.iter()
+			.copied()
+			.find(|reason| <&str>::from(*reason).eq_ignore_ascii_case(s))
+			.ok_or_else(|| {
+				api_error(
+					ErrorCode::InvalidValue,
+					format!("invalid report reason `{s}`"),
+				)
+			})
+	}
+}
+
+pg_enum! {
+	pub enum ReportStatus {
+		OPEN = 0,
+		INVESTIGATING = 1,
+		RESOLVED = 2,
+		DISMISSED = 3,
+	}
+}
+
+impl From<ReportStatus> for &'static str {
+	fn from(status: ReportStatus) -> Self {
+		match status {
+			ReportStatus::OPEN => "OPEN",
+			ReportStatus::INVESTIGATING => "INVESTIGATING",
+			ReportStatus::RESOLVED => "RESOLVED",
+			ReportStatus::DISMISSED => "DISMISSED",
+		}
+	}
+}
+
+impl From<ReportStatus> for String {
+	fn from(status: ReportStatus) -> Self {
+		let string: &'static str = status.into();
+
+		string.into()
+	}
+}
+
+impl FromStr for ReportStatus {
+	type Err = BoxedAppError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		ReportStatus::VARIANTS
+			.iter()
+			.copied()
+			.find(|status| <&str>::from(*status).eq_ignore_ascii_case(s))
+			.ok_or_else(|| {
+				api_error(
+					ErrorCode::InvalidValue,
+					format!("invalid report status `{s}`"),
+				)
+			})
+	}
+}
+
+/// Represents a report of a bot made by a user, e.g. because it is a scam
+/// or doesn't work.
+#[derive(Identifiable, Selectable, Queryable, Associations, Debug, Clone)]
+#[diesel(
+    table_name = bot_reports,
+    check_for_backend(diesel::pg::Pg),
+    belongs_to(User, foreign_key = user_id),
+    belongs_to(Bot, foreign_key = bot_id),
+)]
+pub struct BotReport {
+	/// Unique identifier of the report
+	pub id: i32,
+	/// ID of the reported bot
+	pub bot_id: String,
+	/// ID of the user who reported the bot
+	pub user_id: String,
+	/// Why the bot was reported
+	pub reason: ReportReason,
+	/// Details given by the reporter
+	pub body: String,
+	/// Triage state of the report
+	pub status: ReportStatus,
+	/// ID of the staff member who last handled the report
+	pub handled_by: Option<String>,
+	/// Notes of the staff member about the outcome of the report
+	pub resolution: Option<String>,
+	/// The status the bot was changed to while handling the report, if any
+	pub bot_status: Option<BotStatus>,
+	/// When the report was created
+	pub created_at: chrono::NaiveDateTime,
+	/// Last time the report was updated
+	pub updated_at: chrono::NaiveDateTime,
+}
+
+impl BotReport {
+	/// The maximum length of the details of a report.
+	pub const MAX_BODY_LENGTH: usize = 2000;
+	/// Whether the user already has a report of the bot waiting for triage.
+	pub fn has_pending(conn: &mut impl Conn, bot_id: &str, user_id: &str) -> QueryResult<bool> {
+		diesel::select(diesel::dsl::exists(
+			bot_reports::table
+				.filter(bot_reports::bot_id.eq(bot_id))
+				.filter(bot_reports::user_id.eq(user_id))
+				.filter(
+					bot_reports::status.eq_any([ReportStatus::OPEN, ReportStatus::INVESTIGATING]),
+				),
+		))
+		.get_result(conn)
+	}
+}
+
+/// Represents the data needed to insert a bot report in the database.
+#[derive(Insertable, Debug)]
+#[diesel(table_name = bot_reports, check_for_backend(diesel::pg::Pg))]
+pub struct NewBotReport<'a> {
+	pub bot_id: &'a str,
+	pub user_id: &'a str,
+	pub reason: ReportReason,
+	pub body: &'a str,
+}
+
+impl<'a> NewBotReport<'a> {
+	pub fn new(bot_id: &'a str, user_id: &'a str, reason: ReportReason, body: &'a str) -> Self {
+		Self {
+			bot_id,
+			user_id,
+			reason,
+			body,
+		}
+	}
+
+	pub fn create(&self, conn: &mut impl Conn) -> QueryResult<BotReport> {
+		diesel::insert_into(bot_reports::table)
+			.values(self)
+			.returning(BotReport::as_returning())
+			.get_result(conn)
+	}
+}
+
+/// Changes made by a staff member while triaging a report.
+#[derive(AsChangeset, Debug)]
+#[diesel(table_name = bot_reports, check_for_backend(diesel::pg::Pg))]
+pub struct BotReportChanges<'a> {
+	pub status: Option<ReportStatus>,
+	pub handled_by: &'a str,
+	pub resolution: Option<&'a str>,
+	pub bot_status: Option<BotStatus>,
+}
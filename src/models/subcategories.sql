@@ -0,0 +1,10 @@
+SELECT c.id,
+       c.category,
+       c.slug,
+       c.description,
+       subtree_bots_cnt(c.slug) AS bots_cnt,
+       c.created_at
+FROM categories AS c
+WHERE c.slug LIKE $1 || '::%'
+  AND c.slug NOT LIKE $1 || '::%::%'
+ORDER BY c.category
@@ -0,0 +1,169 @@
+use crate::markdown::{self, ImageProxyUrl};
+use crate::models::util::diesel::Conn;
+use crate::models::Bot;
+use crate::schema::bot_translations;
+use diesel::prelude::*;
+
+/// Represents the descriptions of a bot translated by its owners to one
+/// language. The descriptions of the bot itself are the default, used when
+/// there is no translation to the language of the visitor.
+#[derive(Identifiable, Selectable, Queryable, Associations, Debug, Clone)]
+#[diesel(
+    table_name = bot_translations,
+    check_for_backend(diesel::pg::Pg),
+    primary_key(bot_id, language),
+    belongs_to(Bot, foreign_key = bot_id),
+)]
+pub struct BotTranslation {
+	/// ID of the translated bot
+	pub bot_id: String,
+	/// Lowercase language code, e.g. `es`
+	pub language: String,
+	/// Translated short description
+	pub short_description: String,
+	/// Translated description
+	pub description: String,
+	/// Translated description rendered to sanitized HTML, see [`crate::markdown`]
+	pub description_html: String,
+	/// Sanitizer policy the description was rendered with
+	pub description_html_version: i32,
+	/// When the translation was created
+	pub created_at: chrono::NaiveDateTime,
+	/// Last time the translation was updated
+	pub updated_at: chrono::NaiveDateTime,
+	/// Image proxy the description was rendered with, see [`markdown::proxy_key`]
+	pub description_html_proxy: String,
+}
+
+impl BotTranslation {
+	/// The language of the descriptions of the bots themselves, which can't
+	/// be translated to.
+	pub const DEFAULT_LANGUAGE: &'static str = "en";
+
+	/// Lists the translations of the bot, sorted by language.
+	pub fn for_bot(conn: &mut impl Conn, bot_id: &str) -> QueryResult<Vec<BotTranslation>> {
+		bot_translations::table
+			.filter(bot_translations::bot_id.eq(bot_id))
+			.order(bot_translations::language.asc())
+			.select(BotTranslation::as_select())
+			.load(conn)
+	}
+
+	/// Deletes the translation of the bot to the language, returning whether
+	/// there was one.
+	pub fn delete(conn: &mut impl Conn, bot_id: &str, language: &str) -> QueryResult<bool> {
+		let deleted =
+			diesel::delete(bot_translations::table.find((bot_id, language))).execute(conn)?;
+
+		Ok(deleted > 0)
+	}
+
+	/// Picks the translation for the most preferred of the language ranges,
+	/// like `de-ch`, that has one. `None` means the default descriptions,
+	/// either because none of the ranges has a translation, or because the
+	/// default language is preferred to the translated ones.
+	pub fn negotiate(
+		mut translations: Vec<BotTranslation>,
+		ranges: &[String],
+	) -> Option<BotTranslation> {
+		for range in ranges {
+			let primary = range.split('-').next().unwrap_or(range);
+			if primary == Self::DEFAULT_LANGUAGE {
+				return None;
+			}
+
+			if let Some(index) = translations
+				.iter()
+				.position(|translation| translation.language == primary)
+			{
+				return Some(translations.swap_remove(index));
+			}
+		}
+
+		None
+	}
+}
+
+#[derive(Insertable, AsChangeset, Debug)]
+#[diesel(table_name = bot_translations, check_for_backend(diesel::pg::Pg))]
+pub struct NewBotTranslation<'a> {
+	pub bot_id: &'a str,
+	pub language: &'a str,
+	pub short_description: &'a str,
+	pub description: &'a str,
+	pub description_html: String,
+	pub description_html_version: i32,
+	pub description_html_proxy: String,
+}
+
+impl<'a> NewBotTranslation<'a> {
+	/// Renders the description with [`markdown::render`].
+	pub fn new(
+		bot_id: &'a str,
+		language: &'a str,
+		short_description: &'a str,
+		description: &'a str,
+		image_proxy: Option<&ImageProxyUrl>,
+	) -> Self {
+		Self {
+			bot_id,
+			language,
+			short_description,
+			description,
+			description_html: markdown::render(description, image_proxy),
+			description_html_version: markdown::POLICY_VERSION,
+			description_html_proxy: markdown::proxy_key(image_proxy),
+		}
+	}
+
+	/// Creates the translation, or replaces the existing translation to the
+	/// same language.
+	pub fn save(&self, conn: &mut impl Conn) -> QueryResult<BotTranslation> {
+		diesel::insert_into(bot_translations::table)
+			.values(self)
+			.on_conflict((bot_translations::bot_id, bot_translations::language))
+			.do_update()
+			.set(self)
+			.returning(BotTranslation::as_returning())
+			.get_result(conn)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn translations(languages: &[&str]) -> Vec<BotTranslation> {
+		languages
+			.iter()
+			.map(|language| BotTranslation {
+				bot_id: "1".into(),
+				language: language.to_string(),
+				short_description: String::new(),
+				description: String::new(),
+				description_html: String::new(),
+				description_html_version: markdown::POLICY_VERSION,
+				created_at: chrono::NaiveDateTime::default(),
+				updated_at: chrono::NaiveDateTime::default(),
+				description_html_proxy: String::new(),
+			})
+			.collect()
+	}
+
+	fn negotiate(languages: &[&str], ranges: &[&str]) -> Option<String> {
+		let ranges = ranges.iter().map(ToString::to_string).collect::<Vec<_>>();
+		BotTranslation::negotiate(translations(languages), &ranges).map(|t| t.language)
+	}
+
+	#[test]
+	fn negotiates_translations() {
+		assert_eq!(negotiate(&["de", "es"], &["es"]).as_deref(), Some("es"));
+		assert_eq!(
+			negotiate(&["de", "es"], &["fr", "de-ch"]).as_deref(),
+			Some("de")
+		);
+		assert_eq!(negotiate(&["de", "es"], &["en-us", "de"]), None);
+		assert_eq!(negotiate(&["de", "es"], &["ja"]), None);
+		assert_eq!(negotiate(&[], &["de"]), None);
+	}
+}
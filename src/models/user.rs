@@ -2,7 +2,50 @@ use super::BotOwner;
 use crate::models::util::diesel::Conn;
 use crate::models::Bot;
 use crate::schema::{bot_owners, users};
+use crate::sql::pg_enum;
+use crate::util::errors::{api_error, BoxedAppError, ErrorCode};
 use diesel::prelude::*;
+use diesel::{deserialize::FromSqlRow, expression::AsExpression};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pg_enum! {
+	pub enum UserRole {
+		USER = 0,
+		MODERATOR = 1,
+		ADMIN = 2,
+	}
+}
+
+impl From<UserRole> for &'static str {
+	fn from(role: UserRole) -> Self {
+		match role {
+			UserRole::USER => "USER",
+			UserRole::MODERATOR => "MODERATOR",
+			UserRole::ADMIN => "ADMIN",
+		}
+	}
+}
+
+impl From<UserRole> for String {
+	fn from(role: UserRole) -> Self {
+		let string: &'static str = role.into();
+
+		string.into()
+	}
+}
+
+impl FromStr for UserRole {
+	type Err = BoxedAppError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		UserRole::VARIANTS
+			.iter()
+			.copied()
+			.find(|role| <&str>::from(*role).eq_ignore_ascii_case(s))
+			.ok_or_else(|| api_error(ErrorCode::InvalidValue, format!("invalid role `{s}`")))
+	}
+}
 
 /// User model
 #[derive(Clone, Debug, PartialEq, Eq, Queryable, Identifiable, AsChangeset)]
@@ -14,7 +57,7 @@ pub struct User {
 	pub username: String,
 	/// Avatar hash of the user.
 	pub avatar: Option<String>,
-	/// Banner URL of the user.
+	/// Key of the stored banner of the user.
 	pub banner: Option<String>,
 	/// A beautiful biography.
 	pub bio: Option<String>,
@@ -24,9 +67,25 @@ pub struct User {
 	pub updated_at: chrono::NaiveDateTime,
 	/// Discord access token
 	pub dc_access_token: String,
+	/// Role of the user on the website.
+	pub role: UserRole,
+	/// When the user was banned, `None` if the user is not banned.
+	pub banned_at: Option<chrono::NaiveDateTime>,
+	/// Why the user was banned.
+	pub ban_reason: Option<String>,
 }
 
 impl User {
+	/// Whether the user is part of the staff team (moderators and admins).
+	pub fn is_staff(&self) -> bool {
+		self.role != UserRole::USER
+	}
+
+	/// Whether the user is banned, banned users cannot authenticate.
+	pub fn is_banned(&self) -> bool {
+		self.banned_at.is_some()
+	}
+
 	/// Get the user by their ID.
 	pub fn find(conn: &mut impl Conn, id: &str) -> QueryResult<User> {
 		users::table.find(id).first(conn)
@@ -0,0 +1,303 @@
+//! Token-bucket rate limiting backed by Postgres, so that the limits are
+//! shared between every instance of the service.
+//!
+//! Each action can be configured with the following environment variables,
+//! where `{ACTION}` is e.g. `PUBLISH_NEW`:
+//!
+//! - `RATE_LIMITER_{ACTION}_RATE_SECONDS`: Seconds it takes to refill one token.
+//! - `RATE_LIMITER_{ACTION}_BURST`: Number of requests allowed in a row.
+
+use crate::models::util::diesel::Conn;
+use crate::schema::{rate_limit_buckets, rate_limit_overrides};
+use crate::sql::{date_part, floor, greatest, least, pg_enum};
+use crate::util::errors::{
+	api_error, AppResult, BoxedAppError, ErrorCode, ReadOnlyMode, TooManyRequests,
+};
+use chrono::NaiveDateTime;
+use crates_io_env_vars::var_parsed;
+use diesel::data_types::PgInterval;
+use diesel::dsl::now;
+use diesel::prelude::*;
+use diesel::sql_types::Interval;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+pg_enum! {
+	pub enum LimitedAction {
+		PublishNew = 0,
+		Vote = 1,
+		TokenCreate = 2,
+		Login = 3,
+		ImageUpload = 4,
+		Report = 5,
+		ImageProxy = 6,
+	}
+}
+
+impl LimitedAction {
+	pub fn default_rate_seconds(&self) -> u64 {
+		match self {
+			LimitedAction::PublishNew => 60 * 10,
+			LimitedAction::Vote => 60 * 60 * 12,
+			LimitedAction::TokenCreate => 60,
+			LimitedAction::Login => 10,
+			LimitedAction::ImageUpload => 60,
+			LimitedAction::Report => 60 * 60 * 24 / 5,
+			LimitedAction::ImageProxy => 1,
+		}
+	}
+
+	pub fn default_burst(&self) -> i32 {
+		match self {
+			LimitedAction::PublishNew => 5,
+			LimitedAction::Vote => 1,
+			LimitedAction::TokenCreate => 10,
+			LimitedAction::Login => 30,
+			LimitedAction::ImageUpload => 10,
+			LimitedAction::Report => 5,
+			LimitedAction::ImageProxy => 120,
+		}
+	}
+
+	pub fn env_var_key(&self) -> &'static str {
+		match self {
+			LimitedAction::PublishNew => "PUBLISH_NEW",
+			LimitedAction::Vote => "VOTE",
+			LimitedAction::TokenCreate => "TOKEN_CREATE",
+			LimitedAction::Login => "LOGIN",
+			LimitedAction::ImageUpload => "IMAGE_UPLOAD",
+			LimitedAction::Report => "REPORT",
+			LimitedAction::ImageProxy => "IMAGE_PROXY",
+		}
+	}
+
+	pub fn error_message(&self) -> &'static str {
+		match self {
+			LimitedAction::PublishNew => {
+				"You have published too many new bots in a short period of time."
+			}
+			LimitedAction::Vote => "You can only vote for a bot once every 12 hours.",
+			LimitedAction::TokenCreate => {
+				"You have created too many API tokens in a short period of time."
+			}
+			LimitedAction::Login => {
+				"You have tried to log in too many times in a short period of time."
+			}
+			LimitedAction::ImageUpload => {
+				"You have uploaded too many images in a short period of time."
+			}
+			LimitedAction::Report => "You have sent too many reports recently.",
+			LimitedAction::ImageProxy => {
+				"You have requested too many images in a short period of time."
+			}
+		}
+	}
+}
+
+impl FromStr for LimitedAction {
+	type Err = BoxedAppError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		LimitedAction::VARIANTS
+			.iter()
+			.copied()
+			.find(|action| action.env_var_key().eq_ignore_ascii_case(s))
+			.ok_or_else(|| {
+				api_error(
+					ErrorCode::InvalidValue,
+					format!("invalid rate limited action `{s}`"),
+				)
+			})
+	}
+}
+
+/// What a bucket is keyed by.
+#[derive(Debug, Clone, Copy)]
+pub enum RateLimitKey<'a> {
+	/// An authenticated user.
+	User(&'a str),
+	/// An authenticated user acting on a bot, for the actions limited per bot.
+	UserBot(&'a str, &'a str),
+	/// The IP address of an anonymous client.
+	Ip(IpAddr),
+}
+
+impl fmt::Display for RateLimitKey<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			RateLimitKey::User(id) => write!(f, "user:{id}"),
+			RateLimitKey::UserBot(user_id, bot_id) => write!(f, "user:{user_id}:bot:{bot_id}"),
+			RateLimitKey::Ip(ip) => write!(f, "ip:{ip}"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimiterConfig {
+	pub rate: Duration,
+	pub burst: i32,
+}
+
+impl RateLimiterConfig {
+	/// Reads the configuration of every action from the environment.
+	pub fn from_environment() -> anyhow::Result<HashMap<LimitedAction, RateLimiterConfig>> {
+		let mut config = HashMap::new();
+
+		for action in LimitedAction::VARIANTS {
+			let env_var_key = action.env_var_key();
+
+			let rate = var_parsed(&format!("RATE_LIMITER_{env_var_key}_RATE_SECONDS"))?
+				.unwrap_or_else(|| action.default_rate_seconds());
+			let burst = var_parsed(&format!("RATE_LIMITER_{env_var_key}_BURST"))?
+				.unwrap_or_else(|| action.default_burst());
+
+			config.insert(
+				*action,
+				RateLimiterConfig {
+					rate: Duration::from_secs(rate),
+					burst,
+				},
+			);
+		}
+
+		Ok(config)
+	}
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+	config: HashMap<LimitedAction, RateLimiterConfig>,
+}
+
+impl RateLimiter {
+	pub fn new(config: HashMap<LimitedAction, RateLimiterConfig>) -> Self {
+		Self { config }
+	}
+
+	/// Takes a token from the bucket of the key, and fails with
+	/// `429 Too Many Requests` if the bucket is empty.
+	pub fn check_rate_limit(
+		&self,
+		key: RateLimitKey<'_>,
+		action: LimitedAction,
+		conn: &mut impl Conn,
+	) -> AppResult<()> {
+		let bucket = match self.take_token(key, action, conn) {
+			Ok(bucket) => bucket,
+			Err(err) => {
+				// The buckets can't be updated in read-only mode, let the
+				// request fail later if it needs to write anything else.
+				let err = BoxedAppError::from(err);
+				return if err.is::<ReadOnlyMode>() {
+					Ok(())
+				} else {
+					Err(err)
+				};
+			}
+		};
+
+		if bucket.tokens >= 1 {
+			return Ok(());
+		}
+
+		let refill_rate = self.config_for_action(action).rate;
+		let refill_rate = chrono::Duration::seconds(refill_rate.as_secs() as i64);
+
+		Err(Box::new(TooManyRequests {
+			action,
+			retry_after: bucket.last_refill + refill_rate,
+		}))
+	}
+
+	/// Refills the bucket by the tokens accumulated since the last refill,
+	/// then takes one token out of it.
+	///
+	/// The returned bucket holds the values after the update. The token of the
+	/// current request is only subtracted by the next update, so the request
+	/// is allowed if the bucket holds at least one token.
+	fn take_token(
+		&self,
+		key: RateLimitKey<'_>,
+		action: LimitedAction,
+		conn: &mut impl Conn,
+	) -> QueryResult<Bucket> {
+		let config = self.config_for_action(action);
+		let refill_rate = PgInterval::from_microseconds(config.rate.as_micros() as i64);
+		let burst = self.burst(key, action, conn)?;
+
+		let elapsed_seconds =
+			date_part("epoch", now) - date_part("epoch", rate_limit_buckets::last_refill);
+		let tokens_to_add = floor(elapsed_seconds / config.rate.as_secs_f64().max(f64::EPSILON));
+
+		diesel::insert_into(rate_limit_buckets::table)
+			.values((
+				rate_limit_buckets::action.eq(action),
+				rate_limit_buckets::key.eq(key.to_string()),
+				rate_limit_buckets::tokens.eq(burst),
+				rate_limit_buckets::last_refill.eq(now),
+			))
+			.on_conflict((rate_limit_buckets::action, rate_limit_buckets::key))
+			.do_update()
+			.set((
+				rate_limit_buckets::tokens.eq(least(
+					burst,
+					greatest(0, rate_limit_buckets::tokens - 1) + tokens_to_add,
+				)),
+				rate_limit_buckets::last_refill.eq(rate_limit_buckets::last_refill
+					+ refill_rate.into_sql::<Interval>() * tokens_to_add),
+			))
+			.returning((rate_limit_buckets::tokens, rate_limit_buckets::last_refill))
+			.get_result::<(i32, NaiveDateTime)>(conn)
+			.map(|(tokens, last_refill)| Bucket {
+				tokens,
+				last_refill,
+			})
+	}
+
+	/// Returns the burst of the action, raised by an unexpired override for the
+	/// keys of users.
+	fn burst(
+		&self,
+		key: RateLimitKey<'_>,
+		action: LimitedAction,
+		conn: &mut impl Conn,
+	) -> QueryResult<i32> {
+		let default_burst = self.config_for_action(action).burst;
+
+		let (RateLimitKey::User(user_id) | RateLimitKey::UserBot(user_id, _)) = key else {
+			return Ok(default_burst);
+		};
+
+		let burst = rate_limit_overrides::table
+			.find((action, user_id))
+			.filter(
+				rate_limit_overrides::expires_at
+					.is_null()
+					.or(rate_limit_overrides::expires_at.gt(now)),
+			)
+			.select(rate_limit_overrides::burst)
+			.first::<i32>(conn)
+			.optional()?;
+
+		Ok(burst.unwrap_or(default_burst))
+	}
+
+	fn config_for_action(&self, action: LimitedAction) -> RateLimiterConfig {
+		self.config
+			.get(&action)
+			.copied()
+			.unwrap_or(RateLimiterConfig {
+				rate: Duration::from_secs(action.default_rate_seconds()),
+				burst: action.default_burst(),
+			})
+	}
+}
+
+#[derive(Debug)]
+struct Bucket {
+	tokens: i32,
+	last_refill: NaiveDateTime,
+}
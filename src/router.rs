@@ -1,14 +1,18 @@
 use super::controllers::*;
 use crate::app::{App, AppState};
+use crate::images::ImageSpec;
 use crate::middleware;
+use crate::storage::{StorageBackend, LOCAL_PATH_PREFIX};
 use crate::util::errors::{not_found, AppResult};
+use axum::extract::DefaultBodyLimit;
 use axum::response::IntoResponse;
 use axum::routing::post;
-use axum::routing::{delete, get};
+use axum::routing::{delete, get, patch, put};
 use axum::{Json, Router};
 use reqwest::{Method, StatusCode};
 use serde_json::Value;
 use std::sync::Arc;
+use tower_http::services::ServeDir;
 
 pub fn build_handler(app: Arc<App>) -> axum::Router {
 	let state = AppState(app);
@@ -21,27 +25,130 @@ pub fn build_axum_router(state: AppState) -> Router<()> {
 	let router = Router::new()
 		.route("/", get(handler))
 		.route("/summary", get(summary::summary))
+		.route("/metrics", get(metrics::prometheus))
+		.route("/health/live", get(health::live))
+		.route("/health/ready", get(health::ready))
 		// Session management
 		.route("/private/session/login", get(user::session::login))
 		.route("/private/session/authorize", get(user::session::authorize))
 		.route("/private/session", delete(user::session::logout))
 		// Bots
+		.route("/bots", get(bot::search::search))
 		.route("/bots/:bot_id", get(bot::metadata::show))
 		.route("/bots/new", post(bot::manage::publish))
 		.route("/bots/:bot_id/owners", get(bot::owners::owners))
+		.route("/bots/:bot_id/invite", get(bot::invite::generate))
 		.route(
 			"/bots/:bot_id/votes",
 			get(bot::votes::votes).post(bot::votes::vote),
 		)
+		.route(
+			"/bots/:bot_id/ratings",
+			get(bot::ratings::list)
+				.put(bot::ratings::update)
+				.delete(bot::ratings::delete),
+		)
+		.route("/bots/:bot_id/reports", post(bot::reports::create))
+		.route("/bots/:bot_id/resubmit", post(bot::manage::resubmit))
+		.route("/bots/:bot_id/denials", get(bot::manage::denials))
+		.route("/bots/:bot_id/audit", get(bot::audit::list))
+		.route(
+			"/bots/:bot_id/banner",
+			put(bot::images::update_banner)
+				.delete(bot::images::delete_banner)
+				.layer(DefaultBodyLimit::max(ImageSpec::BANNER.max_bytes)),
+		)
+		.route(
+			"/bots/:bot_id/screenshots",
+			get(bot::images::screenshots)
+				.post(bot::images::add_screenshot)
+				.layer(DefaultBodyLimit::max(ImageSpec::SCREENSHOT.max_bytes)),
+		)
+		.route(
+			"/bots/:bot_id/screenshots/:image_id",
+			delete(bot::images::delete_screenshot),
+		)
+		.route("/bots/:bot_id/translations", get(bot::translations::list))
+		.route(
+			"/bots/:bot_id/translations/:language",
+			put(bot::translations::update).delete(bot::translations::delete),
+		)
+		.route(
+			"/bots/:bot_id/certification",
+			get(bot::certification::show).post(bot::certification::apply),
+		)
+		.route(
+			"/bots/:bot_id/ratings/:rating_id/reply",
+			put(bot::ratings::reply).delete(bot::ratings::delete_reply),
+		)
+		.route(
+			"/bots/:bot_id/ratings/:rating_id/flag",
+			post(bot::ratings::flag),
+		)
+		// Images
+		.route("/images/proxy", get(image_proxy::proxy))
 		// Categories
+		.route("/languages", get(language::index))
 		.route("/categories", get(category::index))
 		.route("/categories/:category_id", get(category::show))
+		.route("/categories/:category_id/bots", get(category::bots))
 		.route("/category_slugs", get(category::slugs))
 		// Tokens
 		.route("/me", get(user::me::me))
+		.route("/me/ratings", get(user::me::ratings))
+		.route(
+			"/me/banner",
+			put(user::me::update_banner)
+				.delete(user::me::delete_banner)
+				.layer(DefaultBodyLimit::max(ImageSpec::BANNER.max_bytes)),
+		)
 		.route("/me/tokens", get(token::list).put(token::new))
 		.route("/me/tokens/:id", get(token::show).delete(token::revoke))
-		.route("/tokens/current", delete(token::revoke_current));
+		.route("/tokens/current", delete(token::revoke_current))
+		// Moderation
+		.route("/admin/audit", get(admin::audit::list))
+		.route(
+			"/admin/blocklist",
+			get(admin::blocklist::list).post(admin::blocklist::create),
+		)
+		.route("/admin/blocklist/:id", delete(admin::blocklist::delete))
+		.route("/admin/queue", get(admin::bots::queue))
+		.route("/admin/bots/:bot_id", get(admin::bots::show))
+		.route(
+			"/admin/bots/:bot_id/status",
+			patch(admin::bots::update_status),
+		)
+		.route("/admin/categories", post(admin::categories::create))
+		.route(
+			"/admin/categories/:slug",
+			patch(admin::categories::update).delete(admin::categories::delete),
+		)
+		.route("/admin/certifications", get(admin::certifications::list))
+		.route(
+			"/admin/certifications/:application_id",
+			patch(admin::certifications::review),
+		)
+		.route("/admin/reports", get(admin::reports::list))
+		.route("/admin/reports/:report_id", patch(admin::reports::update))
+		.route("/admin/ratings/flags", get(admin::ratings::flags))
+		.route("/admin/ratings/:rating_id", get(admin::ratings::show))
+		.route("/admin/ratings/:rating_id/hide", post(admin::ratings::hide))
+		.route(
+			"/admin/ratings/:rating_id/restore",
+			post(admin::ratings::restore),
+		)
+		.route(
+			"/admin/ratings/:rating_id/dismiss",
+			post(admin::ratings::dismiss),
+		);
+
+	// The S3 buckets serve their files themselves.
+	let router = match &state.config.storage.backend {
+		StorageBackend::LocalFileSystem { path } => {
+			router.nest_service(LOCAL_PATH_PREFIX, ServeDir::new(path))
+		}
+		StorageBackend::S3 { .. } => router,
+	};
 
 	router
 		.fallback(|method: Method| async move {
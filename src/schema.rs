@@ -62,6 +62,162 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Representation of the `audit_log` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    audit_log (id) {
+        /// The `id` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int8,
+        /// The `actor_id` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        actor_id -> Varchar,
+        /// The `token_id` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        token_id -> Nullable<Int4>,
+        /// The `action` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        action -> Int4,
+        /// The `bot_id` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_id -> Nullable<Varchar>,
+        /// The `target` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        target -> Varchar,
+        /// The `before` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Nullable<Jsonb>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        before -> Nullable<Jsonb>,
+        /// The `after` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Nullable<Jsonb>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        after -> Nullable<Jsonb>,
+        /// The `ip` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Nullable<Inet>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ip -> Nullable<Inet>,
+        /// The `created_at` column of the `audit_log` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `blocked_traffic` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    blocked_traffic (id) {
+        /// The `id` column of the `blocked_traffic` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `ip_range` column of the `blocked_traffic` table.
+        ///
+        /// Its SQL type is `Nullable<Inet>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ip_range -> Nullable<Inet>,
+        /// The `user_agent` column of the `blocked_traffic` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_agent -> Nullable<Varchar>,
+        /// The `reason` column of the `blocked_traffic` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reason -> Text,
+        /// The `created_by` column of the `blocked_traffic` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Nullable<Varchar>,
+        /// The `created_at` column of the `blocked_traffic` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `expires_at` column of the `blocked_traffic` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamp>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        expires_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `bot_denials` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    bot_denials (id) {
+        /// The `id` column of the `bot_denials` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `bot_id` column of the `bot_denials` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_id -> Varchar,
+        /// The `reviewer_id` column of the `bot_denials` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reviewer_id -> Nullable<Varchar>,
+        /// The `reason` column of the `bot_denials` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reason -> Text,
+        /// The `created_at` column of the `bot_denials` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     /// Representation of the `bot_owners` table.
     ///
@@ -100,6 +256,306 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Representation of the `bot_rating_actions` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    bot_rating_actions (id) {
+        /// The `id` column of the `bot_rating_actions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `rating_id` column of the `bot_rating_actions` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        rating_id -> Nullable<Int4>,
+        /// The `actor_id` column of the `bot_rating_actions` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        actor_id -> Nullable<Varchar>,
+        /// The `action` column of the `bot_rating_actions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        action -> Int4,
+        /// The `reason` column of the `bot_rating_actions` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reason -> Text,
+        /// The `created_at` column of the `bot_rating_actions` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `bot_id` column of the `bot_rating_actions` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_id -> Varchar,
+        /// The `user_id` column of the `bot_rating_actions` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `bot_rating_flags` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    bot_rating_flags (rating_id, user_id) {
+        /// The `rating_id` column of the `bot_rating_flags` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        rating_id -> Int4,
+        /// The `user_id` column of the `bot_rating_flags` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Varchar,
+        /// The `reason` column of the `bot_rating_flags` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reason -> Text,
+        /// The `created_at` column of the `bot_rating_flags` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `resolved_at` column of the `bot_rating_flags` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamp>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        resolved_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `bot_rating_replies` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    bot_rating_replies (rating_id) {
+        /// The `rating_id` column of the `bot_rating_replies` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        rating_id -> Int4,
+        /// The `user_id` column of the `bot_rating_replies` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Varchar,
+        /// The `body` column of the `bot_rating_replies` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        body -> Text,
+        /// The `created_at` column of the `bot_rating_replies` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `bot_rating_replies` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `bot_ratings` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    bot_ratings (id) {
+        /// The `id` column of the `bot_ratings` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `bot_id` column of the `bot_ratings` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_id -> Varchar,
+        /// The `user_id` column of the `bot_ratings` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Varchar,
+        /// The `rating` column of the `bot_ratings` table.
+        ///
+        /// Its SQL type is `Int2`.
+        ///
+        /// (Automatically generated by Diesel.)
+        rating -> Int2,
+        /// The `body` column of the `bot_ratings` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        body -> Text,
+        /// The `created_at` column of the `bot_ratings` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `bot_ratings` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+        /// The `hidden` column of the `bot_ratings` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        hidden -> Bool,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `bot_reports` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    bot_reports (id) {
+        /// The `id` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `bot_id` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_id -> Varchar,
+        /// The `user_id` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Varchar,
+        /// The `reason` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reason -> Int4,
+        /// The `body` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        body -> Text,
+        /// The `status` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        status -> Int4,
+        /// The `handled_by` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        handled_by -> Nullable<Varchar>,
+        /// The `resolution` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        resolution -> Nullable<Text>,
+        /// The `bot_status` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Nullable<Int4>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_status -> Nullable<Int4>,
+        /// The `created_at` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `bot_reports` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `bot_resubmissions` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    bot_resubmissions (id) {
+        /// The `id` column of the `bot_resubmissions` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `bot_id` column of the `bot_resubmissions` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_id -> Varchar,
+        /// The `user_id` column of the `bot_resubmissions` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Varchar,
+        /// The `appeal` column of the `bot_resubmissions` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        appeal -> Nullable<Text>,
+        /// The `created_at` column of the `bot_resubmissions` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     /// Representation of the `bot_reviews` table.
     ///
@@ -132,6 +588,68 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Representation of the `bot_translations` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    bot_translations (bot_id, language) {
+        /// The `bot_id` column of the `bot_translations` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_id -> Varchar,
+        /// The `language` column of the `bot_translations` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        language -> Varchar,
+        /// The `short_description` column of the `bot_translations` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        short_description -> Text,
+        /// The `description` column of the `bot_translations` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description -> Text,
+        /// The `description_html` column of the `bot_translations` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description_html -> Text,
+        /// The `description_html_version` column of the `bot_translations` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description_html_version -> Int4,
+        /// The `created_at` column of the `bot_translations` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `bot_translations` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+        /// The `description_html_proxy` column of the `bot_translations` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description_html_proxy -> Text,
+    }
+}
+
 diesel::table! {
     /// Representation of the `bot_votes` table.
     ///
@@ -271,18 +789,48 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         updated_at -> Timestamp,
-        /// The `supported_languages` column of the `bots` table.
+        /// The `guild_count` column of the `bots` table.
         ///
-        /// Its SQL type is `Array<Nullable<Int4>>`.
+        /// Its SQL type is `Int4`.
         ///
         /// (Automatically generated by Diesel.)
-        supported_languages -> Array<Nullable<Int4>>,
-        /// The `guild_count` column of the `bots` table.
+        guild_count -> Int4,
+        /// The `rating_count` column of the `bots` table.
         ///
         /// Its SQL type is `Int4`.
         ///
         /// (Automatically generated by Diesel.)
-        guild_count -> Int4,
+        rating_count -> Int4,
+        /// The `rating_average` column of the `bots` table.
+        ///
+        /// Its SQL type is `Float4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        rating_average -> Float4,
+        /// The `description_html` column of the `bots` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description_html -> Text,
+        /// The `description_html_version` column of the `bots` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description_html_version -> Int4,
+        /// The `supported_languages` column of the `bots` table.
+        ///
+        /// Its SQL type is `Array<Nullable<Text>>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        supported_languages -> Array<Text>,
+        /// The `description_html_proxy` column of the `bots` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description_html_proxy -> Text,
     }
 }
 
@@ -322,14 +870,14 @@ diesel::table! {
         /// Its SQL type is `Varchar`.
         ///
         /// (Automatically generated by Diesel.)
-        #[max_length = 25]
+        #[max_length = 100]
         category -> Varchar,
         /// The `slug` column of the `categories` table.
         ///
         /// Its SQL type is `Varchar`.
         ///
         /// (Automatically generated by Diesel.)
-        #[max_length = 25]
+        #[max_length = 100]
         slug -> Varchar,
         /// The `description` column of the `categories` table.
         ///
@@ -352,6 +900,244 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    /// Representation of the `certification_applications` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    certification_applications (id) {
+        /// The `id` column of the `certification_applications` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `bot_id` column of the `certification_applications` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_id -> Varchar,
+        /// The `user_id` column of the `certification_applications` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Varchar,
+        /// The `message` column of the `certification_applications` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        message -> Nullable<Text>,
+        /// The `status` column of the `certification_applications` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        status -> Int4,
+        /// The `reviewer_id` column of the `certification_applications` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reviewer_id -> Nullable<Varchar>,
+        /// The `reason` column of the `certification_applications` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reason -> Nullable<Text>,
+        /// The `created_at` column of the `certification_applications` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+        /// The `updated_at` column of the `certification_applications` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `deleted_images` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    deleted_images (id) {
+        /// The `id` column of the `deleted_images` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `key` column of the `deleted_images` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        key -> Text,
+        /// The `extension` column of the `deleted_images` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        extension -> Varchar,
+        /// The `variants` column of the `deleted_images` table.
+        ///
+        /// Its SQL type is `Array<Nullable<Int4>>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        variants -> Array<Int4>,
+        /// The `deleted_at` column of the `deleted_images` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        deleted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `images` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    images (id) {
+        /// The `id` column of the `images` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int4,
+        /// The `kind` column of the `images` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Int4,
+        /// The `bot_id` column of the `images` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        bot_id -> Nullable<Varchar>,
+        /// The `user_id` column of the `images` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Nullable<Varchar>,
+        /// The `key` column of the `images` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        key -> Text,
+        /// The `extension` column of the `images` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        extension -> Varchar,
+        /// The `width` column of the `images` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        width -> Int4,
+        /// The `height` column of the `images` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        height -> Int4,
+        /// The `variants` column of the `images` table.
+        ///
+        /// Its SQL type is `Array<Nullable<Int4>>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        variants -> Array<Int4>,
+        /// The `position` column of the `images` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        position -> Int4,
+        /// The `created_at` column of the `images` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `rate_limit_buckets` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    rate_limit_buckets (action, key) {
+        /// The `action` column of the `rate_limit_buckets` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        action -> Int4,
+        /// The `key` column of the `rate_limit_buckets` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        key -> Varchar,
+        /// The `tokens` column of the `rate_limit_buckets` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        tokens -> Int4,
+        /// The `last_refill` column of the `rate_limit_buckets` table.
+        ///
+        /// Its SQL type is `Timestamp`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_refill -> Timestamp,
+    }
+}
+
+diesel::table! {
+    /// Representation of the `rate_limit_overrides` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    rate_limit_overrides (action, user_id) {
+        /// The `action` column of the `rate_limit_overrides` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        action -> Int4,
+        /// The `user_id` column of the `rate_limit_overrides` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Varchar,
+        /// The `burst` column of the `rate_limit_overrides` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        burst -> Int4,
+        /// The `expires_at` column of the `rate_limit_overrides` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamp>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        expires_at -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     /// Representation of the `users` table.
     ///
@@ -405,25 +1191,77 @@ diesel::table! {
         ///
         /// (Automatically generated by Diesel.)
         dc_access_token -> Varchar,
+        /// The `role` column of the `users` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        role -> Int4,
+        /// The `banned_at` column of the `users` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamp>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        banned_at -> Nullable<Timestamp>,
+        /// The `ban_reason` column of the `users` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ban_reason -> Nullable<Text>,
     }
 }
 
 diesel::joinable!(api_tokens -> users (user_id));
+diesel::joinable!(blocked_traffic -> users (created_by));
+diesel::joinable!(bot_denials -> bots (bot_id));
+diesel::joinable!(bot_denials -> users (reviewer_id));
 diesel::joinable!(bot_owners -> bots (bot_id));
 diesel::joinable!(bot_owners -> users (user_id));
+diesel::joinable!(bot_rating_actions -> bot_ratings (rating_id));
+diesel::joinable!(bot_rating_actions -> users (actor_id));
+diesel::joinable!(bot_rating_flags -> bot_ratings (rating_id));
+diesel::joinable!(bot_rating_flags -> users (user_id));
+diesel::joinable!(bot_rating_replies -> bot_ratings (rating_id));
+diesel::joinable!(bot_rating_replies -> users (user_id));
+diesel::joinable!(bot_ratings -> bots (bot_id));
+diesel::joinable!(bot_ratings -> users (user_id));
+diesel::joinable!(bot_reports -> bots (bot_id));
+diesel::joinable!(bot_resubmissions -> bots (bot_id));
+diesel::joinable!(bot_resubmissions -> users (user_id));
 diesel::joinable!(bot_reviews -> bots (bot_id));
 diesel::joinable!(bot_reviews -> users (user_id));
+diesel::joinable!(bot_translations -> bots (bot_id));
 diesel::joinable!(bot_votes -> bots (bot_id));
 diesel::joinable!(bots_categories -> bots (bot_id));
 diesel::joinable!(bots_categories -> categories (category_id));
+diesel::joinable!(certification_applications -> bots (bot_id));
+diesel::joinable!(images -> bots (bot_id));
+diesel::joinable!(images -> users (user_id));
+diesel::joinable!(rate_limit_overrides -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     api_tokens,
+    audit_log,
+    blocked_traffic,
+    bot_denials,
     bot_owners,
+    bot_rating_actions,
+    bot_rating_flags,
+    bot_rating_replies,
+    bot_ratings,
+    bot_reports,
+    bot_resubmissions,
     bot_reviews,
+    bot_translations,
     bot_votes,
     bots,
     bots_categories,
     categories,
+    certification_applications,
+    deleted_images,
+    images,
+    rate_limit_buckets,
+    rate_limit_overrides,
     users,
 );
@@ -14,6 +14,7 @@ define_sql_function!(fn floor(x: Double) -> Integer);
 define_sql_function!(fn greatest<T: SingleValue>(x: T, y: T) -> T);
 define_sql_function!(fn least<T: SingleValue>(x: T, y: T) -> T);
 define_sql_function!(fn split_part(string: Text, delimiter: Text, n: Integer) -> Text);
+define_sql_function!(fn subtree_bots_cnt(slug: Text) -> Integer);
 
 macro_rules! pg_enum {
     (
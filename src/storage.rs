@@ -0,0 +1,182 @@
+//! Storage of the uploaded files, either on the local filesystem or in an
+//! S3-compatible bucket (AWS S3, MinIO, Cloudflare R2…).
+//!
+//! The S3 backend is used when `S3_BUCKET` is set, with:
+//!
+//! - `S3_REGION`, defaulting to `us-east-1`.
+//! - `S3_ENDPOINT`, the URL of the server when it's not AWS, e.g.
+//!   `http://localhost:9000` for MinIO.
+//! - `S3_ACCESS_KEY` and `S3_SECRET_KEY`.
+//!
+//! Otherwise the files are written to `STORAGE_PATH`, `local_uploads` by
+//! default, and served by the API under `/uploads`.
+//!
+//! `STORAGE_PUBLIC_URL` is the base URL the files are served from, e.g. the
+//! URL of a CDN in front of the bucket.
+
+use anyhow::Context;
+use crates_io_env_vars::{required_var, var};
+use futures_util::StreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::{Attribute, Attributes, ObjectStore, PutOptions, PutPayload};
+use secrecy::{ExposeSecret, SecretString};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The path the files of the local backend are served under.
+pub const LOCAL_PATH_PREFIX: &str = "/uploads";
+
+/// The files never change once uploaded, replacing a file uploads it under a
+/// new key.
+const CACHE_CONTROL: &str = "public,max-age=31536000,immutable";
+
+pub enum StorageBackend {
+	LocalFileSystem {
+		path: PathBuf,
+	},
+	S3 {
+		bucket: String,
+		region: String,
+		endpoint: Option<String>,
+		access_key: String,
+		secret_key: SecretString,
+	},
+}
+
+pub struct StorageConfig {
+	pub backend: StorageBackend,
+	/// Base URL of the stored files, without a trailing slash.
+	pub public_url: String,
+}
+
+impl StorageConfig {
+	pub fn from_environment(port: u16) -> anyhow::Result<Self> {
+		let public_url = var("STORAGE_PUBLIC_URL")?;
+
+		let Some(bucket) = var("S3_BUCKET")? else {
+			let path = var("STORAGE_PATH")?.unwrap_or_else(|| "local_uploads".into());
+
+			return Ok(Self {
+				backend: StorageBackend::LocalFileSystem { path: path.into() },
+				public_url: public_url
+					.unwrap_or_else(|| format!("http://localhost:{port}{LOCAL_PATH_PREFIX}")),
+			});
+		};
+
+		let region = var("S3_REGION")?.unwrap_or_else(|| "us-east-1".into());
+		let endpoint = var("S3_ENDPOINT")?;
+
+		let public_url = public_url.unwrap_or_else(|| match &endpoint {
+			Some(endpoint) => format!("{}/{bucket}", endpoint.trim_end_matches('/')),
+			None => format!("https://{bucket}.s3.{region}.amazonaws.com"),
+		});
+
+		Ok(Self {
+			backend: StorageBackend::S3 {
+				bucket,
+				region,
+				endpoint,
+				access_key: required_var("S3_ACCESS_KEY")?,
+				secret_key: required_var("S3_SECRET_KEY")?.into(),
+			},
+			public_url,
+		})
+	}
+}
+
+pub struct Storage {
+	store: Arc<dyn ObjectStore>,
+	/// Whether the backend keeps the HTTP headers of the files, which the
+	/// local filesystem doesn't.
+	supports_attributes: bool,
+	public_url: String,
+}
+
+impl Storage {
+	pub fn from_config(config: &StorageConfig) -> anyhow::Result<Self> {
+		let (store, supports_attributes): (Arc<dyn ObjectStore>, _) = match &config.backend {
+			StorageBackend::LocalFileSystem { path } => {
+				std::fs::create_dir_all(path)
+					.with_context(|| format!("failed to create `{}`", path.display()))?;
+
+				(Arc::new(LocalFileSystem::new_with_prefix(path)?), false)
+			}
+			StorageBackend::S3 {
+				bucket,
+				region,
+				endpoint,
+				access_key,
+				secret_key,
+			} => {
+				let mut builder = AmazonS3Builder::new()
+					.with_bucket_name(bucket)
+					.with_region(region)
+					.with_access_key_id(access_key)
+					.with_secret_access_key(secret_key.expose_secret());
+
+				if let Some(endpoint) = endpoint {
+					// MinIO and most of the S3-compatible servers only support
+					// path-style requests.
+					builder = builder
+						.with_endpoint(endpoint)
+						.with_allow_http(endpoint.starts_with("http://"))
+						.with_virtual_hosted_style_request(false);
+				}
+
+				(Arc::new(builder.build()?), true)
+			}
+		};
+
+		Ok(Self {
+			store,
+			supports_attributes,
+			public_url: config.public_url.trim_end_matches('/').into(),
+		})
+	}
+
+	/// The public URL of the file.
+	pub fn url(&self, key: &str) -> String {
+		format!("{}/{key}", self.public_url)
+	}
+
+	pub async fn upload(
+		&self,
+		key: &str,
+		content: impl Into<PutPayload>,
+		content_type: &'static str,
+	) -> object_store::Result<()> {
+		let mut attributes = Attributes::new();
+		if self.supports_attributes {
+			attributes.insert(Attribute::ContentType, content_type.into());
+			attributes.insert(Attribute::CacheControl, CACHE_CONTROL.into());
+		}
+
+		let options = PutOptions {
+			attributes,
+			..Default::default()
+		};
+
+		self.store
+			.put_opts(&Path::from(key), content.into(), options)
+			.await?;
+
+		Ok(())
+	}
+
+	/// Deletes the files, ignoring the ones that don't exist.
+	pub async fn delete(&self, keys: Vec<String>) -> object_store::Result<()> {
+		let locations = futures_util::stream::iter(keys.into_iter().map(|key| Ok(Path::from(key))));
+
+		let mut results = self.store.delete_stream(locations.boxed());
+		while let Some(result) = results.next().await {
+			match result {
+				Ok(_) | Err(object_store::Error::NotFound { .. }) => {}
+				Err(error) => return Err(error),
+			}
+		}
+
+		Ok(())
+	}
+}
@@ -1,3 +1,4 @@
+use crate::middleware::request_id::RequestId;
 use sentry::Hub;
 use std::convert::identity;
 use tokio::task::JoinError;
@@ -5,7 +6,8 @@ use tokio::task::JoinError;
 /// Runs the provided closure on a thread where blocking is acceptable.
 ///
 /// This is using [tokio::task::spawn_blocking] internally, but automatically
-/// runs the callback function in the context of the current Sentry [Hub].
+/// runs the callback function in the context of the current Sentry [Hub]
+/// and of the current [RequestId].
 ///
 /// The function also returns a flattened [Result], which requires the error
 /// variant of the [Result] to implement [From\<JoinError>].
@@ -17,12 +19,15 @@ where
 {
 	let current_span = tracing::Span::current();
 	let hub = Hub::current();
-	tokio::task::spawn_blocking(move || current_span.in_scope(|| Hub::run(hub, f)))
-		.await
-		// Convert `JoinError` to `E`
-		.map_err(Into::into)
-		// Flatten `Result<Result<_, E>, E>` to `Result<_, E>`
-		.and_then(identity)
+	let request_id = RequestId::current();
+	tokio::task::spawn_blocking(move || {
+		current_span.in_scope(|| Hub::run(hub, || RequestId::sync_scope(request_id, f)))
+	})
+	.await
+	// Convert `JoinError` to `E`
+	.map_err(Into::into)
+	// Flatten `Result<Result<_, E>, E>` to `Result<_, E>`
+	.and_then(identity)
 }
 
 #[cfg(test)]
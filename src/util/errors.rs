@@ -1,3 +1,26 @@
+//! Errors returned by the API.
+//!
+//! All the error responses share the same JSON envelope:
+//!
+//! ```json
+//! {
+//!     "errors": [
+//!         {
+//!             "code": "too_long",
+//!             "detail": "body must be at most 2000 characters long",
+//!             "source": { "pointer": "/body" }
+//!         }
+//!     ],
+//!     "request_id": "4d5e6f708192a3b4c5d6e7f8091a2b3c"
+//! }
+//! ```
+//!
+//! - `code` is one of the stable [`ErrorCode`]s, for clients to match on.
+//! - `detail` is a human-readable description, which may change at any time.
+//! - `source.pointer` is a JSON pointer to the invalid field of the request
+//!   body, only present for field-level validation errors.
+//! - `request_id` is the id of the request, to include in bug reports.
+
 use axum::response::IntoResponse;
 use oauth2::ErrorResponse;
 use std::any::{Any, TypeId};
@@ -5,6 +28,7 @@ use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 
+mod code;
 mod json;
 
 use axum::Extension;
@@ -13,14 +37,21 @@ use reqwest::StatusCode;
 use tokio::task::JoinError;
 use tracing::error;
 
+use crate::image_proxy::ProxyError;
+use crate::images::ImageError;
 use crate::middleware::log_request::ErrorField;
 
-pub use json::TOKEN_FORMAT_ERROR;
-pub(crate) use json::{custom, InsecurelyGeneratedTokenRevoked, ReadOnlyMode};
+pub use code::ErrorCode;
+pub use json::{
+	api_error, invalid_field, invalid_fields, ErrorObject, ErrorSource, TOKEN_FORMAT_ERROR,
+};
+pub(crate) use json::{custom, InsecurelyGeneratedTokenRevoked, ReadOnlyMode, TooManyRequests};
 
 pub type BoxedAppError = Box<dyn AppError>;
 
 /// Return an error with status 400 and the provided description as JSON
+///
+/// Prefer [`api_error`] or [`invalid_field`] with a specific [`ErrorCode`].
 pub fn bad_request<S: ToString>(error: S) -> BoxedAppError {
 	custom(StatusCode::BAD_REQUEST, error.to_string())
 }
@@ -45,7 +76,7 @@ pub fn service_unavailable() -> BoxedAppError {
 
 pub fn bot_not_found(bot: &str) -> BoxedAppError {
 	let detail = format!("bot `{bot}` does not exist");
-	custom(StatusCode::NOT_FOUND, detail)
+	api_error(ErrorCode::BotNotFound, detail)
 }
 
 // =============================================================================
@@ -106,6 +137,12 @@ impl From<diesel::ConnectionError> for BoxedAppError {
 	}
 }
 
+impl From<prometheus::Error> for BoxedAppError {
+	fn from(err: prometheus::Error) -> BoxedAppError {
+		Box::new(err)
+	}
+}
+
 impl From<DieselError> for BoxedAppError {
 	fn from(err: DieselError) -> BoxedAppError {
 		match err {
@@ -180,6 +217,37 @@ impl From<JoinError> for BoxedAppError {
 	}
 }
 
+impl From<object_store::Error> for BoxedAppError {
+	fn from(err: object_store::Error) -> BoxedAppError {
+		Box::new(err)
+	}
+}
+
+impl From<ImageError> for BoxedAppError {
+	fn from(err: ImageError) -> BoxedAppError {
+		let code = match err {
+			ImageError::UnsupportedFormat | ImageError::Invalid(_) => ErrorCode::InvalidImage,
+			ImageError::TooLarge(_) => ErrorCode::ImageTooLarge,
+			ImageError::InvalidDimensions { .. } => ErrorCode::InvalidImageDimensions,
+		};
+
+		api_error(code, err.to_string())
+	}
+}
+
+impl From<ProxyError> for BoxedAppError {
+	fn from(err: ProxyError) -> BoxedAppError {
+		match err {
+			ProxyError::InvalidUrl | ProxyError::HostNotAllowed(_) => {
+				api_error(ErrorCode::InvalidUrl, err.to_string())
+			}
+			ProxyError::Upstream(StatusCode::NOT_FOUND | StatusCode::GONE) => not_found(),
+			ProxyError::Io(err) => Box::new(err),
+			_ => api_error(ErrorCode::ImageUnavailable, err.to_string()),
+		}
+	}
+}
+
 // =============================================================================
 // Internal error for use with `chain_error`
 
@@ -212,10 +280,6 @@ pub fn internal<S: ToString>(error: S) -> BoxedAppError {
 }
 
 fn server_error_response(error: String) -> axum::response::Response {
-	(
-		StatusCode::INTERNAL_SERVER_ERROR,
-		Extension(ErrorField(error)),
-		"Internal Server Error",
-	)
-		.into_response()
+	let response = api_error(ErrorCode::InternalError, "Internal Server Error").response();
+	(Extension(ErrorField(error)), response).into_response()
 }
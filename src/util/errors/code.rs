@@ -0,0 +1,159 @@
+use axum::http::StatusCode;
+
+/// The stable, machine-readable codes of the errors returned by the API.
+///
+/// The codes are part of the public API: clients match on them instead of the
+/// `detail` text, so existing codes must never be renamed or removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+	// Generic codes, used when no more specific code applies
+	BadRequest,
+	Unauthorized,
+	Forbidden,
+	NotFound,
+	TooManyRequests,
+	InternalError,
+	ServiceUnavailable,
+	ReadOnlyMode,
+
+	// Invalid fields of the request, together with a JSON pointer to the field
+	MissingValue,
+	TooLong,
+	OutOfRange,
+	InvalidValue,
+	InvalidFormat,
+	InvalidUrl,
+	InvalidInvite,
+	UnsafeContent,
+
+	// Authentication and permissions
+	AuthenticationRequired,
+	AuthenticationFailed,
+	AccountBanned,
+	InvalidOrigin,
+	RequestBlocked,
+	InvalidToken,
+	WebsiteOnly,
+	ScopeMismatch,
+	StaffOnly,
+	NotBotOwner,
+	InvalidCsrfState,
+	MissingCodeVerifier,
+
+	// API tokens
+	ApiTokenNotAllowed,
+	TokenLimitReached,
+	TokenNotProvided,
+
+	// Bots
+	BotNotFound,
+	BotAlreadyExists,
+	BotDenied,
+	BotNotDenied,
+	BotNotApproved,
+	DiscordUnavailable,
+
+	// Categories
+	InvalidCategory,
+	InvalidCategoryCount,
+	CategoryAlreadyExists,
+	CategoryHasSubcategories,
+	ParentCategoryNotFound,
+
+	// Ratings, reports and certification
+	OwnBotRating,
+	OwnBotReport,
+	OwnRatingFlag,
+	AlreadyReported,
+	NothingToUpdate,
+	AlreadyCertified,
+	CertificationRequirementsNotMet,
+	ApplicationPending,
+	ApplicationAlreadyReviewed,
+
+	// Images
+	InvalidImage,
+	ImageTooLarge,
+	InvalidImageDimensions,
+	TooManyScreenshots,
+	ImageUnavailable,
+
+	// Pagination
+	InvalidPagination,
+}
+
+impl ErrorCode {
+	/// The HTTP status of the errors with this code.
+	pub fn status(self) -> StatusCode {
+		match self {
+			Self::Unauthorized | Self::InvalidToken => StatusCode::UNAUTHORIZED,
+			Self::Forbidden
+			| Self::AuthenticationRequired
+			| Self::AuthenticationFailed
+			| Self::AccountBanned
+			| Self::InvalidOrigin
+			| Self::RequestBlocked
+			| Self::WebsiteOnly
+			| Self::ScopeMismatch
+			| Self::StaffOnly
+			| Self::NotBotOwner
+			| Self::OwnBotRating => StatusCode::FORBIDDEN,
+			Self::NotFound | Self::BotNotFound => StatusCode::NOT_FOUND,
+			Self::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+			Self::ImageTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+			Self::ImageUnavailable => StatusCode::BAD_GATEWAY,
+			Self::InternalError | Self::DiscordUnavailable => StatusCode::INTERNAL_SERVER_ERROR,
+			Self::ServiceUnavailable | Self::ReadOnlyMode => StatusCode::SERVICE_UNAVAILABLE,
+			_ => StatusCode::BAD_REQUEST,
+		}
+	}
+
+	/// The generic code of the errors created from a bare status.
+	pub fn from_status(status: StatusCode) -> Self {
+		match status {
+			StatusCode::UNAUTHORIZED => Self::Unauthorized,
+			StatusCode::FORBIDDEN => Self::Forbidden,
+			StatusCode::NOT_FOUND => Self::NotFound,
+			StatusCode::TOO_MANY_REQUESTS => Self::TooManyRequests,
+			StatusCode::SERVICE_UNAVAILABLE => Self::ServiceUnavailable,
+			status if status.is_server_error() => Self::InternalError,
+			_ => Self::BadRequest,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn codes_are_snake_case() {
+		let codes = serde_json::to_value([
+			ErrorCode::BotAlreadyExists,
+			ErrorCode::InvalidCategory,
+			ErrorCode::ScopeMismatch,
+		])
+		.unwrap();
+
+		assert_eq!(
+			codes,
+			json!(["bot_already_exists", "invalid_category", "scope_mismatch"])
+		);
+	}
+
+	#[test]
+	fn generic_codes_match_their_status() {
+		for status in [
+			StatusCode::BAD_REQUEST,
+			StatusCode::UNAUTHORIZED,
+			StatusCode::FORBIDDEN,
+			StatusCode::NOT_FOUND,
+			StatusCode::TOO_MANY_REQUESTS,
+			StatusCode::INTERNAL_SERVER_ERROR,
+			StatusCode::SERVICE_UNAVAILABLE,
+		] {
+			assert_eq!(ErrorCode::from_status(status).status(), status);
+		}
+	}
+}
@@ -6,15 +6,47 @@ use std::borrow::Cow;
 use std::fmt;
 
 use crate::middleware::log_request::CauseField;
+use crate::middleware::request_id::RequestId;
+use crate::rate_limiter::LimitedAction;
+use axum::http::{header, HeaderValue};
+use chrono::NaiveDateTime;
+
+use super::{AppError, BoxedAppError, ErrorCode};
+
+/// An error of the `errors` array of the error responses.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorObject {
+	pub code: ErrorCode,
+	pub detail: Cow<'static, str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source: Option<ErrorSource>,
+}
 
-use super::{AppError, BoxedAppError};
+/// The part of the request that caused the error.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorSource {
+	/// A JSON pointer to the invalid field of the request body, e.g. `/categories/0`.
+	pub pointer: Cow<'static, str>,
+}
 
-/// Generates a response with the provided status and description as JSON
-fn json_error(detail: &str, status: StatusCode) -> Response {
-	let json = json!({ "errors": [{ "detail": detail }] });
+/// Generates a response with the provided status and errors as JSON,
+/// together with the id of the current request
+fn json_errors(errors: &[ErrorObject], status: StatusCode) -> Response {
+	let json = json!({ "errors": errors, "request_id": RequestId::current() });
 	(status, Json(json)).into_response()
 }
 
+/// Generates a response with a single error, using the status of its code
+fn json_error(code: ErrorCode, detail: &str) -> Response {
+	let error = ErrorObject {
+		code,
+		detail: detail.to_string().into(),
+		source: None,
+	};
+
+	json_errors(&[error], code.status())
+}
+
 // The following structs are empty and do not provide a custom message to the user
 
 #[derive(Debug)]
@@ -24,7 +56,7 @@ impl AppError for ReadOnlyMode {
 	fn response(&self) -> Response {
 		let detail = "dbots.fun is currently in read-only mode for maintenance. \
                       Please try again later.";
-		json_error(detail, StatusCode::SERVICE_UNAVAILABLE)
+		json_error(ErrorCode::ReadOnlyMode, detail)
 	}
 }
 
@@ -39,25 +71,78 @@ impl fmt::Display for ReadOnlyMode {
 pub fn custom(status: StatusCode, detail: impl Into<Cow<'static, str>>) -> BoxedAppError {
 	Box::new(CustomApiError {
 		status,
-		detail: detail.into(),
+		error: ErrorObject {
+			code: ErrorCode::from_status(status),
+			detail: detail.into(),
+			source: None,
+		},
+	})
+}
+
+pub fn api_error(code: ErrorCode, detail: impl Into<Cow<'static, str>>) -> BoxedAppError {
+	Box::new(CustomApiError {
+		status: code.status(),
+		error: ErrorObject {
+			code,
+			detail: detail.into(),
+			source: None,
+		},
 	})
 }
 
+pub fn invalid_field(
+	pointer: impl Into<Cow<'static, str>>,
+	code: ErrorCode,
+	detail: impl Into<Cow<'static, str>>,
+) -> BoxedAppError {
+	Box::new(CustomApiError {
+		status: code.status(),
+		error: ErrorObject {
+			code,
+			detail: detail.into(),
+			source: Some(ErrorSource {
+				pointer: pointer.into(),
+			}),
+		},
+	})
+}
+
+/// Returns an error with status 400 listing all the invalid fields of the request
+pub fn invalid_fields(errors: Vec<ErrorObject>) -> BoxedAppError {
+	Box::new(InvalidFields(errors))
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidFields(Vec<ErrorObject>);
+
+impl fmt::Display for InvalidFields {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let details = self.0.iter().map(|error| error.detail.as_ref());
+		details.collect::<Vec<_>>().join("; ").fmt(f)
+	}
+}
+
+impl AppError for InvalidFields {
+	fn response(&self) -> Response {
+		json_errors(&self.0, StatusCode::BAD_REQUEST)
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct CustomApiError {
 	status: StatusCode,
-	detail: Cow<'static, str>,
+	error: ErrorObject,
 }
 
 impl fmt::Display for CustomApiError {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		self.detail.fmt(f)
+		self.error.detail.fmt(f)
 	}
 }
 
 impl AppError for CustomApiError {
 	fn response(&self) -> Response {
-		json_error(&self.detail, self.status)
+		json_errors(std::slice::from_ref(&self.error), self.status)
 	}
 }
 
@@ -73,7 +158,7 @@ impl InsecurelyGeneratedTokenRevoked {
 impl AppError for InsecurelyGeneratedTokenRevoked {
 	fn response(&self) -> Response {
 		let cause = CauseField("insecurely generated, revoked 2020-07".to_string());
-		let response = json_error(&self.to_string(), StatusCode::UNAUTHORIZED);
+		let response = json_error(ErrorCode::InvalidToken, &self.to_string());
 		(Extension(cause), response).into_response()
 	}
 }
@@ -87,3 +172,34 @@ impl fmt::Display for InsecurelyGeneratedTokenRevoked {
 		Result::Ok(())
 	}
 }
+
+#[derive(Debug)]
+pub(crate) struct TooManyRequests {
+	pub action: LimitedAction,
+	pub retry_after: NaiveDateTime,
+}
+
+impl AppError for TooManyRequests {
+	fn response(&self) -> Response {
+		const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+		let retry_after = self.retry_after.format(HTTP_DATE_FORMAT);
+
+		let detail = format!(
+			"{} Please try again after {retry_after}.",
+			self.action.error_message()
+		);
+		let mut response = json_error(ErrorCode::TooManyRequests, &detail);
+		response.headers_mut().insert(
+			header::RETRY_AFTER,
+			HeaderValue::from_str(&retry_after.to_string())
+				.expect("HTTP_DATE_FORMAT contains invalid char"),
+		);
+		response
+	}
+}
+
+impl fmt::Display for TooManyRequests {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		"Too many requests".fmt(f)
+	}
+}
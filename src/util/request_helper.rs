@@ -30,6 +30,9 @@ pub trait RequestUtils {
 	fn query(&self) -> IndexMap<String, String>;
 	fn wants_json(&self) -> bool;
 	fn query_with_params(&self, params: IndexMap<String, String>) -> String;
+	/// The language ranges of the `Accept-Language` header, most preferred
+	/// first, without `*` and the ranges with `q=0`.
+	fn accepted_languages(&self) -> Vec<String>;
 }
 
 impl<T: RequestPartsExt> RequestUtils for T {
@@ -54,4 +57,44 @@ impl<T: RequestPartsExt> RequestUtils for T {
 			.finish();
 		format!("?{query_string}")
 	}
+
+	fn accepted_languages(&self) -> Vec<String> {
+		let header = self.headers().get_str_or_default(header::ACCEPT_LANGUAGE);
+		parse_accept_language(header)
+	}
+}
+
+fn parse_accept_language(header: &str) -> Vec<String> {
+	let mut ranges = header
+		.split(',')
+		.filter_map(|range| {
+			let mut parts = range.split(';').map(str::trim);
+			let language = parts.next().filter(|l| !l.is_empty() && *l != "*")?;
+
+			let quality = parts
+				.find_map(|param| param.strip_prefix("q="))
+				.map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+
+			(quality > 0.0).then(|| (language.to_ascii_lowercase(), quality))
+		})
+		.collect::<Vec<_>>();
+
+	// The sort is stable, so ranges with the same quality keep their order.
+	ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+	ranges.into_iter().map(|(language, _)| language).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn accept_language() {
+		assert_eq!(
+			parse_accept_language("de-CH, en;q=0.8, fr;q=0.9, *;q=0.5, ja;q=0"),
+			["de-ch", "fr", "en"]
+		);
+		assert_eq!(parse_accept_language("es;q=nope, pt"), ["pt"]);
+		assert!(parse_accept_language("").is_empty());
+	}
 }
@@ -1,11 +1,27 @@
+use axum::http::HeaderMap;
 use crates_io_env_vars::var;
+use opentelemetry::global;
+use opentelemetry::propagation::Extractor;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
 use sentry::integrations::tracing::EventFilter;
+use std::collections::HashMap;
+use std::sync::OnceLock;
 use tracing::level_filters::LevelFilter;
-use tracing::{warn, Level, Metadata};
+use tracing::{warn, Level, Metadata, Span, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, Layer};
 
+/// The service name reported to the collector, unless `OTEL_SERVICE_NAME` is set.
+const DEFAULT_SERVICE_NAME: &str = "izumo";
+
+static TRACER_PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
 pub fn init() {
 	initialize_tracing(LevelFilter::INFO);
 }
@@ -38,10 +54,17 @@ fn initialize_tracing(level: LevelFilter) {
 		.event_filter(event_filter)
 		.with_filter(LevelFilter::INFO);
 
+	let otel_layer = otel_layer().map(|layer| layer.with_filter(LevelFilter::INFO));
+
 	tracing_subscriber::registry()
 		.with(log_layer)
 		.with(sentry_layer)
+		.with(otel_layer)
 		.init();
+
+	if is_otel_enabled() {
+		crate::db::instrument_queries();
+	}
 }
 
 fn event_filter(metadata: &Metadata<'_>) -> EventFilter {
@@ -52,3 +75,135 @@ fn event_filter(metadata: &Metadata<'_>) -> EventFilter {
 		&Level::DEBUG | &Level::TRACE => EventFilter::Ignore,
 	}
 }
+
+/// Builds the layer exporting the spans to an OpenTelemetry collector.
+///
+/// The export is only enabled when `OTEL_EXPORTER_OTLP_ENDPOINT` (or
+/// `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`) is set. The exporter speaks OTLP over
+/// HTTP and also reads the other standard variables, like
+/// `OTEL_EXPORTER_OTLP_HEADERS`, `OTEL_TRACES_SAMPLER` and `OTEL_TRACES_SAMPLER_ARG`.
+fn otel_layer<S>() -> Option<impl Layer<S>>
+where
+	S: Subscriber + for<'span> LookupSpan<'span>,
+{
+	let endpoint = var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")
+		.ok()
+		.flatten()
+		.or_else(|| var("OTEL_EXPORTER_OTLP_ENDPOINT").ok().flatten())?;
+
+	let exporter = opentelemetry_otlp::SpanExporter::builder()
+		.with_http()
+		.build()
+		.inspect_err(|error| {
+			eprintln!("Failed to create the OTLP exporter for {endpoint}, traces are not exported: {error}")
+		})
+		.ok()?;
+
+	let service_name = var("OTEL_SERVICE_NAME")
+		.ok()
+		.flatten()
+		.unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+
+	let resource = Resource::builder().with_service_name(service_name).build();
+
+	let provider = SdkTracerProvider::builder()
+		.with_batch_exporter(exporter)
+		.with_resource(resource)
+		.build();
+
+	let tracer = provider.tracer(DEFAULT_SERVICE_NAME);
+	let provider = TRACER_PROVIDER.get_or_init(|| provider);
+
+	global::set_tracer_provider(provider.clone());
+	global::set_text_map_propagator(TraceContextPropagator::new());
+
+	Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Whether the spans are exported to an OpenTelemetry collector.
+pub fn is_otel_enabled() -> bool {
+	TRACER_PROVIDER.get().is_some()
+}
+
+/// Exports the spans that are still buffered, to be called before exiting.
+pub fn shutdown() {
+	if let Some(provider) = TRACER_PROVIDER.get() {
+		if let Err(error) = provider.shutdown() {
+			warn!(%error, "Failed to export the remaining spans");
+		}
+	}
+}
+
+/// Makes `span` a child of the W3C `traceparent` of an incoming request.
+pub fn set_parent_from_headers(span: &Span, headers: &HeaderMap) {
+	let context =
+		global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+
+	span.set_parent(context);
+}
+
+/// The W3C `traceparent` and `tracestate` headers of the current span, to
+/// continue the trace in the service called by an outgoing request.
+///
+/// Empty when the spans are not exported.
+pub fn trace_context_headers() -> HashMap<String, String> {
+	let context = Span::current().context();
+
+	let mut headers = HashMap::new();
+	global::get_text_map_propagator(|propagator| propagator.inject_context(&context, &mut headers));
+	headers
+}
+
+/// Propagates the trace context of the current span to an outgoing HTTP call.
+pub trait PropagateTraceContext {
+	fn propagate_trace_context(self) -> Self;
+}
+
+impl PropagateTraceContext for reqwest::blocking::RequestBuilder {
+	fn propagate_trace_context(self) -> Self {
+		trace_context_headers()
+			.into_iter()
+			.fold(self, |builder, (name, value)| builder.header(name, value))
+	}
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+	fn get(&self, key: &str) -> Option<&str> {
+		self.0.get(key).and_then(|value| value.to_str().ok())
+	}
+
+	fn keys(&self) -> Vec<&str> {
+		self.0.keys().map(|name| name.as_str()).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use opentelemetry::propagation::TextMapPropagator;
+	use opentelemetry::trace::TraceContextExt;
+	use opentelemetry::Context;
+
+	#[test]
+	fn extracts_traceparent() {
+		let mut headers = HeaderMap::new();
+		headers.insert(
+			"traceparent",
+			"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+				.parse()
+				.unwrap(),
+		);
+
+		let context: Context = TraceContextPropagator::new().extract(&HeaderExtractor(&headers));
+
+		let span_context = context.span().span_context().clone();
+		assert!(span_context.is_remote());
+		assert_eq!(
+			span_context.trace_id().to_string(),
+			"4bf92f3577b34da6a3ce929d0e0e4736"
+		);
+		assert_eq!(span_context.span_id().to_string(), "00f067aa0ba902b7");
+	}
+}
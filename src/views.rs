@@ -1,7 +1,15 @@
-use crate::models::bot::BotLanguages;
+use crate::discord::cdn::CdnUrl;
+use crate::discord::invite::BotInvite;
+use crate::models::audit::AuditEntry;
+use crate::models::blocked_traffic::BlockedTraffic;
+use crate::models::certification::{
+	CertificationApplication, CertificationEligibility, CertificationRequirement,
+};
+use crate::models::image::StoredImage;
 use crate::models::token::{ApiToken, CreatedApiToken};
-use crate::models::{Bot, User};
-use crate::models::{BotVote, Category};
+use crate::models::{Bot, BotDenial, BotReport, BotResubmission, BotTranslation, Image, User};
+use crate::models::{BotRating, BotRatingAction, BotRatingFlag, BotRatingReply, BotVote, Category};
+use crate::storage::Storage;
 use crate::util::rfc3339;
 use chrono::NaiveDateTime;
 use secrecy::ExposeSecret;
@@ -39,6 +47,13 @@ impl From<Category> for EncodableCategory {
 	}
 }
 
+#[derive(Serialize, Debug)]
+pub struct EncodableLanguage {
+	pub code: &'static str,
+	pub name: &'static str,
+	pub bots_cnt: i64,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EncodableCategoryWithSubcategories {
 	pub id: String,
@@ -48,6 +63,8 @@ pub struct EncodableCategoryWithSubcategories {
 	#[serde(with = "rfc3339")]
 	pub created_at: NaiveDateTime,
 	pub bots_cnt: i32,
+	pub subcategories: Vec<EncodableCategory>,
+	pub parent_categories: Vec<EncodableCategory>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -60,19 +77,21 @@ pub struct EncodableMe {
 pub struct EncodablePrivateUser {
 	pub id: String,
 	pub username: String,
-	pub avatar: Option<String>,
+	pub avatar: String,
 	pub banner: Option<String>,
 	pub bio: Option<String>,
+	pub role: String,
 }
 
 impl EncodablePrivateUser {
-	pub fn from(user: User) -> Self {
+	pub fn from(user: User, storage: &Storage) -> Self {
 		let User {
 			id,
 			username,
 			avatar,
 			banner,
 			bio,
+			role,
 			..
 		} = user;
 
@@ -80,8 +99,9 @@ impl EncodablePrivateUser {
 			id: id.clone(),
 			username,
 			avatar: avatar_url(&id, avatar),
-			banner,
+			banner: banner_url(banner, storage),
 			bio,
+			role: role.into(),
 		}
 	}
 }
@@ -90,13 +110,13 @@ impl EncodablePrivateUser {
 pub struct EncodablePublicUser {
 	pub id: String,
 	pub username: String,
-	pub avatar: Option<String>,
+	pub avatar: String,
 	pub banner: Option<String>,
 	pub bio: Option<String>,
 }
 
-impl From<User> for EncodablePublicUser {
-	fn from(user: User) -> Self {
+impl EncodablePublicUser {
+	pub fn from(user: User, storage: &Storage) -> Self {
 		let User {
 			id,
 			username,
@@ -110,28 +130,28 @@ impl From<User> for EncodablePublicUser {
 			id: id.clone(),
 			username,
 			avatar: avatar_url(&id, avatar),
-			banner,
+			banner: banner_url(banner, storage),
 			bio,
 		}
 	}
 }
 
-fn avatar_url(user_id: &str, hash: Option<String>) -> Option<String> {
-	let avatar = hash.unwrap_or("0".to_string());
-	let animated = avatar.starts_with("a_");
-	let format = if animated { "gif" } else { "png" };
+/// The CDN URL of the avatar of a user or a bot, or of their default avatar
+/// if they have none.
+fn avatar_url(user_id: &str, hash: Option<String>) -> String {
+	CdnUrl::user_avatar(user_id, hash.as_deref()).to_string()
+}
 
-	Some(format!(
-		"https://cdn.discordapp.com/avatars/{}/{}.{}",
-		user_id, avatar, format
-	))
+/// The URL of an uploaded banner, from the key of its stored file.
+fn banner_url(key: Option<String>, storage: &Storage) -> Option<String> {
+	key.map(|key| storage.url(&key))
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EncodableBotOwner {
 	pub id: String,
 	pub username: String,
-	pub avatar: Option<String>,
+	pub avatar: String,
 	pub url: String,
 }
 
@@ -166,17 +186,138 @@ pub struct EncodableBotWithDescription<T> {
 	#[serde(flatten)]
 	pub inner: T,
 	pub description: String,
+	/// The description rendered to sanitized HTML.
+	pub description_html: String,
+	/// The language of the translation the descriptions come from, `None`
+	/// for the default descriptions.
+	pub language: Option<String>,
+	/// The invite link of the bot, with its scopes and permissions decoded.
+	pub invite: Option<EncodableBotInvite>,
+}
+
+impl EncodableBotWithDescription<EncodableBot> {
+	/// Replaces the descriptions with their translation.
+	pub fn translate(mut self, translation: BotTranslation) -> Self {
+		self.inner.short_description = translation.short_description;
+		self.description = translation.description;
+		self.description_html = translation.description_html;
+		self.language = Some(translation.language);
+		self
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBotTranslation {
+	pub language: String,
+	pub short_description: String,
+	pub description: String,
+	pub description_html: String,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+	#[serde(with = "rfc3339")]
+	pub updated_at: NaiveDateTime,
+}
+
+impl From<BotTranslation> for EncodableBotTranslation {
+	fn from(translation: BotTranslation) -> Self {
+		let BotTranslation {
+			language,
+			short_description,
+			description,
+			description_html,
+			created_at,
+			updated_at,
+			..
+		} = translation;
+
+		EncodableBotTranslation {
+			language,
+			short_description,
+			description,
+			description_html,
+			created_at,
+			updated_at,
+		}
+	}
+}
+
+/// An uploaded image, with the URLs of its variants for `srcset`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableImage {
+	pub id: i32,
+	/// URL of the largest variant
+	pub url: String,
+	pub width: i32,
+	pub height: i32,
+	pub variants: Vec<EncodableImageVariant>,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableImageVariant {
+	pub width: i32,
+	pub url: String,
+}
+
+impl EncodableImage {
+	pub fn new(image: &Image, storage: &Storage) -> Self {
+		let variants = image
+			.variants
+			.iter()
+			.map(|width| EncodableImageVariant {
+				width: *width,
+				url: storage.url(&image.variant_key(*width)),
+			})
+			.collect();
+
+		EncodableImage {
+			id: image.id,
+			url: storage.url(&image.variant_key(image.width)),
+			width: image.width,
+			height: image.height,
+			variants,
+			created_at: image.created_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBotInvite {
+	pub url: String,
+	pub scopes: Vec<String>,
+	pub permissions: Vec<String>,
+	/// The permission bitfield, as a string since it doesn't fit in a JS number.
+	pub permissions_bitfield: String,
+}
+
+impl EncodableBotInvite {
+	pub fn new(url: String, invite: BotInvite) -> Self {
+		EncodableBotInvite {
+			url,
+			permissions: invite
+				.permissions
+				.names()
+				.into_iter()
+				.map(Into::into)
+				.collect(),
+			permissions_bitfield: invite.permissions.to_string(),
+			scopes: invite.scopes,
+		}
+	}
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EncodableBot {
 	pub id: String,
 	pub name: String,
-	pub avatar: Option<String>,
+	pub avatar: String,
 	pub short_description: String,
-	pub supported_languages: Vec<Option<BotLanguages>>,
+	pub supported_languages: Vec<String>,
 	pub categories: Option<Vec<String>>,
 	pub guild_count: i32,
+	pub rating_average: f32,
+	pub rating_count: i32,
 	pub status: String,
 	#[serde(with = "rfc3339")]
 	pub updated_at: NaiveDateTime,
@@ -195,15 +336,25 @@ impl EncodableBot {
 			short_description,
 			supported_languages,
 			guild_count,
+			rating_average,
+			rating_count,
 			status,
 			avatar,
+			invite_link,
+			description_html,
 			..
 		} = bot;
 
+		let invite = invite_link.and_then(|url| {
+			let invite = BotInvite::parse(&url)?;
+			Some(EncodableBotInvite::new(url, invite))
+		});
+
 		let category_ids = categories.map(|cats| cats.iter().map(|cat| cat.slug.clone()).collect());
 
 		EncodableBotWithDescription::<EncodableBot> {
 			inner: EncodableBot {
+				avatar: avatar_url(&id, avatar),
 				id,
 				name,
 				updated_at,
@@ -212,10 +363,14 @@ impl EncodableBot {
 				short_description,
 				supported_languages,
 				guild_count,
+				rating_average,
+				rating_count,
 				status: status.into(),
-				avatar,
 			},
 			description,
+			description_html,
+			language: None,
+			invite,
 		}
 	}
 
@@ -232,12 +387,15 @@ impl EncodableBot {
 			short_description,
 			supported_languages,
 			guild_count,
+			rating_average,
+			rating_count,
 			status,
 			avatar,
 			..
 		} = bot;
 
 		EncodableBot {
+			avatar: avatar_url(&id, avatar),
 			id,
 			name,
 			updated_at,
@@ -246,8 +404,9 @@ impl EncodableBot {
 			short_description,
 			supported_languages,
 			guild_count,
+			rating_average,
+			rating_count,
 			status: status.into(),
-			avatar,
 		}
 	}
 }
@@ -300,3 +459,425 @@ impl From<BotVote> for EncodableBotVote {
 		}
 	}
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBotRating {
+	pub id: i32,
+	pub bot_id: String,
+	pub rating: i16,
+	pub body: String,
+	pub user: EncodablePublicUser,
+	pub hidden: bool,
+	pub reply: Option<EncodableBotRatingReply>,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+	#[serde(with = "rfc3339")]
+	pub updated_at: NaiveDateTime,
+}
+
+impl EncodableBotRating {
+	pub fn from(
+		rating: BotRating,
+		user: User,
+		reply: Option<BotRatingReply>,
+		storage: &Storage,
+	) -> Self {
+		let BotRating {
+			id,
+			bot_id,
+			rating,
+			body,
+			created_at,
+			updated_at,
+			hidden,
+			..
+		} = rating;
+
+		EncodableBotRating {
+			id,
+			bot_id,
+			rating,
+			body,
+			user: EncodablePublicUser::from(user, storage),
+			hidden,
+			reply: reply.map(Into::into),
+			created_at,
+			updated_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBotRatingReply {
+	pub user_id: String,
+	pub body: String,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+	#[serde(with = "rfc3339")]
+	pub updated_at: NaiveDateTime,
+}
+
+impl From<BotRatingReply> for EncodableBotRatingReply {
+	fn from(reply: BotRatingReply) -> Self {
+		let BotRatingReply {
+			user_id,
+			body,
+			created_at,
+			updated_at,
+			..
+		} = reply;
+
+		EncodableBotRatingReply {
+			user_id,
+			body,
+			created_at,
+			updated_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBotRatingFlag {
+	pub rating_id: i32,
+	pub user_id: String,
+	pub reason: String,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+	#[serde(with = "rfc3339::option")]
+	pub resolved_at: Option<NaiveDateTime>,
+}
+
+impl From<BotRatingFlag> for EncodableBotRatingFlag {
+	fn from(flag: BotRatingFlag) -> Self {
+		let BotRatingFlag {
+			rating_id,
+			user_id,
+			reason,
+			created_at,
+			resolved_at,
+		} = flag;
+
+		EncodableBotRatingFlag {
+			rating_id,
+			user_id,
+			reason,
+			created_at,
+			resolved_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBotRatingAction {
+	pub id: i32,
+	pub rating_id: Option<i32>,
+	pub actor: Option<EncodablePublicUser>,
+	pub action: String,
+	pub reason: String,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+}
+
+impl EncodableBotRatingAction {
+	pub fn from(action: BotRatingAction, actor: Option<User>, storage: &Storage) -> Self {
+		let BotRatingAction {
+			id,
+			rating_id,
+			action,
+			reason,
+			created_at,
+			..
+		} = action;
+
+		EncodableBotRatingAction {
+			id,
+			rating_id,
+			actor: actor.map(|actor| EncodablePublicUser::from(actor, storage)),
+			action: action.into(),
+			reason,
+			created_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBotReport {
+	pub id: i32,
+	pub bot_id: String,
+	pub user_id: String,
+	pub reason: String,
+	pub body: String,
+	pub status: String,
+	pub handled_by: Option<String>,
+	pub resolution: Option<String>,
+	pub bot_status: Option<String>,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+	#[serde(with = "rfc3339")]
+	pub updated_at: NaiveDateTime,
+}
+
+impl From<BotReport> for EncodableBotReport {
+	fn from(report: BotReport) -> Self {
+		let BotReport {
+			id,
+			bot_id,
+			user_id,
+			reason,
+			body,
+			status,
+			handled_by,
+			resolution,
+			bot_status,
+			created_at,
+			updated_at,
+		} = report;
+
+		EncodableBotReport {
+			id,
+			bot_id,
+			user_id,
+			reason: reason.into(),
+			body,
+			status: status.into(),
+			handled_by,
+			resolution,
+			bot_status: bot_status.map(Into::into),
+			created_at,
+			updated_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBotDenial {
+	pub id: i32,
+	pub bot_id: String,
+	pub reviewer_id: Option<String>,
+	pub reason: String,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+}
+
+impl From<BotDenial> for EncodableBotDenial {
+	fn from(denial: BotDenial) -> Self {
+		let BotDenial {
+			id,
+			bot_id,
+			reviewer_id,
+			reason,
+			created_at,
+		} = denial;
+
+		EncodableBotDenial {
+			id,
+			bot_id,
+			reviewer_id,
+			reason,
+			created_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBotResubmission {
+	pub id: i32,
+	pub bot_id: String,
+	pub user_id: String,
+	pub appeal: Option<String>,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+}
+
+impl From<BotResubmission> for EncodableBotResubmission {
+	fn from(resubmission: BotResubmission) -> Self {
+		let BotResubmission {
+			id,
+			bot_id,
+			user_id,
+			appeal,
+			created_at,
+		} = resubmission;
+
+		EncodableBotResubmission {
+			id,
+			bot_id,
+			user_id,
+			appeal,
+			created_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableCertificationRequirement {
+	pub name: String,
+	pub required: i64,
+	pub current: i64,
+	pub met: bool,
+}
+
+impl From<CertificationRequirement> for EncodableCertificationRequirement {
+	fn from(requirement: CertificationRequirement) -> Self {
+		EncodableCertificationRequirement {
+			met: requirement.is_met(),
+			name: requirement.name.to_string(),
+			required: requirement.required,
+			current: requirement.current,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableCertificationEligibility {
+	pub eligible: bool,
+	pub requirements: Vec<EncodableCertificationRequirement>,
+}
+
+impl From<CertificationEligibility> for EncodableCertificationEligibility {
+	fn from(eligibility: CertificationEligibility) -> Self {
+		EncodableCertificationEligibility {
+			eligible: eligibility.is_eligible(),
+			requirements: eligibility
+				.requirements
+				.into_iter()
+				.map(Into::into)
+				.collect(),
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableCertificationApplication {
+	pub id: i32,
+	pub bot_id: String,
+	pub user_id: String,
+	pub message: Option<String>,
+	pub status: String,
+	pub reviewer_id: Option<String>,
+	pub reason: Option<String>,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+	#[serde(with = "rfc3339")]
+	pub updated_at: NaiveDateTime,
+}
+
+impl From<CertificationApplication> for EncodableCertificationApplication {
+	fn from(application: CertificationApplication) -> Self {
+		let CertificationApplication {
+			id,
+			bot_id,
+			user_id,
+			message,
+			status,
+			reviewer_id,
+			reason,
+			created_at,
+			updated_at,
+		} = application;
+
+		EncodableCertificationApplication {
+			id,
+			bot_id,
+			user_id,
+			message,
+			status: status.into(),
+			reviewer_id,
+			reason,
+			created_at,
+			updated_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableAuditEntry {
+	pub id: i64,
+	pub actor_id: String,
+	/// `cookie` or `token`
+	pub auth: String,
+	pub token_id: Option<i32>,
+	pub action: String,
+	pub bot_id: Option<String>,
+	pub target: String,
+	pub before: Option<serde_json::Value>,
+	pub after: Option<serde_json::Value>,
+	pub ip: Option<String>,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+}
+
+impl EncodableAuditEntry {
+	/// Encodes the entry, leaving out the IP address unless `include_ip` is set.
+	pub fn from(entry: AuditEntry, include_ip: bool) -> Self {
+		let AuditEntry {
+			id,
+			actor_id,
+			token_id,
+			action,
+			bot_id,
+			target,
+			before,
+			after,
+			ip,
+			created_at,
+		} = entry;
+
+		let auth = if token_id.is_some() {
+			"token"
+		} else {
+			"cookie"
+		};
+
+		EncodableAuditEntry {
+			id,
+			actor_id,
+			auth: auth.to_string(),
+			token_id,
+			action: action.into(),
+			bot_id,
+			target,
+			before,
+			after,
+			ip: ip.filter(|_| include_ip).map(|ip| ip.ip().to_string()),
+			created_at,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EncodableBlockedTraffic {
+	pub id: i32,
+	pub ip_range: Option<String>,
+	pub user_agent: Option<String>,
+	pub reason: String,
+	pub created_by: Option<String>,
+	#[serde(with = "rfc3339")]
+	pub created_at: NaiveDateTime,
+	#[serde(with = "rfc3339::option")]
+	pub expires_at: Option<NaiveDateTime>,
+}
+
+impl From<BlockedTraffic> for EncodableBlockedTraffic {
+	fn from(entry: BlockedTraffic) -> Self {
+		let BlockedTraffic {
+			id,
+			ip_range,
+			user_agent,
+			reason,
+			created_by,
+			created_at,
+			expires_at,
+		} = entry;
+
+		EncodableBlockedTraffic {
+			id,
+			ip_range: ip_range.map(|ip_range| ip_range.to_string()),
+			user_agent,
+			reason,
+			created_by,
+			created_at,
+			expires_at,
+		}
+	}
+}